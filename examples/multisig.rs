@@ -53,7 +53,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("  This transaction requires 2 out of 3 signatures");
     println!("  Signers: Alice, Bob, and Charlie");
 
-    let _signers = vec![
+    let _signers = [
         ("Alice", "alice_secret_key_here", "alice_public_key_here"),
         ("Bob", "bob_secret_key_here", "bob_public_key_here"),
         ("Charlie", "charlie_secret_key_here", "charlie_public_key_here"),
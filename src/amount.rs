@@ -0,0 +1,389 @@
+use crate::error::XrplError;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
+
+/// An amount as it appears in transactions and metadata: XRP as a string
+/// of drops, issued currencies as a `{currency, issuer, value}` object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CurrencyAmount {
+    Xrp(String),
+    Issued(IssuedAmount),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuedAmount {
+    pub currency: String,
+    pub issuer: String,
+    pub value: String,
+}
+
+impl CurrencyAmount {
+    pub fn xrp_drops(drops: u64) -> Self {
+        CurrencyAmount::Xrp(drops.to_string())
+    }
+
+    pub fn issued(currency: &str, issuer: &str, value: &str) -> Self {
+        CurrencyAmount::Issued(IssuedAmount {
+            currency: currency.to_string(),
+            issuer: issuer.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Parse an amount from transaction or metadata JSON
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    pub fn is_xrp(&self) -> bool {
+        matches!(self, CurrencyAmount::Xrp(_))
+    }
+
+    /// "XRP" or the issued currency code
+    pub fn currency(&self) -> &str {
+        match self {
+            CurrencyAmount::Xrp(_) => "XRP",
+            CurrencyAmount::Issued(amount) => &amount.currency,
+        }
+    }
+
+    pub fn issuer(&self) -> Option<&str> {
+        match self {
+            CurrencyAmount::Xrp(_) => None,
+            CurrencyAmount::Issued(amount) => Some(&amount.issuer),
+        }
+    }
+
+    /// Drops for XRP, the decimal value for issued currencies
+    pub fn value(&self) -> &str {
+        match self {
+            CurrencyAmount::Xrp(drops) => drops,
+            CurrencyAmount::Issued(amount) => &amount.value,
+        }
+    }
+
+    pub fn drops(&self) -> Option<u64> {
+        match self {
+            CurrencyAmount::Xrp(drops) => drops.parse().ok(),
+            CurrencyAmount::Issued(_) => None,
+        }
+    }
+}
+
+/// The value of an issued currency amount in rippled's representation: a
+/// 16 digit mantissa and a decimal exponent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IouValue {
+    mantissa: i64,
+    exponent: i32,
+}
+
+impl IouValue {
+    const MIN_MANTISSA: i64 = 1_000_000_000_000_000;
+    const MAX_MANTISSA: i64 = 9_999_999_999_999_999;
+    const MIN_EXPONENT: i32 = -96;
+    const MAX_EXPONENT: i32 = 80;
+
+    pub const ZERO: IouValue = IouValue {
+        mantissa: 0,
+        exponent: 0,
+    };
+
+    /// Build `mantissa * 10^exponent`, normalized. Precision beyond 16
+    /// significant digits is truncated and values too small to represent
+    /// become zero.
+    pub fn new(mantissa: i64, exponent: i32) -> Result<Self, XrplError> {
+        Self::normalize(mantissa as i128, exponent)
+    }
+
+    fn normalize(mantissa: i128, mut exponent: i32) -> Result<Self, XrplError> {
+        if mantissa == 0 {
+            return Ok(Self::ZERO);
+        }
+
+        let negative = mantissa < 0;
+        let mut mantissa = mantissa.unsigned_abs();
+
+        while mantissa < Self::MIN_MANTISSA as u128 {
+            mantissa *= 10;
+            exponent -= 1;
+        }
+
+        while mantissa > Self::MAX_MANTISSA as u128 {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        if exponent < Self::MIN_EXPONENT {
+            return Ok(Self::ZERO);
+        }
+
+        if exponent > Self::MAX_EXPONENT {
+            return Err(XrplError::InvalidAmount("Issued amount out of range".to_string()));
+        }
+
+        let mantissa = mantissa as i64;
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            exponent,
+        })
+    }
+
+    pub fn mantissa(&self) -> i64 {
+        self.mantissa
+    }
+
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Sum of two values, truncated to 16 significant digits
+    pub fn checked_add(self, other: IouValue) -> Result<IouValue, XrplError> {
+        if self.is_zero() {
+            return Ok(other);
+        }
+        if other.is_zero() {
+            return Ok(self);
+        }
+
+        let (high, low) = if self.exponent >= other.exponent {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        // Beyond 20 digits apart the smaller value is lost to truncation
+        let shift = (high.exponent - low.exponent) as u32;
+        if shift > 20 {
+            return Ok(high);
+        }
+
+        let mantissa = high.mantissa as i128 * 10i128.pow(shift) + low.mantissa as i128;
+        Self::normalize(mantissa, low.exponent)
+    }
+
+    pub fn checked_sub(self, other: IouValue) -> Result<IouValue, XrplError> {
+        self.checked_add(-other)
+    }
+}
+
+impl Neg for IouValue {
+    type Output = IouValue;
+
+    fn neg(self) -> IouValue {
+        IouValue {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl Ord for IouValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |value: &IouValue| value.mantissa.signum();
+
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+
+        // Normalized mantissas have the same number of digits, so the
+        // exponent decides first
+        let magnitude = (self.exponent, self.mantissa.abs()).cmp(&(other.exponent, other.mantissa.abs()));
+        if self.is_negative() {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl PartialOrd for IouValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for IouValue {
+    /// Formats like rippled: plain decimals for exponents in -25..=-5 (and
+    /// 0), scientific notation otherwise
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa == 0 {
+            return write!(f, "0");
+        }
+
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let mut mantissa = self.mantissa.unsigned_abs();
+        let mut exponent = self.exponent;
+
+        if exponent != 0 && !(-25..=-5).contains(&exponent) {
+            while mantissa.is_multiple_of(10) {
+                mantissa /= 10;
+                exponent += 1;
+            }
+            return write!(f, "{}{}e{}", sign, mantissa, exponent);
+        }
+
+        let digits = mantissa.to_string();
+        if exponent == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let point = digits.len() as i32 + exponent;
+        let (integer, fraction) = if point > 0 {
+            let (integer, fraction) = digits.split_at(point as usize);
+            (integer.to_string(), fraction.to_string())
+        } else {
+            ("0".to_string(), format!("{}{}", "0".repeat((-point) as usize), digits))
+        };
+
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}{}", sign, integer)
+        } else {
+            write!(f, "{}{}.{}", sign, integer, fraction)
+        }
+    }
+}
+
+impl FromStr for IouValue {
+    type Err = XrplError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || XrplError::InvalidAmount(format!("Invalid issued amount: {}", s));
+
+        let (number, exponent) = match s.find(['e', 'E']) {
+            Some(position) => {
+                let exponent: i32 = s[position + 1..].parse().map_err(|_| invalid())?;
+                (&s[..position], exponent)
+            }
+            None => (s, 0),
+        };
+
+        let (negative, number) = match number.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (number, ""),
+        };
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let digits = format!("{}{}", integer, fraction);
+        let digits = digits.trim_start_matches('0');
+        let mut exponent = exponent - fraction.len() as i32;
+
+        // Keep at most 19 significant digits so the mantissa fits; the rest
+        // is truncated during normalization anyway
+        let kept = &digits[..digits.len().min(19)];
+        exponent += (digits.len() - kept.len()) as i32;
+
+        let mantissa: i128 = if kept.is_empty() {
+            0
+        } else {
+            kept.parse().map_err(|_| invalid())?
+        };
+
+        Self::normalize(if negative { -mantissa } else { mantissa }, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_amounts() {
+        let xrp = CurrencyAmount::from_json(&json!("1000000")).unwrap();
+        assert!(xrp.is_xrp());
+        assert_eq!(xrp.drops(), Some(1000000));
+        assert_eq!(xrp.currency(), "XRP");
+
+        let usd = CurrencyAmount::from_json(&json!({
+            "currency": "USD",
+            "issuer": "rIssuer123456789012345678901234",
+            "value": "12.5"
+        }))
+        .unwrap();
+        assert_eq!(usd, CurrencyAmount::issued("USD", "rIssuer123456789012345678901234", "12.5"));
+        assert_eq!(usd.issuer(), Some("rIssuer123456789012345678901234"));
+        assert_eq!(usd.value(), "12.5");
+    }
+
+    #[test]
+    fn test_iou_value_round_trip() {
+        for text in ["1", "100.5", "0.001", "-42.25", "1234567890123456"] {
+            let value: IouValue = text.parse().unwrap();
+            assert_eq!(value.to_string(), text);
+        }
+
+        assert_eq!("0.00000000000000000001".parse::<IouValue>().unwrap().to_string(), "1e-20");
+        assert_eq!("1e20".parse::<IouValue>().unwrap().to_string(), "1e20");
+        assert_eq!("1.5e-30".parse::<IouValue>().unwrap().to_string(), "15e-31");
+        assert_eq!("0".parse::<IouValue>().unwrap(), IouValue::ZERO);
+        assert!("abc".parse::<IouValue>().is_err());
+        assert!("1..2".parse::<IouValue>().is_err());
+    }
+
+    #[test]
+    fn test_iou_value_normalization() {
+        let value = IouValue::new(5, 0).unwrap();
+        assert_eq!(value.mantissa(), 5_000_000_000_000_000);
+        assert_eq!(value.exponent(), -15);
+        assert_eq!(value.to_string(), "5");
+        assert!(IouValue::new(1, 100).is_err());
+    }
+
+    #[test]
+    fn test_iou_value_arithmetic() {
+        let value = |text: &str| text.parse::<IouValue>().unwrap();
+
+        assert_eq!(value("1.5").checked_add(value("2.25")).unwrap(), value("3.75"));
+        assert_eq!(value("1").checked_sub(value("3")).unwrap(), value("-2"));
+        assert_eq!(value("0.1").checked_sub(value("0.1")).unwrap(), IouValue::ZERO);
+        assert_eq!(value("1e30").checked_add(value("1")).unwrap(), value("1e30"));
+        assert_eq!(-value("4"), value("-4"));
+    }
+
+    #[test]
+    fn test_iou_value_ordering() {
+        let value = |text: &str| text.parse::<IouValue>().unwrap();
+
+        assert!(value("10") > value("9.99"));
+        assert!(value("-10") < value("-9.99"));
+        assert!(value("-1") < IouValue::ZERO);
+        assert!(value("0.001") > IouValue::ZERO);
+        assert_eq!(value("5").max(value("5.0")), value("5"));
+    }
+
+    #[test]
+    fn test_serialize_amounts() {
+        assert_eq!(serde_json::to_value(CurrencyAmount::xrp_drops(12)).unwrap(), json!("12"));
+        assert_eq!(
+            serde_json::to_value(CurrencyAmount::issued("EUR", "rIssuer", "1")).unwrap(),
+            json!({ "currency": "EUR", "issuer": "rIssuer", "value": "1" })
+        );
+    }
+}
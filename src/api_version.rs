@@ -0,0 +1,246 @@
+//! rippled API versions and the differences between their responses.
+//!
+//! The client sends its `ApiVersion` with every request and brings the
+//! `result` of both versions into the API v2 shape before parsing, so typed
+//! models do not depend on the version in use.
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// The rippled API version requests are made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiVersion {
+    /// The version rippled assumes when none is given
+    #[default]
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub fn number(self) -> u32 {
+        match self {
+            ApiVersion::V1 => 1,
+            ApiVersion::V2 => 2,
+        }
+    }
+}
+
+impl Serialize for ApiVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.number())
+    }
+}
+
+/// Fields describing a transaction's place in the ledger rather than the
+/// transaction itself. API v1 mixes them into the transaction fields.
+const TX_RESPONSE_FIELDS: &[&str] = &[
+    "close_time_iso",
+    "ctid",
+    "date",
+    "hash",
+    "inLedger",
+    "ledger_hash",
+    "ledger_index",
+    "meta",
+    "metaData",
+    "status",
+    "validated",
+    "warnings",
+];
+
+/// Add `api_version` to the params of a request unless it names one already
+pub(crate) fn attach(request: &Value, version: ApiVersion) -> Value {
+    let mut request = request.clone();
+
+    if let Some(params) = request["params"][0].as_object_mut() {
+        params
+            .entry("api_version")
+            .or_insert_with(|| Value::from(version.number()));
+    }
+
+    request
+}
+
+/// Bring the `result` of a method into the API v2 shape
+pub(crate) fn normalize(method: &str, result: &mut Value) {
+    if result.get("error").is_some() {
+        return;
+    }
+
+    match method {
+        "tx" | "transaction_entry" => normalize_transaction(result),
+        "account_tx" => {
+            if let Some(transactions) = result["transactions"].as_array_mut() {
+                transactions.iter_mut().for_each(normalize_transaction);
+            }
+        }
+        "ledger" => {
+            let ledger = &mut result["ledger"];
+            if let Some(index) = ledger["ledger_index"].as_str().and_then(|index| index.parse::<u64>().ok()) {
+                ledger["ledger_index"] = Value::from(index);
+            }
+            if let Some(transactions) = ledger["transactions"].as_array_mut() {
+                transactions
+                    .iter_mut()
+                    .filter(|tx| tx.is_object())
+                    .for_each(normalize_transaction);
+            }
+        }
+        "account_info" => {
+            if let Some(signer_lists) = result["account_data"]
+                .as_object_mut()
+                .and_then(|account_data| account_data.remove("signer_lists"))
+            {
+                result["signer_lists"] = signer_lists;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Bring a single transaction into the API v2 shape: the transaction in
+/// `tx_json` (or `tx_blob`), with `hash`, `ledger_index`, `date` and `meta`
+/// (or `meta_blob`) next to it. Accepts the v1 shapes of `tx`,
+/// `transaction_entry`, `account_tx` and expanded `ledger` transactions.
+pub(crate) fn normalize_transaction(entry: &mut Value) {
+    let Some(fields) = entry.as_object_mut() else {
+        return;
+    };
+
+    if let Some(meta) = fields.remove("metaData").or_else(|| fields.remove("metadata")) {
+        fields.entry("meta").or_insert(meta);
+    }
+
+    if let Some(Value::String(_)) = fields.get("tx") {
+        let blob = fields.remove("tx").unwrap();
+        fields.insert("tx_blob".to_string(), blob);
+    }
+    if fields.contains_key("tx_blob") {
+        if let Some(Value::String(_)) = fields.get("meta") {
+            let blob = fields.remove("meta").unwrap();
+            fields.insert("meta_blob".to_string(), blob);
+        }
+        return;
+    }
+
+    let mut tx_json = match fields.remove("tx_json").or_else(|| fields.remove("tx")) {
+        Some(Value::Object(tx_json)) => tx_json,
+        _ => {
+            if !fields.contains_key("TransactionType") {
+                return;
+            }
+            let mut tx_json = Map::new();
+            let keys: Vec<String> = fields
+                .keys()
+                .filter(|key| !TX_RESPONSE_FIELDS.contains(&key.as_str()))
+                .cloned()
+                .collect();
+            for key in keys {
+                if let Some(value) = fields.remove(&key) {
+                    tx_json.insert(key, value);
+                }
+            }
+            tx_json
+        }
+    };
+
+    for key in TX_RESPONSE_FIELDS {
+        if let Some(value) = tx_json.remove(*key) {
+            fields.entry(*key).or_insert(value);
+        }
+    }
+    fields.remove("inLedger");
+
+    if tx_json.get("TransactionType").and_then(Value::as_str) == Some("Payment") {
+        match (tx_json.get("Amount").cloned(), tx_json.get("DeliverMax").cloned()) {
+            (Some(amount), None) => {
+                tx_json.insert("DeliverMax".to_string(), amount);
+            }
+            (None, Some(deliver_max)) => {
+                tx_json.insert("Amount".to_string(), deliver_max);
+            }
+            _ => {}
+        }
+    }
+
+    fields.insert("tx_json".to_string(), Value::Object(tx_json));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_attach() {
+        let request = json!({ "method": "tx", "params": [{ "transaction": "ABC" }] });
+        assert_eq!(attach(&request, ApiVersion::V2)["params"][0]["api_version"], 2);
+
+        let explicit = json!({ "method": "tx", "params": [{ "api_version": 1 }] });
+        assert_eq!(attach(&explicit, ApiVersion::V2)["params"][0]["api_version"], 1);
+    }
+
+    #[test]
+    fn test_v1_and_v2_tx_agree() {
+        let mut v1 = json!({
+            "hash": "ABC",
+            "ledger_index": 5,
+            "date": 100,
+            "inLedger": 5,
+            "validated": true,
+            "TransactionType": "Payment",
+            "Account": "rA",
+            "Amount": "25",
+            "DeliverMax": "25",
+            "meta": { "TransactionResult": "tesSUCCESS" }
+        });
+        let mut v2 = json!({
+            "hash": "ABC",
+            "ledger_index": 5,
+            "date": 100,
+            "validated": true,
+            "tx_json": { "TransactionType": "Payment", "Account": "rA", "DeliverMax": "25" },
+            "meta": { "TransactionResult": "tesSUCCESS" }
+        });
+
+        normalize("tx", &mut v1);
+        normalize("tx", &mut v2);
+        assert_eq!(v1, v2);
+        assert_eq!(v1["tx_json"]["Amount"], "25");
+    }
+
+    #[test]
+    fn test_v1_account_tx_and_ledger() {
+        let mut account_tx = json!({
+            "transactions": [
+                { "tx": { "TransactionType": "AccountSet", "hash": "H1", "ledger_index": 7, "date": 1 }, "meta": {}, "validated": true },
+                { "tx_blob": "1200", "meta": "2000", "ledger_index": 7 }
+            ]
+        });
+        normalize("account_tx", &mut account_tx);
+        let entry = &account_tx["transactions"][0];
+        assert_eq!(entry["hash"], "H1");
+        assert_eq!(entry["ledger_index"], 7);
+        assert_eq!(entry["tx_json"], json!({ "TransactionType": "AccountSet" }));
+        assert_eq!(account_tx["transactions"][1]["meta_blob"], "2000");
+
+        let mut ledger = json!({
+            "ledger": {
+                "ledger_index": "42",
+                "transactions": [{ "TransactionType": "Payment", "Amount": "1", "hash": "H2", "metaData": {} }]
+            }
+        });
+        normalize("ledger", &mut ledger);
+        assert_eq!(ledger["ledger"]["ledger_index"], 42);
+        assert_eq!(ledger["ledger"]["transactions"][0]["hash"], "H2");
+        assert!(ledger["ledger"]["transactions"][0]["meta"].is_object());
+    }
+
+    #[test]
+    fn test_v1_signer_lists() {
+        let mut info = json!({ "account_data": { "Account": "rA", "signer_lists": [] } });
+        normalize("account_info", &mut info);
+        assert_eq!(info["signer_lists"], json!([]));
+        assert!(info["account_data"].get("signer_lists").is_none());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::requests::AccountInfoRequest;
+use crate::server_info::Reserves;
+use crate::types::*;
+use anyhow::Result;
+
+/// An account's XRP balance split into the reserve it must keep and what it
+/// can spend, all in drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpendableXrp {
+    pub balance: u64,
+    pub owner_count: u32,
+    pub reserves: Reserves,
+}
+
+impl SpendableXrp {
+    /// Reserve held for the account's owned objects
+    pub fn owner_reserve(&self) -> u64 {
+        self.reserves.owner * self.owner_count as u64
+    }
+
+    /// Total reserve: base plus owner reserve
+    pub fn reserve(&self) -> u64 {
+        self.reserves.total(self.owner_count)
+    }
+
+    /// What can be sent or paid in fees without touching the reserve
+    pub fn spendable(&self) -> u64 {
+        self.balance.saturating_sub(self.reserve())
+    }
+
+    /// Fail with `InsufficientFunds` if the account cannot send `drops` and
+    /// pay `fee`. As in rippled, the fee may be paid out of the reserve, so a
+    /// payment needs `drops + max(reserve, fee)` and anything else just the fee.
+    pub fn check(&self, drops: u64, fee: u64) -> Result<()> {
+        let needed = if drops == 0 {
+            fee
+        } else {
+            drops.saturating_add(self.reserve().max(fee))
+        };
+
+        if self.balance < needed {
+            return Err(XrplError::InsufficientFunds(format!(
+                "{} drops plus fee {} needed but balance is {} with reserve {} (base {} + {} objects x {})",
+                drops,
+                fee,
+                self.balance,
+                self.reserve(),
+                self.reserves.base,
+                self.owner_count,
+                self.reserves.owner
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+impl XrplClient {
+    /// XRP an account can spend on the validated ledger, with the reserve
+    /// breakdown. Reserves come from `reserves()`.
+    pub async fn spendable_xrp(&self, address: &str) -> Result<SpendableXrp> {
+        let account = self.request(&AccountInfoRequest::new(address)).await?.account_data;
+        let balance = account
+            .balance
+            .parse()
+            .map_err(|_| XrplError::Deserialization(format!("Invalid balance {}", account.balance)))?;
+
+        Ok(SpendableXrp {
+            balance,
+            owner_count: account.owner_count,
+            reserves: self.reserves().await?,
+        })
+    }
+
+    /// Check that the sender can afford a transaction's fee and, for XRP
+    /// payments, its amount; see `SpendableXrp::check`
+    pub async fn check_spendable(&self, transaction: &Transaction) -> Result<SpendableXrp> {
+        let spendable = self.spendable_xrp(&transaction.account).await?;
+        let fee = transaction
+            .fee
+            .parse()
+            .map_err(|_| XrplError::InvalidTransaction("Invalid fee format".to_string()))?;
+        let drops = if transaction.transaction_type == "Payment" && transaction.currency == "XRP" {
+            transaction
+                .amount
+                .parse()
+                .map_err(|_| XrplError::InvalidAmount(format!("Invalid XRP amount {}", transaction.amount)))?
+        } else {
+            0
+        };
+
+        spendable.check(drops, fee)?;
+        Ok(spendable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{account_info, client};
+    use crate::transport::MockTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    fn server_state() -> serde_json::Value {
+        json!({
+            "state": {
+                "build_version": "2.2.3",
+                "server_state": "full",
+                "validated_ledger": {
+                    "base_fee": 10,
+                    "close_time": 750000000,
+                    "hash": "ABC",
+                    "reserve_base": 1000000,
+                    "reserve_inc": 200000,
+                    "seq": 90000
+                }
+            }
+        })
+    }
+
+    /// 3 XRP held by an account owning five objects
+    fn owner_of_five() -> serde_json::Value {
+        let mut info = account_info(ACCOUNT, 1, 0);
+        info["account_data"]["Balance"] = json!("3000000");
+        info["account_data"]["OwnerCount"] = json!(5);
+        info
+    }
+
+    #[tokio::test]
+    async fn test_spendable_breakdown() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("server_state", server_state());
+        mock.on("account_info", owner_of_five());
+        let spendable = client(&mock).spendable_xrp(ACCOUNT).await.unwrap();
+
+        assert_eq!(spendable.reserves, Reserves { base: 1_000_000, owner: 200_000 });
+        assert_eq!(spendable.owner_reserve(), 1_000_000);
+        assert_eq!(spendable.reserve(), 2_000_000);
+        assert_eq!(spendable.spendable(), 1_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_payment_into_reserve() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("server_state", server_state());
+        mock.on("account_info", owner_of_five());
+        let xrpl = client(&mock);
+        let mut transaction = Transaction {
+            account: ACCOUNT.to_string(),
+            destination: "rReceiver1234567890123456789012".to_string(),
+            amount: "1000000".to_string(),
+            currency: "XRP".to_string(),
+            ..Transaction::default()
+        };
+
+        // The fee is covered by the reserve
+        assert!(xrpl.check_spendable(&transaction).await.is_ok());
+
+        transaction.amount = "1000001".to_string();
+        let error = xrpl.check_spendable(&transaction).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<XrplError>(), Some(XrplError::InsufficientFunds(_))));
+        assert!(error.to_string().contains("reserve 2000000"));
+    }
+
+    #[test]
+    fn test_fee_may_use_the_reserve() {
+        let spendable = SpendableXrp {
+            balance: 100,
+            owner_count: 0,
+            reserves: Reserves { base: 1_000_000, owner: 200_000 },
+        };
+
+        assert!(spendable.check(0, 12).is_ok());
+        assert!(spendable.check(0, 101).is_err());
+        assert!(spendable.check(1, 12).is_err());
+    }
+}
@@ -0,0 +1,742 @@
+//! Decoder for the XRPL canonical binary format, used for `binary: true`
+//! responses (`tx_blob`, `meta`) so they can be turned back into the same
+//! JSON rippled would have returned.
+
+use crate::amount::IouValue;
+use crate::error::XrplError;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+const ALPHABET: &[u8] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+const ACCOUNT_ID_VERSION: u8 = 0;
+
+const TYPE_UINT16: u8 = 1;
+const TYPE_UINT32: u8 = 2;
+const TYPE_UINT64: u8 = 3;
+const TYPE_HASH128: u8 = 4;
+const TYPE_HASH256: u8 = 5;
+const TYPE_AMOUNT: u8 = 6;
+const TYPE_BLOB: u8 = 7;
+const TYPE_ACCOUNT: u8 = 8;
+const TYPE_OBJECT: u8 = 14;
+const TYPE_ARRAY: u8 = 15;
+const TYPE_UINT8: u8 = 16;
+const TYPE_HASH160: u8 = 17;
+const TYPE_PATHSET: u8 = 18;
+const TYPE_VECTOR256: u8 = 19;
+const TYPE_ISSUE: u8 = 24;
+
+const OBJECT_END: (u8, u8) = (TYPE_OBJECT, 1);
+const ARRAY_END: (u8, u8) = (TYPE_ARRAY, 1);
+
+/// Decode a hex encoded transaction, metadata or ledger entry into JSON
+pub fn decode(hex_blob: &str) -> Result<Value> {
+    let bytes = hex::decode(hex_blob).map_err(|e| XrplError::Deserialization(e.to_string()))?;
+    decode_bytes(&bytes)
+}
+
+pub fn decode_bytes(bytes: &[u8]) -> Result<Value> {
+    let mut parser = Parser { bytes, position: 0 };
+    let object = parser.read_object(false)?;
+    Ok(Value::Object(object))
+}
+
+/// Encode a 20 byte account ID as a classic `r...` address
+pub fn encode_account_id(account_id: &[u8]) -> String {
+    let mut payload = Vec::with_capacity(account_id.len() + 5);
+    payload.push(ACCOUNT_ID_VERSION);
+    payload.extend_from_slice(account_id);
+
+    let checksum = Sha256::digest(Sha256::digest(&payload));
+    payload.extend_from_slice(&checksum[..4]);
+
+    encode_base58(&payload)
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut digits: Vec<u8> = Vec::new();
+
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = String::with_capacity(zeros + digits.len());
+    encoded.extend(std::iter::repeat_n(ALPHABET[0] as char, zeros));
+    encoded.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    encoded
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn is_done(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn read(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position + length;
+        if end > self.bytes.len() {
+            return Err(XrplError::Deserialization("Unexpected end of binary data".to_string()).into());
+        }
+
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_uint(&mut self, length: usize) -> Result<u64> {
+        Ok(self.read(length)?.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    fn read_field_header(&mut self) -> Result<(u8, u8)> {
+        let first = self.read_u8()?;
+        let mut type_code = first >> 4;
+        let mut field_code = first & 0x0F;
+
+        if type_code == 0 {
+            type_code = self.read_u8()?;
+        }
+        if field_code == 0 {
+            field_code = self.read_u8()?;
+        }
+
+        Ok((type_code, field_code))
+    }
+
+    fn read_vl_length(&mut self) -> Result<usize> {
+        let b1 = self.read_u8()? as usize;
+        match b1 {
+            0..=192 => Ok(b1),
+            193..=240 => {
+                let b2 = self.read_u8()? as usize;
+                Ok(193 + (b1 - 193) * 256 + b2)
+            }
+            241..=254 => {
+                let b2 = self.read_u8()? as usize;
+                let b3 = self.read_u8()? as usize;
+                Ok(12481 + (b1 - 241) * 65536 + b2 * 256 + b3)
+            }
+            _ => Err(XrplError::Deserialization("Invalid variable length prefix".to_string()).into()),
+        }
+    }
+
+    /// Read fields until the end of the data, or until the object end
+    /// marker when `nested`
+    fn read_object(&mut self, nested: bool) -> Result<Map<String, Value>> {
+        let mut object = Map::new();
+
+        while !self.is_done() {
+            let header = self.read_field_header()?;
+            if header == OBJECT_END {
+                if nested {
+                    return Ok(object);
+                }
+                return Err(XrplError::Deserialization("Unexpected object end marker".to_string()).into());
+            }
+
+            let name = field_name(header);
+            let value = self.read_field_value(header, &name)?;
+            object.insert(name, value);
+        }
+
+        if nested {
+            return Err(XrplError::Deserialization("Unterminated object".to_string()).into());
+        }
+        Ok(object)
+    }
+
+    fn read_array(&mut self) -> Result<Vec<Value>> {
+        let mut array = Vec::new();
+
+        loop {
+            let header = self.read_field_header()?;
+            if header == ARRAY_END {
+                return Ok(array);
+            }
+
+            if header.0 != TYPE_OBJECT {
+                return Err(XrplError::Deserialization("Array elements must be objects".to_string()).into());
+            }
+
+            let name = field_name(header);
+            let inner = self.read_object(true)?;
+            array.push(json!({ name: inner }));
+        }
+    }
+
+    fn read_field_value(&mut self, (type_code, field_code): (u8, u8), name: &str) -> Result<Value> {
+        let value = match type_code {
+            TYPE_UINT8 => {
+                let value = self.read_u8()?;
+                if name == "TransactionResult" {
+                    transaction_result_name(value)
+                } else {
+                    json!(value)
+                }
+            }
+            TYPE_UINT16 => {
+                let value = self.read_uint(2)? as u16;
+                match name {
+                    "TransactionType" => transaction_type_name(value),
+                    "LedgerEntryType" => ledger_entry_type_name(value),
+                    _ => json!(value),
+                }
+            }
+            TYPE_UINT32 => json!(self.read_uint(4)?),
+            TYPE_UINT64 => json!(hex::encode_upper(self.read(8)?)),
+            TYPE_HASH128 => json!(hex::encode_upper(self.read(16)?)),
+            TYPE_HASH160 => json!(hex::encode_upper(self.read(20)?)),
+            TYPE_HASH256 => json!(hex::encode_upper(self.read(32)?)),
+            TYPE_AMOUNT => self.read_amount()?,
+            TYPE_BLOB => {
+                let length = self.read_vl_length()?;
+                json!(hex::encode_upper(self.read(length)?))
+            }
+            TYPE_ACCOUNT => {
+                let length = self.read_vl_length()?;
+                json!(encode_account_id(self.read(length)?))
+            }
+            TYPE_OBJECT => Value::Object(self.read_object(true)?),
+            TYPE_ARRAY => Value::Array(self.read_array()?),
+            TYPE_PATHSET => self.read_path_set()?,
+            TYPE_VECTOR256 => {
+                let length = self.read_vl_length()?;
+                let hashes = self.read(length)?;
+                Value::Array(
+                    hashes
+                        .chunks(32)
+                        .map(|hash| json!(hex::encode_upper(hash)))
+                        .collect(),
+                )
+            }
+            TYPE_ISSUE => self.read_issue()?,
+            _ => {
+                return Err(XrplError::Deserialization(format!(
+                    "Unsupported field type {} (field {})",
+                    type_code, field_code
+                ))
+                .into())
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn read_amount(&mut self) -> Result<Value> {
+        let raw = self.read_uint(8)?;
+        let positive = raw & 0x4000_0000_0000_0000 != 0;
+
+        if raw & 0x8000_0000_0000_0000 == 0 {
+            let drops = raw & 0x3FFF_FFFF_FFFF_FFFF;
+            let sign = if positive || drops == 0 { "" } else { "-" };
+            return Ok(json!(format!("{}{}", sign, drops)));
+        }
+
+        let currency = currency_code(self.read(20)?);
+        let issuer = encode_account_id(self.read(20)?);
+
+        let value = if raw & 0x3FFF_FFFF_FFFF_FFFF == 0 {
+            IouValue::ZERO
+        } else {
+            let exponent = ((raw >> 54) & 0xFF) as i32 - 97;
+            let mantissa = (raw & 0x003F_FFFF_FFFF_FFFF) as i64;
+            IouValue::new(if positive { mantissa } else { -mantissa }, exponent)?
+        };
+
+        Ok(json!({
+            "currency": currency,
+            "issuer": issuer,
+            "value": value.to_string()
+        }))
+    }
+
+    fn read_issue(&mut self) -> Result<Value> {
+        let currency = currency_code(self.read(20)?);
+        if currency == "XRP" {
+            return Ok(json!({ "currency": currency }));
+        }
+
+        let issuer = encode_account_id(self.read(20)?);
+        Ok(json!({ "currency": currency, "issuer": issuer }))
+    }
+
+    fn read_path_set(&mut self) -> Result<Value> {
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+
+        loop {
+            let step_type = self.read_u8()?;
+            match step_type {
+                0x00 | 0xFF => {
+                    paths.push(Value::Array(std::mem::take(&mut path)));
+                    if step_type == 0x00 {
+                        return Ok(Value::Array(paths));
+                    }
+                }
+                _ => {
+                    let mut step = Map::new();
+                    if step_type & 0x01 != 0 {
+                        step.insert("account".to_string(), json!(encode_account_id(self.read(20)?)));
+                    }
+                    if step_type & 0x10 != 0 {
+                        step.insert("currency".to_string(), json!(currency_code(self.read(20)?)));
+                    }
+                    if step_type & 0x20 != 0 {
+                        step.insert("issuer".to_string(), json!(encode_account_id(self.read(20)?)));
+                    }
+                    path.push(Value::Object(step));
+                }
+            }
+        }
+    }
+}
+
+/// A three letter code where the standard format is used, hex otherwise
+fn currency_code(bytes: &[u8]) -> String {
+    if bytes.iter().all(|&b| b == 0) {
+        return "XRP".to_string();
+    }
+
+    let standard = bytes[..12].iter().all(|&b| b == 0) && bytes[15..].iter().all(|&b| b == 0);
+    let code = &bytes[12..15];
+    if standard && code.iter().all(|b| b.is_ascii_graphic()) {
+        return String::from_utf8_lossy(code).to_string();
+    }
+
+    hex::encode_upper(bytes)
+}
+
+fn field_name((type_code, field_code): (u8, u8)) -> String {
+    let name = match (type_code, field_code) {
+        (TYPE_UINT8, 1) => "CloseResolution",
+        (TYPE_UINT8, 2) => "Method",
+        (TYPE_UINT8, 3) => "TransactionResult",
+        (TYPE_UINT8, 16) => "TickSize",
+
+        (TYPE_UINT16, 1) => "LedgerEntryType",
+        (TYPE_UINT16, 2) => "TransactionType",
+        (TYPE_UINT16, 3) => "SignerWeight",
+        (TYPE_UINT16, 4) => "TransferFee",
+        (TYPE_UINT16, 5) => "TradingFee",
+        (TYPE_UINT16, 6) => "DiscountedFee",
+        (TYPE_UINT16, 16) => "Version",
+
+        (TYPE_UINT32, 1) => "NetworkID",
+        (TYPE_UINT32, 2) => "Flags",
+        (TYPE_UINT32, 3) => "SourceTag",
+        (TYPE_UINT32, 4) => "Sequence",
+        (TYPE_UINT32, 5) => "PreviousTxnLgrSeq",
+        (TYPE_UINT32, 6) => "LedgerSequence",
+        (TYPE_UINT32, 7) => "CloseTime",
+        (TYPE_UINT32, 8) => "ParentCloseTime",
+        (TYPE_UINT32, 9) => "SigningTime",
+        (TYPE_UINT32, 10) => "Expiration",
+        (TYPE_UINT32, 11) => "TransferRate",
+        (TYPE_UINT32, 12) => "WalletSize",
+        (TYPE_UINT32, 13) => "OwnerCount",
+        (TYPE_UINT32, 14) => "DestinationTag",
+        (TYPE_UINT32, 15) => "LastUpdateTime",
+        (TYPE_UINT32, 16) => "HighQualityIn",
+        (TYPE_UINT32, 17) => "HighQualityOut",
+        (TYPE_UINT32, 18) => "LowQualityIn",
+        (TYPE_UINT32, 19) => "LowQualityOut",
+        (TYPE_UINT32, 20) => "QualityIn",
+        (TYPE_UINT32, 21) => "QualityOut",
+        (TYPE_UINT32, 22) => "StampEscrow",
+        (TYPE_UINT32, 23) => "BondAmount",
+        (TYPE_UINT32, 24) => "LoadFee",
+        (TYPE_UINT32, 25) => "OfferSequence",
+        (TYPE_UINT32, 26) => "FirstLedgerSequence",
+        (TYPE_UINT32, 27) => "LastLedgerSequence",
+        (TYPE_UINT32, 28) => "TransactionIndex",
+        (TYPE_UINT32, 29) => "OperationLimit",
+        (TYPE_UINT32, 30) => "ReferenceFeeUnits",
+        (TYPE_UINT32, 31) => "ReserveBase",
+        (TYPE_UINT32, 32) => "ReserveIncrement",
+        (TYPE_UINT32, 33) => "SetFlag",
+        (TYPE_UINT32, 34) => "ClearFlag",
+        (TYPE_UINT32, 35) => "SignerQuorum",
+        (TYPE_UINT32, 36) => "CancelAfter",
+        (TYPE_UINT32, 37) => "FinishAfter",
+        (TYPE_UINT32, 38) => "SignerListID",
+        (TYPE_UINT32, 39) => "SettleDelay",
+        (TYPE_UINT32, 40) => "TicketCount",
+        (TYPE_UINT32, 41) => "TicketSequence",
+        (TYPE_UINT32, 42) => "NFTokenTaxon",
+        (TYPE_UINT32, 43) => "MintedNFTokens",
+        (TYPE_UINT32, 44) => "BurnedNFTokens",
+        (TYPE_UINT32, 50) => "FirstNFTokenSequence",
+
+        (TYPE_UINT64, 1) => "IndexNext",
+        (TYPE_UINT64, 2) => "IndexPrevious",
+        (TYPE_UINT64, 3) => "BookNode",
+        (TYPE_UINT64, 4) => "OwnerNode",
+        (TYPE_UINT64, 5) => "BaseFee",
+        (TYPE_UINT64, 6) => "ExchangeRate",
+        (TYPE_UINT64, 7) => "LowNode",
+        (TYPE_UINT64, 8) => "HighNode",
+        (TYPE_UINT64, 9) => "DestinationNode",
+        (TYPE_UINT64, 10) => "Cookie",
+        (TYPE_UINT64, 11) => "ServerVersion",
+        (TYPE_UINT64, 12) => "NFTokenOfferNode",
+
+        (TYPE_HASH128, 1) => "EmailHash",
+
+        (TYPE_HASH160, 1) => "TakerPaysCurrency",
+        (TYPE_HASH160, 2) => "TakerPaysIssuer",
+        (TYPE_HASH160, 3) => "TakerGetsCurrency",
+        (TYPE_HASH160, 4) => "TakerGetsIssuer",
+
+        (TYPE_HASH256, 1) => "LedgerHash",
+        (TYPE_HASH256, 2) => "ParentHash",
+        (TYPE_HASH256, 3) => "TransactionHash",
+        (TYPE_HASH256, 4) => "AccountHash",
+        (TYPE_HASH256, 5) => "PreviousTxnID",
+        (TYPE_HASH256, 6) => "LedgerIndex",
+        (TYPE_HASH256, 7) => "WalletLocator",
+        (TYPE_HASH256, 8) => "RootIndex",
+        (TYPE_HASH256, 9) => "AccountTxnID",
+        (TYPE_HASH256, 10) => "NFTokenID",
+        (TYPE_HASH256, 14) => "AMMID",
+        (TYPE_HASH256, 16) => "BookDirectory",
+        (TYPE_HASH256, 17) => "InvoiceID",
+        (TYPE_HASH256, 18) => "Nickname",
+        (TYPE_HASH256, 19) => "Amendment",
+        (TYPE_HASH256, 21) => "Digest",
+        (TYPE_HASH256, 22) => "Channel",
+        (TYPE_HASH256, 23) => "ConsensusHash",
+        (TYPE_HASH256, 24) => "CheckID",
+        (TYPE_HASH256, 25) => "ValidatedHash",
+        (TYPE_HASH256, 26) => "PreviousPageMin",
+        (TYPE_HASH256, 27) => "NextPageMin",
+        (TYPE_HASH256, 28) => "NFTokenBuyOffer",
+        (TYPE_HASH256, 29) => "NFTokenSellOffer",
+
+        (TYPE_AMOUNT, 1) => "Amount",
+        (TYPE_AMOUNT, 2) => "Balance",
+        (TYPE_AMOUNT, 3) => "LimitAmount",
+        (TYPE_AMOUNT, 4) => "TakerPays",
+        (TYPE_AMOUNT, 5) => "TakerGets",
+        (TYPE_AMOUNT, 6) => "LowLimit",
+        (TYPE_AMOUNT, 7) => "HighLimit",
+        (TYPE_AMOUNT, 8) => "Fee",
+        (TYPE_AMOUNT, 9) => "SendMax",
+        (TYPE_AMOUNT, 10) => "DeliverMin",
+        (TYPE_AMOUNT, 11) => "Amount2",
+        (TYPE_AMOUNT, 12) => "BidMin",
+        (TYPE_AMOUNT, 13) => "BidMax",
+        (TYPE_AMOUNT, 16) => "MinimumOffer",
+        (TYPE_AMOUNT, 17) => "RippleEscrow",
+        (TYPE_AMOUNT, 18) => "DeliveredAmount",
+        (TYPE_AMOUNT, 19) => "NFTokenBrokerFee",
+        (TYPE_AMOUNT, 22) => "BaseFeeDrops",
+        (TYPE_AMOUNT, 23) => "ReserveBaseDrops",
+        (TYPE_AMOUNT, 24) => "ReserveIncrementDrops",
+        (TYPE_AMOUNT, 25) => "LPTokenOut",
+        (TYPE_AMOUNT, 26) => "LPTokenIn",
+        (TYPE_AMOUNT, 27) => "EPrice",
+        (TYPE_AMOUNT, 28) => "Price",
+        (TYPE_AMOUNT, 31) => "LPTokenBalance",
+
+        (TYPE_BLOB, 1) => "PublicKey",
+        (TYPE_BLOB, 2) => "MessageKey",
+        (TYPE_BLOB, 3) => "SigningPubKey",
+        (TYPE_BLOB, 4) => "TxnSignature",
+        (TYPE_BLOB, 5) => "URI",
+        (TYPE_BLOB, 6) => "Signature",
+        (TYPE_BLOB, 7) => "Domain",
+        (TYPE_BLOB, 8) => "FundCode",
+        (TYPE_BLOB, 9) => "RemoveCode",
+        (TYPE_BLOB, 10) => "ExpireCode",
+        (TYPE_BLOB, 11) => "CreateCode",
+        (TYPE_BLOB, 12) => "MemoType",
+        (TYPE_BLOB, 13) => "MemoData",
+        (TYPE_BLOB, 14) => "MemoFormat",
+        (TYPE_BLOB, 16) => "Fulfillment",
+        (TYPE_BLOB, 17) => "Condition",
+        (TYPE_BLOB, 18) => "MasterSignature",
+        (TYPE_BLOB, 19) => "UNLModifyValidator",
+        (TYPE_BLOB, 20) => "ValidatorToDisable",
+        (TYPE_BLOB, 21) => "ValidatorToReEnable",
+
+        (TYPE_ACCOUNT, 1) => "Account",
+        (TYPE_ACCOUNT, 2) => "Owner",
+        (TYPE_ACCOUNT, 3) => "Destination",
+        (TYPE_ACCOUNT, 4) => "Issuer",
+        (TYPE_ACCOUNT, 5) => "Authorize",
+        (TYPE_ACCOUNT, 6) => "Unauthorize",
+        (TYPE_ACCOUNT, 8) => "RegularKey",
+        (TYPE_ACCOUNT, 9) => "NFTokenMinter",
+
+        (TYPE_OBJECT, 2) => "TransactionMetaData",
+        (TYPE_OBJECT, 3) => "CreatedNode",
+        (TYPE_OBJECT, 4) => "DeletedNode",
+        (TYPE_OBJECT, 5) => "ModifiedNode",
+        (TYPE_OBJECT, 6) => "PreviousFields",
+        (TYPE_OBJECT, 7) => "FinalFields",
+        (TYPE_OBJECT, 8) => "NewFields",
+        (TYPE_OBJECT, 9) => "TemplateEntry",
+        (TYPE_OBJECT, 10) => "Memo",
+        (TYPE_OBJECT, 11) => "SignerEntry",
+        (TYPE_OBJECT, 12) => "NFToken",
+        (TYPE_OBJECT, 16) => "Signer",
+        (TYPE_OBJECT, 18) => "Majority",
+        (TYPE_OBJECT, 19) => "DisabledValidator",
+        (TYPE_OBJECT, 26) => "AuctionSlot",
+        (TYPE_OBJECT, 27) => "AuthAccount",
+
+        (TYPE_ARRAY, 3) => "Signers",
+        (TYPE_ARRAY, 4) => "SignerEntries",
+        (TYPE_ARRAY, 5) => "Template",
+        (TYPE_ARRAY, 6) => "Necessary",
+        (TYPE_ARRAY, 7) => "Sufficient",
+        (TYPE_ARRAY, 8) => "AffectedNodes",
+        (TYPE_ARRAY, 9) => "Memos",
+        (TYPE_ARRAY, 10) => "NFTokens",
+        (TYPE_ARRAY, 16) => "Majorities",
+        (TYPE_ARRAY, 17) => "DisabledValidators",
+        (TYPE_ARRAY, 25) => "VoteSlots",
+        (TYPE_ARRAY, 26) => "AuthAccounts",
+
+        (TYPE_PATHSET, 1) => "Paths",
+
+        (TYPE_VECTOR256, 1) => "Indexes",
+        (TYPE_VECTOR256, 2) => "Hashes",
+        (TYPE_VECTOR256, 3) => "Amendments",
+        (TYPE_VECTOR256, 4) => "NFTokenOffers",
+
+        (TYPE_ISSUE, 3) => "Asset",
+        (TYPE_ISSUE, 4) => "Asset2",
+
+        _ => return format!("Field_{}_{}", type_code, field_code),
+    };
+
+    name.to_string()
+}
+
+fn transaction_type_name(code: u16) -> Value {
+    let name = match code {
+        0 => "Payment",
+        1 => "EscrowCreate",
+        2 => "EscrowFinish",
+        3 => "AccountSet",
+        4 => "EscrowCancel",
+        5 => "SetRegularKey",
+        7 => "OfferCreate",
+        8 => "OfferCancel",
+        10 => "TicketCreate",
+        12 => "SignerListSet",
+        13 => "PaymentChannelCreate",
+        14 => "PaymentChannelFund",
+        15 => "PaymentChannelClaim",
+        16 => "CheckCreate",
+        17 => "CheckCash",
+        18 => "CheckCancel",
+        19 => "DepositPreauth",
+        20 => "TrustSet",
+        21 => "AccountDelete",
+        25 => "NFTokenMint",
+        26 => "NFTokenBurn",
+        27 => "NFTokenCreateOffer",
+        28 => "NFTokenCancelOffer",
+        29 => "NFTokenAcceptOffer",
+        30 => "Clawback",
+        35 => "AMMCreate",
+        36 => "AMMDeposit",
+        37 => "AMMWithdraw",
+        38 => "AMMVote",
+        39 => "AMMBid",
+        40 => "AMMDelete",
+        100 => "EnableAmendment",
+        101 => "SetFee",
+        102 => "UNLModify",
+        _ => return json!(code),
+    };
+
+    json!(name)
+}
+
+fn ledger_entry_type_name(code: u16) -> Value {
+    let name = match code {
+        0x0037 => "NFTokenOffer",
+        0x0043 => "Check",
+        0x004E => "NegativeUNL",
+        0x0050 => "NFTokenPage",
+        0x0053 => "SignerList",
+        0x0054 => "Ticket",
+        0x0061 => "AccountRoot",
+        0x0064 => "DirectoryNode",
+        0x0066 => "Amendments",
+        0x0068 => "LedgerHashes",
+        0x006F => "Offer",
+        0x0070 => "DepositPreauth",
+        0x0072 => "RippleState",
+        0x0073 => "FeeSettings",
+        0x0075 => "Escrow",
+        0x0078 => "PayChannel",
+        0x0079 => "AMM",
+        _ => return json!(code),
+    };
+
+    json!(name)
+}
+
+fn transaction_result_name(code: u8) -> Value {
+    let name = match code {
+        0 => "tesSUCCESS",
+        100 => "tecCLAIM",
+        101 => "tecPATH_PARTIAL",
+        102 => "tecUNFUNDED_ADD",
+        103 => "tecUNFUNDED_OFFER",
+        104 => "tecUNFUNDED_PAYMENT",
+        105 => "tecFAILED_PROCESSING",
+        121 => "tecDIR_FULL",
+        122 => "tecINSUF_RESERVE_LINE",
+        123 => "tecINSUF_RESERVE_OFFER",
+        124 => "tecNO_DST",
+        125 => "tecNO_DST_INSUF_XRP",
+        126 => "tecNO_LINE_INSUF_RESERVE",
+        127 => "tecNO_LINE_REDUNDANT",
+        128 => "tecPATH_DRY",
+        129 => "tecUNFUNDED",
+        130 => "tecNO_ALTERNATIVE_KEY",
+        131 => "tecNO_REGULAR_KEY",
+        132 => "tecOWNERS",
+        133 => "tecNO_ISSUER",
+        134 => "tecNO_AUTH",
+        135 => "tecNO_LINE",
+        136 => "tecINSUFF_FEE",
+        137 => "tecFROZEN",
+        138 => "tecNO_TARGET",
+        139 => "tecNO_PERMISSION",
+        140 => "tecNO_ENTRY",
+        141 => "tecINSUFFICIENT_RESERVE",
+        142 => "tecNEED_MASTER_KEY",
+        143 => "tecDST_TAG_NEEDED",
+        144 => "tecINTERNAL",
+        145 => "tecOVERSIZE",
+        146 => "tecCRYPTOCONDITION_ERROR",
+        147 => "tecINVARIANT_FAILED",
+        148 => "tecEXPIRED",
+        149 => "tecDUPLICATE",
+        150 => "tecKILLED",
+        151 => "tecHAS_OBLIGATIONS",
+        152 => "tecTOO_SOON",
+        154 => "tecMAX_SEQUENCE_REACHED",
+        155 => "tecNO_SUITABLE_NFTOKEN_PAGE",
+        156 => "tecNFTOKEN_BUY_SELL_MISMATCH",
+        157 => "tecNFTOKEN_OFFER_TYPE_MISMATCH",
+        158 => "tecCANT_ACCEPT_OWN_NFTOKEN_OFFER",
+        159 => "tecINSUFFICIENT_FUNDS",
+        160 => "tecOBJECT_NOT_FOUND",
+        161 => "tecINSUFFICIENT_PAYMENT",
+        _ => return json!(code),
+    };
+
+    json!(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_account_id() {
+        assert_eq!(encode_account_id(&[0u8; 20]), "rrrrrrrrrrrrrrrrrrrrrhoLvTp");
+
+        let mut one = [0u8; 20];
+        one[19] = 1;
+        assert_eq!(encode_account_id(&one), "rrrrrrrrrrrrrrrrrrrrBZbvji");
+    }
+
+    #[test]
+    fn test_decode_payment() {
+        // TransactionType, Flags, Sequence, Amount (XRP), Fee, Account, Destination
+        let blob = concat!(
+            "120000",
+            "2280000000",
+            "2400000001",
+            "6140000000000F4240",
+            "68400000000000000C",
+            "81140000000000000000000000000000000000000000",
+            "83140000000000000000000000000000000000000001",
+        );
+
+        let tx = decode(blob).unwrap();
+        assert_eq!(tx["TransactionType"], "Payment");
+        assert_eq!(tx["Flags"], 0x8000_0000u32);
+        assert_eq!(tx["Sequence"], 1);
+        assert_eq!(tx["Amount"], "1000000");
+        assert_eq!(tx["Fee"], "12");
+        assert_eq!(tx["Account"], "rrrrrrrrrrrrrrrrrrrrrhoLvTp");
+        assert_eq!(tx["Destination"], "rrrrrrrrrrrrrrrrrrrrBZbvji");
+    }
+
+    #[test]
+    fn test_decode_issued_amount_and_meta() {
+        // 1.5 USD: positive, exponent -15 + 97 = 82, mantissa 1500000000000000
+        let mut amount = 0xC000_0000_0000_0000u64 | (82u64 << 54) | 1_500_000_000_000_000;
+        let usd = format!("{}{}{}", "0".repeat(24), hex::encode_upper("USD"), "0".repeat(10));
+        let issuer = "0".repeat(40);
+        let limit = format!("63{:016X}{}{}", amount, usd, issuer);
+
+        // TransactionIndex, AffectedNodes [ ModifiedNode { LedgerEntryType, FinalFields { LimitAmount } } ], TransactionResult
+        let meta = format!(
+            "{}{}{}{}{}{}{}{}",
+            "201C00000003",
+            "F8",
+            "E5",
+            "110072",
+            "E7",
+            limit,
+            "E1E1F1",
+            "031000"
+        );
+
+        let decoded = decode(&meta).unwrap();
+        assert_eq!(decoded["TransactionIndex"], 3);
+        assert_eq!(decoded["TransactionResult"], "tesSUCCESS");
+
+        let node = &decoded["AffectedNodes"][0]["ModifiedNode"];
+        assert_eq!(node["LedgerEntryType"], "RippleState");
+        assert_eq!(node["FinalFields"]["LimitAmount"]["currency"], "USD");
+        assert_eq!(node["FinalFields"]["LimitAmount"]["value"], "1.5");
+
+        amount &= !0x4000_0000_0000_0000;
+        let negative = decode(&format!("63{:016X}{}{}", amount, usd, issuer)).unwrap();
+        assert_eq!(negative["LimitAmount"]["value"], "-1.5");
+    }
+
+    #[test]
+    fn test_decode_variable_length() {
+        let mut parser = Parser { bytes: &[0xC1, 0x00], position: 0 };
+        assert_eq!(parser.read_vl_length().unwrap(), 193);
+
+        let mut parser = Parser { bytes: &[0xF1, 0x00, 0x00], position: 0 };
+        assert_eq!(parser.read_vl_length().unwrap(), 12481);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        assert!(decode("2400").is_err());
+        assert!(decode("zz").is_err());
+    }
+}
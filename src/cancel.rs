@@ -0,0 +1,307 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::fee::TransactionCost;
+use crate::history::AccountTxRequest;
+use crate::requests::{AccountInfoRequest, LedgerIndex};
+use crate::resubmit::{FeeBumpPolicy, QUEUE_REPLACE_PERCENT};
+use crate::signing::TransactionSigner;
+use crate::types::*;
+use anyhow::Result;
+use futures::StreamExt;
+
+/// Ledgers a cancellation stays valid for, and the longest `cancel_pending`
+/// waits for a transaction only applied in the open ledger
+pub const CANCEL_LEDGER_WINDOW: u32 = 20;
+
+/// Which transaction ended up using the sequence
+#[derive(Debug, Clone, PartialEq)]
+pub enum CancellationOutcome {
+    /// The no-op AccountSet was validated, so the original never can be
+    Cancelled(Box<TransactionMetadata>),
+    /// A different transaction with that sequence was validated first. The
+    /// hash is `None` if `account_tx` does not return it.
+    OriginalValidated { hash: Option<TransactionHash> },
+}
+
+impl XrplClient {
+    /// Cancel a pending transaction by submitting a no-op AccountSet with the
+    /// same Sequence and a higher fee, then wait until one of them is validated.
+    ///
+    /// The fee follows the client's `FeeStrategy` but is raised enough to
+    /// replace the original if it is in the queue, up to `policy.max_fee`.
+    /// While the original is applied in the open ledger but not yet
+    /// validated, nothing is submitted, and if that lasts more than
+    /// `CANCEL_LEDGER_WINDOW` validated ledgers this fails.
+    pub async fn cancel_pending(
+        &self,
+        secret: &str,
+        account: &str,
+        sequence: u32,
+        policy: &FeeBumpPolicy,
+    ) -> Result<CancellationOutcome> {
+        // Validated ledger of the first check that found the original not
+        // yet validated
+        let mut first_check = None;
+        let info = loop {
+            let validated_ledger = self.get_ledger_index().await?;
+            let validated = self.request(&AccountInfoRequest::new(account)).await?;
+            if validated.account_data.sequence > sequence {
+                return Ok(CancellationOutcome::OriginalValidated {
+                    hash: self.find_by_sequence(account, sequence, first_check).await?,
+                });
+            }
+            let first_check = *first_check.get_or_insert(validated_ledger);
+
+            let current = self
+                .request(&AccountInfoRequest {
+                    queue: Some(true),
+                    ledger_index: Some(LedgerIndex::Current),
+                    ..AccountInfoRequest::new(account)
+                })
+                .await?;
+            if current.account_data.sequence <= sequence {
+                break current;
+            }
+
+            // Only provisionally applied in the open ledger: wait until it is
+            // validated or dropped
+            if validated_ledger > first_check + CANCEL_LEDGER_WINDOW {
+                return Err(XrplError::TransactionFailed(format!(
+                    "Sequence {} is still only applied in the open ledger after {} ledgers",
+                    sequence, CANCEL_LEDGER_WINDOW
+                ))
+                .into());
+            }
+            tokio::time::sleep(policy.poll_interval).await;
+        };
+
+        let mut fee = self.estimate_fee(&TransactionCost::reference()).await?;
+        if let Some(queued_fee) = queued_fee(info.queue_data.as_ref(), sequence) {
+            fee = fee.max(queued_fee + (queued_fee * QUEUE_REPLACE_PERCENT).div_ceil(100));
+        }
+        if fee > policy.max_fee {
+            return Err(XrplError::InvalidTransaction(format!(
+                "Cancelling needs a fee of {} drops, more than the maximum of {}",
+                fee, policy.max_fee
+            ))
+            .into());
+        }
+
+        let last_ledger_sequence = self.get_ledger_index().await? + CANCEL_LEDGER_WINDOW;
+        let cancellation = Transaction {
+            transaction_type: "AccountSet".to_string(),
+            account: account.to_string(),
+            fee: fee.to_string(),
+            sequence,
+            last_ledger_sequence: Some(last_ledger_sequence),
+            network_id: self.network().requires_network_id().then_some(self.network().network_id),
+            ..Transaction::default()
+        };
+
+        let signed = TransactionSigner::with_network(self.network().clone()).sign_transaction(secret, &cancellation)?;
+        let hash = TransactionSigner::transaction_hash(&signed.tx_blob)?;
+        let result = self.submit_transaction(&signed).await?;
+        if result.engine_result.starts_with("tem") {
+            return Err(XrplError::TransactionFailed(result.engine_result).into());
+        }
+
+        loop {
+            // Read before looking up the cancellation, so that a cancellation
+            // validated in between is not mistaken for the original
+            let validated_ledger = self.get_ledger_index().await?;
+            let validated = self.request(&AccountInfoRequest::new(account)).await?;
+
+            if let Some(cancellation) = self.validated_transaction(&hash).await? {
+                return Ok(CancellationOutcome::Cancelled(Box::new(cancellation)));
+            }
+
+            if validated.account_data.sequence > sequence {
+                return Ok(CancellationOutcome::OriginalValidated {
+                    hash: self.find_by_sequence(account, sequence, first_check).await?,
+                });
+            }
+
+            if validated_ledger > last_ledger_sequence {
+                return Err(XrplError::TransactionFailed(format!(
+                    "Cancellation of sequence {} expired at ledger {}",
+                    sequence, last_ledger_sequence
+                ))
+                .into());
+            }
+
+            tokio::time::sleep(policy.poll_interval).await;
+        }
+    }
+
+    /// Hash of the account's validated transaction with this sequence, looked
+    /// up from ledger `since`, or the oldest available, to the newest
+    async fn find_by_sequence(&self, account: &str, sequence: u32, since: Option<u32>) -> Result<Option<TransactionHash>> {
+        let request = AccountTxRequest::new(account).ledger_range(since, None);
+        let mut transactions = Box::pin(self.account_tx_stream(request));
+
+        while let Some(transaction) = transactions.next().await {
+            let transaction = transaction?;
+            if transaction.tx["Sequence"].as_u64() == Some(sequence as u64) {
+                return Ok(Some(transaction.hash));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Fee of the queued transaction with this sequence, from `account_info`'s `queue_data`
+fn queued_fee(queue_data: Option<&serde_json::Value>, sequence: u32) -> Option<u64> {
+    queue_data?["transactions"]
+        .as_array()?
+        .iter()
+        .find(|tx| tx["seq"].as_u64() == Some(sequence as u64))
+        .and_then(|tx| tx["fee"].as_str()?.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, queued_account_info, submit_result};
+    use crate::transport::MockTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const SECRET: &str = "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2";
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    fn policy() -> FeeBumpPolicy {
+        FeeBumpPolicy::new(1000).with_poll_interval(Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_queued_fee() {
+        let queue_data = json!({ "transactions": [{ "seq": 7, "fee": "100" }, { "seq": 8, "fee": "40" }] });
+        assert_eq!(queued_fee(Some(&queue_data), 8), Some(40));
+        assert_eq!(queued_fee(Some(&queue_data), 9), None);
+        assert_eq!(queued_fee(None, 8), None);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_validated() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params(
+            "account_info",
+            json!({ "queue": true }),
+            queued_account_info(ACCOUNT, 8, json!({ "transactions": [{ "seq": 8, "fee": "100" }] })),
+        );
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("submit", submit_result("terQUEUED"));
+        // The cancellation itself moved the validated sequence past 8
+        mock.on("account_info", queued_account_info(ACCOUNT, 8, json!(null)))
+            .on("account_info", queued_account_info(ACCOUNT, 9, json!(null)));
+        mock.on("tx", json!({
+            "hash": "CANCEL",
+            "ledger_index": 101,
+            "validated": true,
+            "TransactionType": "AccountSet",
+            "Account": ACCOUNT,
+            "Fee": "125",
+            "Sequence": 8,
+            "meta": { "TransactionIndex": 0, "TransactionResult": "tesSUCCESS", "AffectedNodes": [] }
+        }));
+
+        let outcome = client(&mock).cancel_pending(SECRET, ACCOUNT, 8, &policy()).await.unwrap();
+        assert!(matches!(outcome, CancellationOutcome::Cancelled(ref tx) if tx.tx_json.transaction_type == "AccountSet"));
+        let ledgers: Vec<_> = mock
+            .requests_for("account_info")
+            .iter()
+            .map(|params| params["ledger_index"].clone())
+            .collect();
+        assert_eq!(ledgers[..2], [json!("validated"), json!("current")]);
+    }
+
+    #[tokio::test]
+    async fn test_original_validated() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "queue": true }), queued_account_info(ACCOUNT, 8, json!(null)));
+        mock.on("account_info", queued_account_info(ACCOUNT, 8, json!(null)))
+            .on("account_info", queued_account_info(ACCOUNT, 9, json!(null)));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("submit", submit_result("tefPAST_SEQ"));
+        mock.on_error("tx", "txnNotFound");
+        // The original is on the second page of the account's history
+        mock.on("account_tx", json!({
+            "account": ACCOUNT,
+            "ledger_index_min": 100,
+            "ledger_index_max": 101,
+            "transactions": [{
+                "hash": "OTHER",
+                "ledger_index": 101,
+                "validated": true,
+                "tx_json": { "TransactionType": "TicketCreate", "Account": ACCOUNT, "Sequence": 0, "TicketSequence": 3 },
+                "meta": { "TransactionResult": "tesSUCCESS" }
+            }],
+            "marker": { "ledger": 101, "seq": 0 }
+        }))
+        .on("account_tx", json!({
+            "account": ACCOUNT,
+            "ledger_index_min": 100,
+            "ledger_index_max": 101,
+            "transactions": [{
+                "hash": "ORIGINAL",
+                "ledger_index": 101,
+                "validated": true,
+                "tx_json": { "TransactionType": "Payment", "Account": ACCOUNT, "Sequence": 8 },
+                "meta": { "TransactionResult": "tesSUCCESS" }
+            }]
+        }));
+
+        let outcome = client(&mock).cancel_pending(SECRET, ACCOUNT, 8, &policy()).await.unwrap();
+        assert_eq!(outcome, CancellationOutcome::OriginalValidated { hash: Some("ORIGINAL".to_string()) });
+        assert_eq!(mock.requests_for("submit").len(), 1);
+
+        let pages = mock.requests_for("account_tx");
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0]["ledger_index_min"], 100);
+        assert_eq!(pages[1]["marker"], json!({ "ledger": 101, "seq": 0 }));
+    }
+
+    #[tokio::test]
+    async fn test_waits_while_original_only_in_open_ledger() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "queue": true }), queued_account_info(ACCOUNT, 9, json!(null)));
+        mock.on("account_info", queued_account_info(ACCOUNT, 8, json!(null)))
+            .on("account_info", queued_account_info(ACCOUNT, 9, json!(null)));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("account_tx", json!({ "account": ACCOUNT, "transactions": [] }));
+
+        let outcome = client(&mock).cancel_pending(SECRET, ACCOUNT, 8, &policy()).await.unwrap();
+        assert_eq!(outcome, CancellationOutcome::OriginalValidated { hash: None });
+        assert_eq!(mock.request_count("submit"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_open_ledger_wait_is_bounded() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "queue": true }), queued_account_info(ACCOUNT, 9, json!(null)));
+        mock.on("account_info", queued_account_info(ACCOUNT, 8, json!(null)));
+        mock.on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 100 + CANCEL_LEDGER_WINDOW + 1 }));
+
+        let error = client(&mock).cancel_pending(SECRET, ACCOUNT, 8, &policy()).await.unwrap_err();
+        assert!(error.to_string().contains("only applied in the open ledger"));
+        assert_eq!(mock.request_count("ledger"), 2);
+        assert_eq!(mock.request_count("submit"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fee_above_maximum() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on(
+            "account_info",
+            queued_account_info(ACCOUNT, 8, json!({ "transactions": [{ "seq": 8, "fee": "2000" }] })),
+        );
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+
+        let error = client(&mock).cancel_pending(SECRET, ACCOUNT, 8, &policy()).await.unwrap_err();
+        assert!(error.to_string().contains("2500 drops"));
+        assert_eq!(mock.request_count("submit"), 0);
+    }
+}
@@ -1,67 +1,357 @@
+use crate::api_version::{self, ApiVersion};
 use crate::error::XrplError;
+use crate::fee::{FeeStrategy, FixedFee};
+use crate::network::Network;
+use crate::pool::{NodeHealth, NodePool, FAILOVER_ERRORS};
+use crate::requests::{AccountInfoRequest, AccountInfoResponse, LedgerIndex, LedgerRequest, SubmitRequest, TxRequest};
+use crate::retry::{RetryPolicy, NON_IDEMPOTENT_METHODS, RETRYABLE_API_ERRORS};
+use crate::signing::TransactionSigner;
+use crate::transport::{HttpTransport, Transport};
 use crate::types::*;
+use crate::websocket::WebSocketTransport;
 use anyhow::Result;
-use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, Proxy};
 use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
 
 pub struct XrplClient {
-    client: Client,
-    base_url: String,
-    testnet: bool,
+    pool: NodePool,
+    network: Network,
+    retry_policy: RetryPolicy,
+    api_version: ApiVersion,
+    fee_strategy: Arc<dyn FeeStrategy>,
+}
+
+enum Endpoint {
+    Url(String),
+    Transport(Arc<dyn Transport>),
+}
+
+/// Builder for an `XrplClient` talking to a custom endpoint or network
+pub struct XrplClientBuilder {
+    network: Network,
+    endpoints: Vec<Endpoint>,
+    max_ledger_age: Option<u32>,
+    health_check_interval: Option<Duration>,
+    retry_policy: RetryPolicy,
+    api_version: ApiVersion,
+    fee_strategy: Arc<dyn FeeStrategy>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    headers: Vec<(String, String)>,
+    user_agent: Option<String>,
+    accept_invalid_certs: bool,
+    root_certificates: Vec<Vec<u8>>,
+}
+
+impl XrplClientBuilder {
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            endpoints: Vec::new(),
+            max_ledger_age: None,
+            health_check_interval: None,
+            retry_policy: RetryPolicy::default(),
+            api_version: ApiVersion::default(),
+            fee_strategy: Arc::new(FixedFee::default()),
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            headers: Vec::new(),
+            user_agent: None,
+            accept_invalid_certs: false,
+            root_certificates: Vec::new(),
+        }
+    }
+
+    /// Use this JSON-RPC endpoint instead of the network's default one.
+    /// Calling it several times builds a pool of failover endpoints.
+    pub fn url(mut self, url: &str) -> Self {
+        self.endpoints.push(Endpoint::Url(url.to_string()));
+        self
+    }
+
+    pub fn urls(mut self, urls: &[&str]) -> Self {
+        self.endpoints
+            .extend(urls.iter().map(|url| Endpoint::Url(url.to_string())));
+        self
+    }
+
+    /// Add an endpoint reached through a custom transport, e.g. a `MockTransport`
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.endpoints.push(Endpoint::Transport(transport));
+        self
+    }
+
+    /// Treat nodes whose validated ledger is older than this as unhealthy
+    pub fn max_ledger_age(mut self, seconds: u32) -> Self {
+        self.max_ledger_age = Some(seconds);
+        self
+    }
+
+    /// Re-run `server_info` health checks on all endpoints at this interval,
+    /// every 30 seconds by default. Pools of one endpoint are never checked.
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = Some(interval);
+        self
+    }
+
+    /// How to retry rate limited and transiently failing requests
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// The rippled API version to request. Responses are parsed into the
+    /// same models either way.
+    pub fn api_version(mut self, version: ApiVersion) -> Self {
+        self.api_version = version;
+        self
+    }
+
+    /// How fees are chosen for transactions this client prepares
+    pub fn fee_strategy(mut self, strategy: impl FeeStrategy + 'static) -> Self {
+        self.fee_strategy = Arc::new(strategy);
+        self
+    }
+
+    /// Total time allowed for a single request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed for establishing the connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Route all requests through this proxy
+    pub fn proxy(mut self, proxy_url: &str) -> Self {
+        self.proxy = Some(proxy_url.to_string());
+        self
+    }
+
+    /// Send this header with every request, e.g. an API key
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Accept self-signed or otherwise invalid certificates (local nodes only)
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Trust an additional PEM encoded root certificate
+    pub fn add_root_certificate(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    pub fn build(self) -> Result<XrplClient> {
+        let mut builder = Client::builder().danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(proxy_url) = &self.proxy {
+            let proxy = Proxy::all(proxy_url)
+                .map_err(|e| XrplError::Config(format!("Invalid proxy: {}", e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if !self.headers.is_empty() {
+            let mut headers = HeaderMap::new();
+            for (name, value) in &self.headers {
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| XrplError::Config(format!("Invalid header name: {}", e)))?;
+                let value = HeaderValue::from_str(value)
+                    .map_err(|e| XrplError::Config(format!("Invalid header value: {}", e)))?;
+                headers.insert(name, value);
+            }
+            builder = builder.default_headers(headers);
+        }
+
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent.as_str());
+        }
+
+        for pem in &self.root_certificates {
+            let certificate = Certificate::from_pem(pem)
+                .map_err(|e| XrplError::Config(format!("Invalid root certificate: {}", e)))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| XrplError::Config(e.to_string()))?;
+
+        let endpoints = if self.endpoints.is_empty() {
+            vec![Endpoint::Url(self.network.rpc_url.clone())]
+        } else {
+            self.endpoints
+        };
+
+        let transports = endpoints
+            .into_iter()
+            .map(|endpoint| match endpoint {
+                Endpoint::Url(url) => {
+                    Arc::new(HttpTransport::with_client(client.clone(), &url)) as Arc<dyn Transport>
+                }
+                Endpoint::Transport(transport) => transport,
+            })
+            .collect();
+
+        let mut pool = NodePool::new(transports);
+        if let Some(seconds) = self.max_ledger_age {
+            pool = pool.with_max_ledger_age(seconds);
+        }
+        if let Some(interval) = self.health_check_interval {
+            pool = pool.with_health_check_interval(interval);
+        }
+
+        Ok(XrplClient {
+            pool,
+            network: self.network,
+            retry_policy: self.retry_policy,
+            api_version: self.api_version,
+            fee_strategy: self.fee_strategy,
+        })
+    }
 }
 
 impl XrplClient {
     pub fn new(testnet: bool) -> Self {
-        let base_url = if testnet {
-            "https://s.altnet.rippletest.net:51234".to_string()
+        let network = if testnet {
+            Network::testnet()
         } else {
-            "https://xrplcluster.com".to_string()
+            Network::mainnet()
         };
 
         Self {
-            client: Client::new(),
-            base_url,
-            testnet,
+            pool: NodePool::new(vec![Arc::new(HttpTransport::new(&network.rpc_url))]),
+            network,
+            retry_policy: RetryPolicy::default(),
+            api_version: ApiVersion::default(),
+            fee_strategy: Arc::new(FixedFee::default()),
         }
     }
 
+    pub fn builder(network: Network) -> XrplClientBuilder {
+        XrplClientBuilder::new(network)
+    }
+
+    /// Connect to a WebSocket endpoint (`ws://` or `wss://`) instead of JSON-RPC over HTTP
+    pub async fn connect_websocket(network: Network, url: &str) -> Result<XrplClient> {
+        let transport = WebSocketTransport::connect(url).await?;
+        XrplClientBuilder::new(network)
+            .transport(Arc::new(transport))
+            .build()
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    pub fn api_version(&self) -> ApiVersion {
+        self.api_version
+    }
+
+    pub fn fee_strategy(&self) -> &dyn FeeStrategy {
+        self.fee_strategy.as_ref()
+    }
+
+    /// The primary endpoint, i.e. the first one configured
+    pub fn base_url(&self) -> &str {
+        self.pool.endpoint(0)
+    }
+
+    pub fn endpoints(&self) -> Vec<&str> {
+        self.pool.endpoints()
+    }
+
+    /// Last known health of every endpoint, in configuration order
+    pub fn endpoint_health(&self) -> Vec<NodeHealth> {
+        (0..self.pool.len()).map(|index| self.pool.health(index)).collect()
+    }
+
+    /// Run a `server_info` health check against every endpoint
+    pub async fn check_health(&self) -> Vec<NodeHealth> {
+        let request = api_version::attach(
+            &json!({
+                "method": "server_info",
+                "params": [{}]
+            }),
+            self.api_version,
+        );
+
+        for index in 0..self.pool.len() {
+            match self.pool.transport(index).send(&request).await {
+                Ok(response) => self.pool.update_from_server_info(index, &response),
+                Err(_) => self.pool.update_unreachable(index),
+            }
+        }
+
+        self.endpoint_health()
+    }
+
     pub fn is_testnet(&self) -> bool {
-        self.testnet
+        self.network.is_testnet()
     }
 
     pub async fn get_ledger_index(&self) -> Result<u32> {
-        let request = json!({
-            "method": "ledger",
-            "params": [{
-                "ledger_index": "validated"
-            }]
-        });
-
-        let response: Value = self.make_request(&request).await?;
-        
-        response["result"]["ledger_index"]
-            .as_u64()
-            .map(|v| v as u32)
+        self.request(&LedgerRequest::new(LedgerIndex::Validated))
+            .await?
+            .ledger_index
             .ok_or_else(|| XrplError::ApiError("Invalid ledger response".to_string()).into())
     }
 
-    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfo> {
-        let request = json!({
-            "method": "account_info",
-            "params": [{
-                "account": address,
-                "ledger_index": "validated"
-            }]
-        });
+    /// Fetch a validated ledger with its transactions expanded to JSON.
+    /// Fails if the ledger is not validated yet.
+    pub async fn get_ledger_transactions(&self, ledger_index: u32) -> Result<LedgerTransactions> {
+        let response = self
+            .request(&LedgerRequest {
+                transactions: Some(true),
+                expand: Some(true),
+                ..LedgerRequest::new(LedgerIndex::Index(ledger_index))
+            })
+            .await?;
 
-        let response: Value = self.make_request(&request).await?;
-        
-        if let Some(error) = response["result"]["error"].as_str() {
-            return Err(XrplError::ApiError(error.to_string()).into());
+        if !response.validated {
+            return Err(XrplError::ApiError(format!("Ledger {} is not validated", ledger_index)).into());
         }
 
-        serde_json::from_value(response["result"].clone())
-            .map_err(|e| XrplError::Deserialization(e.to_string()).into())
+        Ok(LedgerTransactions {
+            ledger_index,
+            ledger_hash: response.ledger.ledger_hash.unwrap_or_default(),
+            close_time: response.ledger.close_time.unwrap_or(0),
+            transactions: response.ledger.transactions.unwrap_or_default(),
+        })
+    }
+
+    /// The account's root entry on the validated ledger
+    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfoResponse> {
+        self.request(&AccountInfoRequest::new(address)).await
     }
 
     pub async fn get_account_sequence(&self, address: &str) -> Result<u32> {
@@ -70,22 +360,11 @@ impl XrplClient {
     }
 
     pub async fn get_transaction(&self, tx_hash: &str) -> Result<TransactionMetadata> {
-        let request = json!({
-            "method": "tx",
-            "params": [{
-                "transaction": tx_hash,
-                "binary": false
-            }]
-        });
-
-        let response: Value = self.make_request(&request).await?;
-        
-        if let Some(error) = response["result"]["error"].as_str() {
-            return Err(XrplError::ApiError(error.to_string()).into());
-        }
-
-        serde_json::from_value(response["result"].clone())
-            .map_err(|e| XrplError::Deserialization(e.to_string()).into())
+        self.request(&TxRequest {
+            binary: Some(false),
+            ..TxRequest::new(tx_hash)
+        })
+        .await
     }
 
     pub fn create_payment_transaction(
@@ -99,72 +378,166 @@ impl XrplClient {
         let public_key = self.secret_to_public_key(user1_secret)?;
         let user1_address = self.public_key_to_address(&public_key)?;
 
-        let mut transaction = Transaction::default();
-        transaction.account = user1_address;
-        transaction.destination = user2_address.to_string();
-        transaction.amount = amount.to_string();
-        transaction.currency = currency_code.to_string();
-        transaction.issuer = Some(issuer_address.to_string());
+        let transaction = Transaction {
+            account: user1_address,
+            destination: user2_address.to_string(),
+            amount: amount.to_string(),
+            currency: currency_code.to_string(),
+            issuer: Some(issuer_address.to_string()),
+            network_id: self.network_id_field(),
+            ..Transaction::default()
+        };
 
         Ok(transaction)
     }
 
+    /// Submit a signed transaction.
+    ///
+    /// A failed submit is only retried once a `tx` lookup confirms that the
+    /// transaction hash is unknown to the network; if the lookup finds it, the
+    /// result of that earlier submission is returned instead.
     pub async fn submit_transaction(&self, signed_tx: &SignedTransaction) -> Result<TransactionResult> {
-        let request = json!({
-            "method": "submit",
-            "params": [{
-                "tx_blob": signed_tx.tx_blob
-            }]
-        });
-
-        let response: Value = self.make_request(&request).await?;
-        
-        if let Some(error) = response["result"]["error"].as_str() {
-            return Err(XrplError::ApiError(error.to_string()).into());
-        }
+        let request = SubmitRequest::new(&signed_tx.tx_blob);
 
-        let result = &response["result"];
-        
+        let mut attempt = 0;
+        let result = loop {
+            let error = match self.request(&request).await {
+                Ok(response) => break response,
+                Err(error) => error,
+            };
+
+            if !self.retry_policy.retry_submits
+                || attempt >= self.retry_policy.max_retries
+                || !RetryPolicy::is_retryable(&error)
+            {
+                return Err(error);
+            }
+
+            tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+            attempt += 1;
+
+            let hash = TransactionSigner::transaction_hash(&signed_tx.tx_blob)?;
+            match self.find_submitted_transaction(&hash).await {
+                Ok(Some(result)) => return Ok(result),
+                Ok(None) => continue,
+                Err(_) => return Err(error),
+            }
+        };
+
+        // A preliminary result: not in any ledger yet
         Ok(TransactionResult {
-            hash: result["tx_json"]["hash"]
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-            validated: result["validated"].as_bool().unwrap_or(false),
-            ledger_index: result["ledger_index"].as_u64().map(|v| v as u32),
-            engine_result: result["engine_result"]
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-            engine_result_message: result["engine_result_message"]
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-            engine_result_code: result["engine_result_code"]
-                .as_i64()
-                .unwrap_or(0) as i32,
-            meta: Some(result["meta"].clone()),
+            hash: result.tx_json["hash"].as_str().unwrap_or("").to_string(),
+            validated: false,
+            ledger_index: None,
+            engine_result: result.engine_result,
+            engine_result_message: result.engine_result_message,
+            engine_result_code: result.engine_result_code,
+            meta: None,
         })
     }
 
-    async fn make_request(&self, request: &Value) -> Result<Value> {
-        let response = self
-            .client
-            .post(&self.base_url)
-            .json(request)
-            .send()
-            .await?;
+    /// Look up a previously submitted transaction by hash, `None` if the network does not know it
+    pub(crate) async fn find_submitted_transaction(&self, tx_hash: &str) -> Result<Option<TransactionResult>> {
+        let transaction = match self.get_transaction(tx_hash).await {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                return match error.downcast_ref::<XrplError>() {
+                    Some(XrplError::ApiError(code)) if code == "txnNotFound" => Ok(None),
+                    _ => Err(error),
+                };
+            }
+        };
+
+        Ok(Some(TransactionResult {
+            engine_result: transaction.transaction_result().unwrap_or("").to_string(),
+            engine_result_message: String::new(),
+            engine_result_code: 0,
+            meta: transaction
+                .meta
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| XrplError::Serialization(e.to_string()))?,
+            hash: transaction.hash,
+            validated: transaction.validated,
+            ledger_index: transaction.ledger_index,
+        }))
+    }
 
-        if !response.status().is_success() {
-            return Err(XrplError::Network(format!(
-                "HTTP error: {}",
-                response.status()
-            ))
-            .into());
+    fn network_id_field(&self) -> Option<u32> {
+        if self.network.requires_network_id() {
+            Some(self.network.network_id)
+        } else {
+            None
         }
+    }
+
+    /// Send an idempotent request, retrying transient failures per the retry policy
+    pub(crate) async fn make_request(&self, request: &Value) -> Result<Value> {
+        let mut attempt = 0;
+
+        loop {
+            match self.send_to_pool(request).await {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    if attempt >= self.retry_policy.max_retries || !RetryPolicy::is_retryable(&error) {
+                        return Err(error);
+                    }
 
-        let response_data: Value = response.json().await?;
-        Ok(response_data)
+                    tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Send a request once, failing over between endpoints. Submits only fail
+    /// over on errors that show the node did not process them. The configured
+    /// API version is attached and the result normalized to the v2 shape.
+    pub(crate) async fn send_to_pool(&self, request: &Value) -> Result<Value> {
+        if self.pool.needs_health_check() {
+            self.check_health().await;
+        }
+
+        let request = api_version::attach(request, self.api_version);
+        let method = request["method"].as_str().unwrap_or("");
+        let mut last_error = None;
+
+        for index in self.pool.ordered() {
+            match self.pool.transport(index).send(&request).await {
+                Ok(mut response) => {
+                    if let Some(error) = response["result"]["error"].as_str() {
+                        if FAILOVER_ERRORS.contains(&error) {
+                            self.pool.mark_failure(index);
+                            last_error = Some(XrplError::ApiError(error.to_string()).into());
+                            continue;
+                        }
+
+                        if RETRYABLE_API_ERRORS.contains(&error) {
+                            self.pool.mark_success(index);
+                            return Err(XrplError::ApiError(error.to_string()).into());
+                        }
+                    }
+
+                    self.pool.mark_success(index);
+                    if let Some(result) = response.get_mut("result") {
+                        api_version::normalize(method, result);
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    self.pool.mark_failure(index);
+                    // The node may have applied a submit before the connection
+                    // broke, so only a hash lookup may decide to send it again
+                    if NON_IDEMPOTENT_METHODS.contains(&method) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| XrplError::Network("No endpoints configured".to_string()).into()))
     }
 
     fn secret_to_public_key(&self, secret: &str) -> Result<String> {
@@ -198,28 +571,12 @@ impl XrplClient {
         Ok(account_info.account_data.balance)
     }
 
+    /// All trust lines of an account, following markers across pages
     pub async fn get_trust_lines(&self, address: &str) -> Result<Vec<TrustLine>> {
-        let request = json!({
-            "method": "account_lines",
-            "params": [{
-                "account": address,
-                "ledger_index": "validated"
-            }]
-        });
-
-        let response: Value = self.make_request(&request).await?;
-        
-        if let Some(error) = response["result"]["error"].as_str() {
-            return Err(XrplError::ApiError(error.to_string()).into());
-        }
+        let lines = self.account_lines(address).collect_all().await?;
 
-        let lines = &response["result"]["lines"];
-        if lines.is_array() {
-            serde_json::from_value(lines.clone())
-                .map_err(|e| XrplError::Deserialization(e.to_string()).into())
-        } else {
-            Ok(Vec::new())
-        }
+        serde_json::from_value(Value::Array(lines))
+            .map_err(|e| XrplError::Deserialization(e.to_string()).into())
     }
 }
 
@@ -231,13 +588,78 @@ mod tests {
     fn test_client_creation() {
         let client = XrplClient::new(true);
         assert!(client.is_testnet());
-        assert!(client.base_url.contains("altnet.rippletest.net"));
+        assert!(client.base_url().contains("altnet.rippletest.net"));
     }
 
     #[test]
     fn test_mainnet_client() {
         let client = XrplClient::new(false);
         assert!(!client.is_testnet());
-        assert!(client.base_url.contains("xrplcluster.com"));
+        assert!(client.base_url().contains("xrplcluster.com"));
+    }
+
+    #[test]
+    fn test_builder_custom_endpoint() {
+        let client = XrplClient::builder(Network::devnet())
+            .url("http://localhost:5005")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .header("x-api-key", "secret")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "http://localhost:5005");
+        assert_eq!(client.network().network_id, Network::DEVNET_ID);
+        assert!(client.is_testnet());
+    }
+
+    #[test]
+    fn test_builder_failover_endpoints() {
+        let client = XrplClient::builder(Network::mainnet())
+            .urls(&["https://node-a.example", "https://node-b.example"])
+            .url("https://node-c.example")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "https://node-a.example");
+        assert_eq!(client.endpoints().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoints_unreachable() {
+        let client = XrplClient::builder(Network::standalone("http://127.0.0.1:1"))
+            .url("http://127.0.0.1:1")
+            .url("http://127.0.0.1:2")
+            .connect_timeout(Duration::from_millis(200))
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        assert!(client.get_ledger_index().await.is_err());
+        // Once in the initial health check, once by the request itself
+        assert!(client.endpoint_health().iter().all(|health| health.consecutive_failures == 2));
+        assert!(client.endpoint_health().iter().all(|health| health.last_checked.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_are_retried() {
+        let client = XrplClient::builder(Network::standalone("http://127.0.0.1:1"))
+            .connect_timeout(Duration::from_millis(200))
+            .retry_policy(RetryPolicy::default().with_max_retries(2).with_backoff(Duration::from_millis(1), Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        let error = client.get_ledger_index().await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<XrplError>(), Some(XrplError::Network(_))));
+        assert_eq!(client.endpoint_health()[0].consecutive_failures, 3);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_header() {
+        let result = XrplClient::builder(Network::testnet())
+            .header("bad header", "value")
+            .build();
+
+        assert!(result.is_err());
     }
 }
@@ -6,6 +6,9 @@ pub enum XrplError {
     #[error("Network error: {0}")]
     Network(String),
 
+    #[error("HTTP error: {0}")]
+    HttpStatus(u16),
+
     #[error("Invalid secret key: {0}")]
     InvalidSecret(String),
 
@@ -38,6 +41,9 @@ pub enum XrplError {
 
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
+
+    #[error("Invalid configuration: {0}")]
+    Config(String),
 }
 
 impl From<reqwest::Error> for XrplError {
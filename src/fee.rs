@@ -0,0 +1,280 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::types::*;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Fee in drops used when nothing else is configured
+pub const DEFAULT_FEE_DROPS: u64 = 12;
+
+/// What sets a transaction's base cost apart from the reference transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TransactionCost {
+    /// Number of signers of a multisigned transaction, 0 for single signing
+    pub signer_count: u32,
+    /// Size in bytes of an EscrowFinish fulfillment
+    pub fulfillment_len: Option<usize>,
+}
+
+impl TransactionCost {
+    /// A single-signed transaction without extra cost
+    pub fn reference() -> Self {
+        Self::default()
+    }
+
+    pub fn with_signers(mut self, signer_count: u32) -> Self {
+        self.signer_count = signer_count;
+        self
+    }
+
+    pub fn with_fulfillment_len(mut self, bytes: usize) -> Self {
+        self.fulfillment_len = Some(bytes);
+        self
+    }
+
+    /// Base cost in multiples of the reference fee, as rippled computes it:
+    /// one per signature, plus `32 + size / 16` for a fulfillment
+    pub fn reference_units(&self) -> u64 {
+        let signatures = 1 + self.signer_count as u64;
+        let fulfillment = self
+            .fulfillment_len
+            .map(|bytes| 32 + bytes as u64 / 16)
+            .unwrap_or(0);
+
+        signatures + fulfillment
+    }
+}
+
+/// Decides the fee to pay for a transaction
+#[async_trait]
+pub trait FeeStrategy: Send + Sync {
+    /// Fee in drops for a transaction with the given cost
+    async fn fee(&self, client: &XrplClient, cost: &TransactionCost) -> Result<u64>;
+}
+
+/// The same fee per reference unit, regardless of network conditions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedFee {
+    pub drops: u64,
+}
+
+impl FixedFee {
+    pub fn new(drops: u64) -> Self {
+        Self { drops }
+    }
+}
+
+impl Default for FixedFee {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEE_DROPS)
+    }
+}
+
+#[async_trait]
+impl FeeStrategy for FixedFee {
+    async fn fee(&self, _client: &XrplClient, cost: &TransactionCost) -> Result<u64> {
+        Ok(self.drops * cost.reference_units())
+    }
+}
+
+/// The base fee scaled by the server's load factor from `server_state`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadScaledFee;
+
+#[async_trait]
+impl FeeStrategy for LoadScaledFee {
+    async fn fee(&self, client: &XrplClient, cost: &TransactionCost) -> Result<u64> {
+        let state = client.server_state().await?;
+        let base_fee = state
+            .validated_ledger
+            .as_ref()
+            .map(|ledger| ledger.base_fee)
+            .ok_or_else(|| XrplError::ApiError("Server has no validated ledger".to_string()))?;
+
+        match (state.load_factor, state.load_base) {
+            (Some(factor), Some(base)) if base > 0 => Ok(scale(base_fee * cost.reference_units(), factor, base)),
+            _ => Ok(base_fee * cost.reference_units()),
+        }
+    }
+}
+
+/// Enough to get into the current open ledger, per the `fee` method
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenLedgerFee;
+
+#[async_trait]
+impl FeeStrategy for OpenLedgerFee {
+    async fn fee(&self, client: &XrplClient, cost: &TransactionCost) -> Result<u64> {
+        let fee = client.fee().await?;
+        Ok(level_to_drops(&fee, fee.levels.open_ledger_level, cost))
+    }
+}
+
+/// Enough to get into the next ledger when the open ledger is full.
+///
+/// Queued transactions are applied to the next ledger in fee level order, so
+/// with a non-empty queue this pays at least the median level of the last
+/// validated ledger to get ahead of most of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NextLedgerFee;
+
+#[async_trait]
+impl FeeStrategy for NextLedgerFee {
+    async fn fee(&self, client: &XrplClient, cost: &TransactionCost) -> Result<u64> {
+        let fee = client.fee().await?;
+        let levels = &fee.levels;
+
+        let level = if fee.current_queue_size == 0 {
+            levels.open_ledger_level
+        } else {
+            levels
+                .open_ledger_level
+                .max(levels.median_level)
+                .max(levels.minimum_level + 1)
+        };
+
+        Ok(level_to_drops(&fee, level, cost))
+    }
+}
+
+/// Limits another strategy to protect against fee spikes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CappedFee<S> {
+    inner: S,
+    /// Per reference unit, so multisigned transactions can still pay their share
+    max_drops: u64,
+}
+
+impl<S: FeeStrategy> CappedFee<S> {
+    pub fn new(inner: S, max_drops: u64) -> Self {
+        Self { inner, max_drops }
+    }
+}
+
+#[async_trait]
+impl<S: FeeStrategy> FeeStrategy for CappedFee<S> {
+    async fn fee(&self, client: &XrplClient, cost: &TransactionCost) -> Result<u64> {
+        let fee = self.inner.fee(client, cost).await?;
+        Ok(fee.min(self.max_drops * cost.reference_units()))
+    }
+}
+
+/// `value * numerator / denominator`, rounded up
+fn scale(value: u64, numerator: u64, denominator: u64) -> u64 {
+    let scaled = (value as u128 * numerator as u128).div_ceil(denominator as u128);
+    scaled.min(u64::MAX as u128) as u64
+}
+
+/// Drops a transaction must pay to reach a fee level
+fn level_to_drops(fee: &crate::requests::FeeResponse, level: u64, cost: &TransactionCost) -> u64 {
+    let base_fee = fee.drops.base_fee * cost.reference_units();
+    scale(base_fee, level, fee.levels.reference_level.max(1))
+}
+
+impl XrplClient {
+    /// Fee in drops for a transaction, per the configured `FeeStrategy`
+    pub async fn estimate_fee(&self, cost: &TransactionCost) -> Result<u64> {
+        self.fee_strategy().fee(self, cost).await
+    }
+
+    /// Set the `Fee` of a transaction per the configured `FeeStrategy`
+    pub async fn fill_fee(&self, transaction: &mut Transaction, cost: &TransactionCost) -> Result<()> {
+        transaction.fee = self.estimate_fee(cost).await?.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn mock_fee(queue_size: u64) -> Arc<MockTransport> {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("fee", json!({
+            "current_ledger_size": "30",
+            "current_queue_size": queue_size.to_string(),
+            "drops": { "base_fee": "10", "median_fee": "5000", "minimum_fee": "10", "open_ledger_fee": "20" },
+            "expected_ledger_size": "25",
+            "ledger_current_index": 100,
+            "levels": {
+                "median_level": "128000",
+                "minimum_level": "256",
+                "open_ledger_level": "512",
+                "reference_level": "256"
+            },
+            "max_queue_size": "500"
+        }));
+        mock.on("server_state", json!({
+            "state": {
+                "build_version": "2.2.3",
+                "load_base": 256,
+                "load_factor": 384,
+                "server_state": "full",
+                "validated_ledger": { "base_fee": 10, "hash": "ABC", "reserve_base": 1000000, "reserve_inc": 200000, "seq": 99 }
+            }
+        }));
+        mock
+    }
+
+    #[test]
+    fn test_reference_units() {
+        assert_eq!(TransactionCost::reference().reference_units(), 1);
+        assert_eq!(TransactionCost::reference().with_signers(3).reference_units(), 4);
+        assert_eq!(TransactionCost::reference().with_fulfillment_len(36).reference_units(), 35);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_and_load_scaled() {
+        let client = client(&mock_fee(0));
+        let multisig = TransactionCost::reference().with_signers(2);
+
+        assert_eq!(FixedFee::default().fee(&client, &multisig).await.unwrap(), 36);
+        assert_eq!(LoadScaledFee.fee(&client, &TransactionCost::reference()).await.unwrap(), 15);
+        assert_eq!(LoadScaledFee.fee(&client, &multisig).await.unwrap(), 45);
+    }
+
+    #[tokio::test]
+    async fn test_open_and_next_ledger() {
+        let reference = TransactionCost::reference();
+
+        let idle = client(&mock_fee(0));
+        assert_eq!(OpenLedgerFee.fee(&idle, &reference).await.unwrap(), 20);
+        assert_eq!(NextLedgerFee.fee(&idle, &reference).await.unwrap(), 20);
+
+        let busy = client(&mock_fee(40));
+        assert_eq!(NextLedgerFee.fee(&busy, &reference).await.unwrap(), 5000);
+        assert_eq!(
+            NextLedgerFee.fee(&busy, &reference.with_signers(1)).await.unwrap(),
+            10000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cap() {
+        let busy = client(&mock_fee(40));
+        let capped = CappedFee::new(NextLedgerFee, 1000);
+
+        assert_eq!(capped.fee(&busy, &TransactionCost::reference()).await.unwrap(), 1000);
+        assert_eq!(capped.fee(&busy, &TransactionCost::reference().with_signers(1)).await.unwrap(), 2000);
+        assert_eq!(CappedFee::new(OpenLedgerFee, 1000).fee(&busy, &TransactionCost::reference()).await.unwrap(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_client_strategy() {
+        let mock = mock_fee(0);
+        let client = XrplClient::builder(Network::testnet())
+            .transport(mock.clone())
+            .fee_strategy(LoadScaledFee)
+            .build()
+            .unwrap();
+
+        let mut transaction = Transaction::default();
+        client.fill_fee(&mut transaction, &TransactionCost::reference()).await.unwrap();
+        assert_eq!(transaction.fee, "15");
+    }
+}
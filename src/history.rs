@@ -0,0 +1,354 @@
+use crate::binary;
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::meta::TransactionMeta;
+use crate::signing::TransactionSigner;
+use crate::types::*;
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+
+/// Parameters of an `account_tx` query
+#[derive(Debug, Clone)]
+pub struct AccountTxRequest {
+    pub account: Address,
+    /// Earliest ledger to include; `None` means the oldest available
+    pub ledger_index_min: Option<u32>,
+    /// Latest ledger to include; `None` means the newest validated
+    pub ledger_index_max: Option<u32>,
+    /// Oldest first instead of newest first
+    pub forward: bool,
+    /// Transactions per page
+    pub limit: Option<u32>,
+    /// Fetch blobs and decode them locally instead of server-side JSON
+    pub binary: bool,
+}
+
+impl AccountTxRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ledger_index_min: None,
+            ledger_index_max: None,
+            forward: false,
+            limit: None,
+            binary: false,
+        }
+    }
+
+    pub fn ledger_range(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.ledger_index_min = min;
+        self.ledger_index_max = max;
+        self
+    }
+
+    pub fn forward(mut self, forward: bool) -> Self {
+        self.forward = forward;
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
+
+    pub(crate) fn to_params(&self, marker: Option<&Value>) -> Value {
+        let bound = |index: Option<u32>| index.map(i64::from).unwrap_or(-1);
+
+        let mut params = json!({
+            "account": self.account,
+            "ledger_index_min": bound(self.ledger_index_min),
+            "ledger_index_max": bound(self.ledger_index_max),
+            "forward": self.forward,
+            "binary": self.binary
+        });
+
+        if let Some(limit) = self.limit {
+            params["limit"] = json!(limit);
+        }
+        if let Some(marker) = marker {
+            params["marker"] = marker.clone();
+        }
+
+        params
+    }
+}
+
+/// A transaction from an account's history, with its metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountTransaction {
+    pub hash: TransactionHash,
+    pub ledger_index: u32,
+    pub validated: bool,
+    /// Close time of the ledger in seconds since the Ripple epoch, when known
+    pub date: Option<Timestamp>,
+    pub tx: Value,
+    pub meta: Value,
+}
+
+impl AccountTransaction {
+    pub fn transaction_type(&self) -> Option<&str> {
+        self.tx["TransactionType"].as_str()
+    }
+
+    /// The engine result recorded in the metadata, e.g. `tesSUCCESS`
+    pub fn result(&self) -> Option<&str> {
+        self.meta["TransactionResult"].as_str()
+    }
+
+    pub fn transaction_meta(&self) -> Result<TransactionMeta> {
+        TransactionMeta::from_json(&self.meta)
+    }
+
+    /// Parse one entry of the `transactions` array. Handles API v1 (`tx`)
+    /// and v2 (`tx_json`) entries, in JSON or binary form.
+    pub fn from_entry(entry: &Value) -> Result<Self> {
+        if let Some(tx_blob) = entry["tx_blob"].as_str() {
+            let meta_blob = entry["meta_blob"]
+                .as_str()
+                .or_else(|| entry["meta"].as_str())
+                .ok_or_else(|| XrplError::Deserialization("Missing transaction metadata".to_string()))?;
+
+            let tx = binary::decode(tx_blob)?;
+            return Ok(Self {
+                hash: TransactionSigner::transaction_hash(tx_blob)?,
+                ledger_index: ledger_index_of(entry, &tx)?,
+                validated: entry["validated"] == true,
+                date: entry["date"].as_u64(),
+                meta: binary::decode(meta_blob)?,
+                tx,
+            });
+        }
+
+        let tx = if entry["tx_json"].is_object() {
+            entry["tx_json"].clone()
+        } else {
+            entry["tx"].clone()
+        };
+        if !tx.is_object() {
+            return Err(XrplError::Deserialization("Missing transaction".to_string()).into());
+        }
+
+        let hash = entry["hash"]
+            .as_str()
+            .or_else(|| tx["hash"].as_str())
+            .ok_or_else(|| XrplError::Deserialization("Missing transaction hash".to_string()))?
+            .to_string();
+
+        Ok(Self {
+            hash,
+            ledger_index: ledger_index_of(entry, &tx)?,
+            validated: entry["validated"] == true,
+            date: entry["date"].as_u64().or_else(|| tx["date"].as_u64()),
+            meta: entry["meta"].clone(),
+            tx,
+        })
+    }
+}
+
+fn ledger_index_of(entry: &Value, tx: &Value) -> Result<u32> {
+    entry["ledger_index"]
+        .as_u64()
+        .or_else(|| tx["ledger_index"].as_u64())
+        .map(|index| index as u32)
+        .ok_or_else(|| XrplError::Deserialization("Missing ledger index".to_string()).into())
+}
+
+/// One page of `account_tx` results
+#[derive(Debug, Clone)]
+pub struct AccountTxPage {
+    pub account: Address,
+    pub ledger_index_min: u32,
+    pub ledger_index_max: u32,
+    pub transactions: Vec<AccountTransaction>,
+    /// Pass to the next call to continue; `None` on the last page
+    pub marker: Option<Value>,
+}
+
+impl XrplClient {
+    /// Fetch one page of an account's transactions
+    pub async fn account_tx(&self, request: &AccountTxRequest, marker: Option<&Value>) -> Result<AccountTxPage> {
+        let request = json!({
+            "method": "account_tx",
+            "params": [request.to_params(marker)]
+        });
+
+        let response: Value = self.make_request(&request).await?;
+        let result = &response["result"];
+
+        if let Some(error) = result["error"].as_str() {
+            return Err(XrplError::ApiError(error.to_string()).into());
+        }
+
+        let transactions = result["transactions"]
+            .as_array()
+            .map(|entries| entries.iter().map(AccountTransaction::from_entry).collect())
+            .unwrap_or_else(|| Ok(Vec::new()))?;
+
+        Ok(AccountTxPage {
+            account: result["account"].as_str().unwrap_or("").to_string(),
+            ledger_index_min: result["ledger_index_min"].as_u64().unwrap_or(0) as u32,
+            ledger_index_max: result["ledger_index_max"].as_u64().unwrap_or(0) as u32,
+            transactions,
+            marker: result.get("marker").filter(|marker| !marker.is_null()).cloned(),
+        })
+    }
+
+    /// Every transaction matching `request`, following markers until the
+    /// last page. Stops after the first error.
+    pub fn account_tx_stream(&self, request: AccountTxRequest) -> impl Stream<Item = Result<AccountTransaction>> + '_ {
+        let state = (request, VecDeque::new(), None::<Value>, false);
+
+        stream::unfold(state, move |(request, mut queue, mut marker, mut done)| async move {
+            loop {
+                if let Some(transaction) = queue.pop_front() {
+                    return Some((Ok(transaction), (request, queue, marker, done)));
+                }
+
+                if done {
+                    return None;
+                }
+
+                match self.account_tx(&request, marker.as_ref()).await {
+                    Ok(page) => {
+                        queue.extend(page.transactions);
+                        done = page.marker.is_none();
+                        marker = page.marker;
+                    }
+                    Err(e) => return Some((Err(e), (request, queue, None, true))),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    const ACCOUNT: &str = "rHistory123456789012345678901234";
+
+    fn entry(hash: &str, ledger_index: u32) -> Value {
+        json!({
+            "meta": { "TransactionResult": "tesSUCCESS" },
+            "tx": {
+                "hash": hash,
+                "TransactionType": "Payment",
+                "Account": ACCOUNT,
+                "date": 700000000 + ledger_index,
+                "ledger_index": ledger_index
+            },
+            "validated": true
+        })
+    }
+
+    #[test]
+    fn test_request_params() {
+        let request = AccountTxRequest::new(ACCOUNT)
+            .ledger_range(Some(100), None)
+            .forward(true)
+            .limit(50);
+
+        let params = request.to_params(Some(&json!({ "ledger": 120, "seq": 3 })));
+        assert_eq!(params["ledger_index_min"], 100);
+        assert_eq!(params["ledger_index_max"], -1);
+        assert_eq!(params["forward"], true);
+        assert_eq!(params["limit"], 50);
+        assert_eq!(params["marker"]["ledger"], 120);
+    }
+
+    #[test]
+    fn test_parse_v2_entry() {
+        let parsed = AccountTransaction::from_entry(&json!({
+            "hash": "ABC",
+            "ledger_index": 7,
+            "validated": true,
+            "tx_json": { "TransactionType": "OfferCreate", "date": 123 },
+            "meta": { "TransactionResult": "tecKILLED" }
+        }))
+        .unwrap();
+
+        assert_eq!(parsed.hash, "ABC");
+        assert_eq!(parsed.ledger_index, 7);
+        assert_eq!(parsed.date, Some(123));
+        assert_eq!(parsed.transaction_type(), Some("OfferCreate"));
+        assert_eq!(parsed.result(), Some("tecKILLED"));
+        assert_eq!(parsed.transaction_meta().unwrap().transaction_result, "tecKILLED");
+    }
+
+    #[test]
+    fn test_parse_binary_entry() {
+        let tx_blob = concat!(
+            "120000",
+            "2400000001",
+            "6140000000000F4240",
+            "68400000000000000C",
+            "81140000000000000000000000000000000000000000",
+            "83140000000000000000000000000000000000000001",
+        );
+
+        let parsed = AccountTransaction::from_entry(&json!({
+            "tx_blob": tx_blob,
+            "meta": "031000",
+            "ledger_index": 9,
+            "validated": true
+        }))
+        .unwrap();
+
+        assert_eq!(parsed.hash, TransactionSigner::transaction_hash(tx_blob).unwrap());
+        assert_eq!(parsed.tx["Amount"], "1000000");
+        assert_eq!(parsed.result(), Some("tesSUCCESS"));
+        assert_eq!(parsed.date, None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_follows_markers() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_tx", json!({
+            "account": ACCOUNT,
+            "ledger_index_min": 1,
+            "ledger_index_max": 20,
+            "transactions": [entry("A", 5), entry("B", 6)],
+            "marker": { "ledger": 6, "seq": 1 }
+        }))
+        .on_params("account_tx", json!({ "marker": { "ledger": 6, "seq": 1 } }), json!({
+            "account": ACCOUNT,
+            "ledger_index_min": 1,
+            "ledger_index_max": 20,
+            "transactions": [entry("C", 9)]
+        }));
+        let client = client(&mock);
+
+        let request = AccountTxRequest::new(ACCOUNT).forward(true).limit(2);
+        let hashes: Vec<String> = client
+            .account_tx_stream(request)
+            .map(|transaction| transaction.unwrap().hash)
+            .collect()
+            .await;
+
+        assert_eq!(hashes, vec!["A", "B", "C"]);
+        assert_eq!(mock.request_count("account_tx"), 2);
+        assert!(mock.requests_for("account_tx")[0].get("marker").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_account_not_found() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_error("account_tx", "actNotFound");
+        let client = client(&mock);
+
+        let mut stream = Box::pin(client.account_tx_stream(AccountTxRequest::new(ACCOUNT)));
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+}
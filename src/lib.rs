@@ -1,28 +1,120 @@
 pub mod error;
 pub mod types;
+pub mod amount;
+pub mod api_version;
+pub mod binary;
+pub mod network;
+pub mod pool;
+pub mod retry;
+pub mod transport;
+#[cfg(test)]
+mod test_support;
+pub mod websocket;
+pub mod subscription;
 pub mod client;
+pub mod fee;
+pub mod resubmit;
+pub mod cancel;
+pub mod sequence;
+pub mod tickets;
+pub mod outbox;
+pub mod payout;
+pub mod watcher;
+pub mod meta;
+pub mod history;
+pub mod verification;
+pub mod pagination;
+pub mod requests;
+pub mod server_info;
+pub mod balance;
+pub mod preflight;
+pub mod precheck;
 pub mod transaction;
 pub mod signing;
 
 pub use error::XrplError;
 pub use types::*;
-pub use client::XrplClient;
+pub use amount::{CurrencyAmount, IouValue, IssuedAmount};
+pub use api_version::ApiVersion;
+pub use network::Network;
+pub use pool::{NodeHealth, NodePool};
+pub use retry::RetryPolicy;
+pub use transport::{HttpTransport, MockReply, MockTransport, Transport};
+pub use websocket::{WebSocketConfig, WebSocketTransport};
+pub use subscription::{
+    BookSnapshot, LedgerClosed, OrderBook, ServerStatusEvent, SubscriptionStream, TransactionEvent,
+    ValidationEvent,
+};
+pub use client::{XrplClient, XrplClientBuilder};
+pub use fee::{
+    CappedFee, FeeStrategy, FixedFee, LoadScaledFee, NextLedgerFee, OpenLedgerFee, TransactionCost, DEFAULT_FEE_DROPS,
+};
+pub use cancel::CancellationOutcome;
+pub use sequence::SequenceManager;
+pub use tickets::TicketPool;
+pub use payout::{PayoutBatch, PayoutEngine, PayoutIssue, PayoutReport, PayoutRow, PayoutRowResult, PayoutStatus};
+pub use outbox::{FileOutboxStore, MemoryOutboxStore, Outbox, OutboxEntry, OutboxStatus, OutboxStore};
+pub use resubmit::{FeeBumpOutcome, FeeBumpPolicy, SubmissionAttempt};
+pub use watcher::{
+    CheckpointStore, FileCheckpointStore, IncomingPayment, LedgerPayments, MemoryCheckpointStore,
+    PaymentWatcher,
+};
+pub use meta::{
+    AccountRootFields, AffectedNode, BalanceChange, BalanceDelta, LedgerEntry, LedgerNode, OfferFields, RippleStateFields,
+    TransactionMeta,
+};
+pub use history::{AccountTransaction, AccountTxPage, AccountTxRequest};
+pub use pagination::Paginator;
+pub use requests::{LedgerIndex, Request};
+pub use server_info::Reserves;
+pub use balance::SpendableXrp;
+pub use verification::{ExpectedTransfer, TransferVerification, VerificationIssue};
+pub use preflight::{preflight, PreflightError};
+pub use precheck::{PaymentPrecheck, PrecheckIssue};
 pub use transaction::*;
 pub use signing::*;
 
 use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 pub struct XrplLib {
     client: XrplClient,
+    sequences: Mutex<HashMap<Address, Arc<SequenceManager>>>,
 }
 
 impl XrplLib {
     pub fn new(testnet: bool) -> Self {
-        let client = XrplClient::new(testnet);
-        Self { client }
+        Self::with_client(XrplClient::new(testnet))
+    }
+
+    /// Create a library instance on top of a preconfigured client
+    pub fn with_client(client: XrplClient) -> Self {
+        Self {
+            client,
+            sequences: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn client(&self) -> &XrplClient {
+        &self.client
+    }
+
+    /// The sequence manager shared by every transaction this instance sends
+    /// from `account`
+    pub fn sequence_manager(&self, account: &str) -> Arc<SequenceManager> {
+        self.sequences
+            .lock()
+            .unwrap()
+            .entry(account.to_string())
+            .or_insert_with(|| Arc::new(SequenceManager::new(account)))
+            .clone()
     }
 
     /// Send a token (issued asset) from user1 to user2
+    ///
+    /// Sequences come from the account's `SequenceManager`, so concurrent
+    /// calls for the same sender do not collide.
     /// 
     /// # Arguments
     /// * `user1_secret` - The secret key of the sender
@@ -38,19 +130,48 @@ impl XrplLib {
         currency_code: &str,
         amount: &str,
     ) -> Result<TransactionResult> {
-        let transaction = self.client.create_payment_transaction(
+        let mut transaction = self.client.create_payment_transaction(
             user1_secret,
             user2_address,
             issuer_address,
             currency_code,
             amount,
         )?;
+        self.client
+            .fill_fee(&mut transaction, &TransactionCost::reference())
+            .await?;
+
+        let sequences = self.sequence_manager(&transaction.account);
+        transaction.sequence = sequences.next(&self.client).await?;
+
+        let signed_tx = match self.sign_transaction_offline(user1_secret, &transaction) {
+            Ok(signed_tx) => signed_tx,
+            Err(error) => {
+                sequences.release(transaction.sequence).await;
+                return Err(error);
+            }
+        };
+
+        let result = match self.submit_signed_transaction(&signed_tx).await {
+            Ok(result) => result,
+            Err(error) => {
+                let hash = TransactionSigner::transaction_hash(&signed_tx.tx_blob)?;
+                sequences.reconcile(&self.client, transaction.sequence, &hash).await;
+                return Err(error);
+            }
+        };
+        sequences
+            .handle_result(&self.client, transaction.sequence, &result.engine_result)
+            .await?;
 
-        let signed_tx = self.sign_transaction_offline(user1_secret, &transaction)?;
-        self.submit_signed_transaction(&signed_tx).await
+        Ok(result)
     }
 
-    /// Verify that user1 sent a token to user2
+    /// Verify that user1 sent a token to user2.
+    ///
+    /// The amount is checked against what was actually delivered, and the
+    /// transaction must be validated with `tesSUCCESS`; see
+    /// `TransferVerification::is_valid`.
     /// 
     /// # Arguments
     /// * `user1_address` - The address of the sender
@@ -67,25 +188,9 @@ impl XrplLib {
         currency_code: &str,
         amount: &str,
         tx_hash: &str,
-    ) -> Result<bool> {
-        let tx_data = self.client.get_transaction(tx_hash).await?;
-        
-        if tx_data.transaction_type != "Payment" {
-            return Ok(false);
-        }
-
-        if let Some(payment) = tx_data.payment {
-            let amount_matches = payment.amount == amount;
-            let currency_matches = payment.currency == currency_code;
-            let issuer_matches = payment.issuer.as_deref() == Some(issuer_address);
-            let sender_matches = tx_data.account == user1_address;
-            let destination_matches = payment.destination == user2_address;
-
-            Ok(amount_matches && currency_matches && issuer_matches && 
-               sender_matches && destination_matches)
-        } else {
-            Ok(false)
-        }
+    ) -> Result<TransferVerification> {
+        let expected = ExpectedTransfer::issued(user1_address, user2_address, currency_code, issuer_address, amount);
+        self.client.verify_transfer(tx_hash, &expected).await
     }
 
     /// Sign a transfer transaction offline (produce a signed blob, but don't submit)
@@ -98,7 +203,7 @@ impl XrplLib {
         secret: &str,
         transaction: &Transaction,
     ) -> Result<SignedTransaction> {
-        let signer = TransactionSigner::new();
+        let signer = TransactionSigner::with_network(self.client.network().clone());
         signer.sign_transaction(secret, transaction)
     }
 
@@ -0,0 +1,495 @@
+use crate::amount::{CurrencyAmount, IouValue, IssuedAmount};
+use crate::error::XrplError;
+use crate::types::*;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Transaction metadata: the outcome of a transaction and the ledger entries
+/// it touched
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawTransactionMeta")]
+pub struct TransactionMeta {
+    #[serde(rename = "TransactionResult")]
+    pub transaction_result: String,
+    #[serde(rename = "TransactionIndex")]
+    pub transaction_index: u32,
+    #[serde(rename = "AffectedNodes")]
+    pub affected_nodes: Vec<AffectedNode>,
+    /// What a payment actually delivered. `None` for other transaction
+    /// types and for payments in ledgers too old to know it.
+    pub delivered_amount: Option<CurrencyAmount>,
+}
+
+#[derive(Deserialize)]
+struct RawTransactionMeta {
+    #[serde(rename = "TransactionResult")]
+    transaction_result: String,
+    #[serde(rename = "TransactionIndex", default)]
+    transaction_index: u32,
+    #[serde(rename = "AffectedNodes", default)]
+    affected_nodes: Vec<AffectedNode>,
+    #[serde(default)]
+    delivered_amount: Option<CurrencyAmount>,
+    #[serde(rename = "DeliveredAmount", default)]
+    delivered_amount_field: Option<CurrencyAmount>,
+}
+
+impl From<RawTransactionMeta> for TransactionMeta {
+    fn from(raw: RawTransactionMeta) -> Self {
+        Self {
+            transaction_result: raw.transaction_result,
+            transaction_index: raw.transaction_index,
+            affected_nodes: raw.affected_nodes,
+            delivered_amount: raw
+                .delivered_amount
+                .or(raw.delivered_amount_field)
+                .filter(|amount| amount.value() != "unavailable"),
+        }
+    }
+}
+
+/// A ledger entry created, modified or deleted by a transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AffectedNode {
+    CreatedNode(LedgerNode),
+    ModifiedNode(LedgerNode),
+    DeletedNode(LedgerNode),
+}
+
+impl AffectedNode {
+    pub fn node(&self) -> &LedgerNode {
+        match self {
+            AffectedNode::CreatedNode(node) | AffectedNode::ModifiedNode(node) | AffectedNode::DeletedNode(node) => node,
+        }
+    }
+
+    pub fn is_created(&self) -> bool {
+        matches!(self, AffectedNode::CreatedNode(_))
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        matches!(self, AffectedNode::DeletedNode(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LedgerNode {
+    pub ledger_entry_type: String,
+    pub ledger_index: String,
+    /// Fields of a created entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_fields: Option<Value>,
+    /// Fields of a modified entry after the change, or of a deleted entry
+    /// just before deletion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_fields: Option<Value>,
+    /// Previous values of the fields that changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_fields: Option<Value>,
+    #[serde(rename = "PreviousTxnID", default, skip_serializing_if = "Option::is_none")]
+    pub previous_txn_id: Option<TransactionHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_txn_lgr_seq: Option<u32>,
+}
+
+impl LedgerNode {
+    /// The entry after the transaction (`NewFields` or `FinalFields`)
+    pub fn final_entry(&self) -> Result<Option<LedgerEntry>> {
+        self.new_fields
+            .as_ref()
+            .or(self.final_fields.as_ref())
+            .map(|fields| LedgerEntry::parse(&self.ledger_entry_type, fields))
+            .transpose()
+    }
+
+    /// The changed fields as they were before the transaction
+    pub fn previous_entry(&self) -> Result<Option<LedgerEntry>> {
+        self.previous_fields
+            .as_ref()
+            .map(|fields| LedgerEntry::parse(&self.ledger_entry_type, fields))
+            .transpose()
+    }
+}
+
+/// Typed fields of the ledger entries most transactions touch. Every field is
+/// optional because `PreviousFields` only lists what changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerEntry {
+    AccountRoot(AccountRootFields),
+    RippleState(RippleStateFields),
+    Offer(OfferFields),
+    Other(Value),
+}
+
+impl LedgerEntry {
+    pub fn parse(entry_type: &str, fields: &Value) -> Result<Self> {
+        let parse_error = |e: serde_json::Error| XrplError::Deserialization(e.to_string());
+
+        let entry = match entry_type {
+            "AccountRoot" => LedgerEntry::AccountRoot(serde_json::from_value(fields.clone()).map_err(parse_error)?),
+            "RippleState" => LedgerEntry::RippleState(serde_json::from_value(fields.clone()).map_err(parse_error)?),
+            "Offer" => LedgerEntry::Offer(serde_json::from_value(fields.clone()).map_err(parse_error)?),
+            _ => LedgerEntry::Other(fields.clone()),
+        };
+
+        Ok(entry)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct AccountRootFields {
+    pub account: Option<Address>,
+    /// Drops
+    pub balance: Option<Amount>,
+    pub sequence: Option<Sequence>,
+    pub owner_count: Option<u32>,
+    pub flags: Option<u32>,
+}
+
+/// A trust line. `Balance` is from the low account's point of view and its
+/// issuer is a placeholder; the parties are the issuers of the limits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct RippleStateFields {
+    pub balance: Option<IssuedAmount>,
+    pub low_limit: Option<IssuedAmount>,
+    pub high_limit: Option<IssuedAmount>,
+    pub flags: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct OfferFields {
+    pub account: Option<Address>,
+    pub sequence: Option<Sequence>,
+    pub taker_pays: Option<CurrencyAmount>,
+    pub taker_gets: Option<CurrencyAmount>,
+    pub book_directory: Option<String>,
+    pub expiration: Option<u32>,
+    pub flags: Option<u32>,
+}
+
+/// The net change of one account's balance in one currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    pub account: Address,
+    /// "XRP" or the issued currency code
+    pub currency: CurrencyCode,
+    /// The counterparty of the trust line; `None` for XRP
+    pub issuer: Option<Address>,
+    pub value: BalanceDelta,
+}
+
+/// How much a balance changed: exact drops for XRP, since balances above 10
+/// billion XRP need more than the 16 significant digits of an `IouValue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceDelta {
+    Drops(i64),
+    Issued(IouValue),
+}
+
+impl BalanceDelta {
+    pub fn is_zero(&self) -> bool {
+        match self {
+            BalanceDelta::Drops(drops) => *drops == 0,
+            BalanceDelta::Issued(value) => value.is_zero(),
+        }
+    }
+
+    fn checked_add(self, other: BalanceDelta) -> Result<BalanceDelta> {
+        match (self, other) {
+            (BalanceDelta::Drops(a), BalanceDelta::Drops(b)) => a
+                .checked_add(b)
+                .map(BalanceDelta::Drops)
+                .ok_or_else(|| XrplError::InvalidAmount("XRP balance change overflows".to_string()).into()),
+            (BalanceDelta::Issued(a), BalanceDelta::Issued(b)) => Ok(BalanceDelta::Issued(a.checked_add(b)?)),
+            _ => Err(XrplError::InvalidAmount("Cannot add XRP and issued amounts".to_string()).into()),
+        }
+    }
+}
+
+impl fmt::Display for BalanceDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceDelta::Drops(drops) => write!(f, "{}", drops),
+            BalanceDelta::Issued(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl TransactionMeta {
+    pub fn from_json(meta: &Value) -> Result<Self> {
+        serde_json::from_value(meta.clone()).map_err(|e| XrplError::Deserialization(e.to_string()).into())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.transaction_result == "tesSUCCESS"
+    }
+
+    /// Sequences of the tickets a TicketCreate created, in ascending order
+    pub fn created_tickets(&self) -> Vec<u32> {
+        let mut tickets: Vec<u32> = self
+            .affected_nodes
+            .iter()
+            .filter(|node| node.is_created() && node.node().ledger_entry_type == "Ticket")
+            .filter_map(|node| node.node().new_fields.as_ref()?["TicketSequence"].as_u64())
+            .map(|sequence| sequence as u32)
+            .collect();
+        tickets.sort_unstable();
+        tickets
+    }
+
+    /// Net balance changes per account and currency. XRP changes include
+    /// the fee paid by the sender; trust line changes are reported for both
+    /// parties, each with the other as issuer.
+    pub fn balance_changes(&self) -> Result<Vec<BalanceChange>> {
+        let mut changes: Vec<BalanceChange> = Vec::new();
+
+        for affected in &self.affected_nodes {
+            let node = affected.node();
+            let previous = if affected.is_created() {
+                None
+            } else {
+                match node.previous_entry()? {
+                    Some(previous) => Some(previous),
+                    None => continue,
+                }
+            };
+
+            match (node.final_entry()?, previous) {
+                (Some(LedgerEntry::AccountRoot(current)), previous) => {
+                    let previous_balance = match previous {
+                        Some(LedgerEntry::AccountRoot(previous)) => match previous.balance {
+                            Some(balance) => parse_drops(&balance)?,
+                            None => continue,
+                        },
+                        _ => 0,
+                    };
+
+                    let (Some(account), Some(balance)) = (current.account, current.balance) else {
+                        continue;
+                    };
+                    let delta = parse_drops(&balance)? - previous_balance;
+                    add_change(&mut changes, &account, "XRP", None, BalanceDelta::Drops(delta))?;
+                }
+                (Some(LedgerEntry::RippleState(current)), previous) => {
+                    let previous_balance = match previous {
+                        Some(LedgerEntry::RippleState(previous)) => match previous.balance {
+                            Some(balance) => balance.value.parse::<IouValue>()?,
+                            None => continue,
+                        },
+                        _ => IouValue::ZERO,
+                    };
+
+                    let (Some(balance), Some(low), Some(high)) = (current.balance, current.low_limit, current.high_limit)
+                    else {
+                        continue;
+                    };
+                    let delta = balance.value.parse::<IouValue>()?.checked_sub(previous_balance)?;
+                    add_change(&mut changes, &low.issuer, &balance.currency, Some(&high.issuer), BalanceDelta::Issued(delta))?;
+                    add_change(&mut changes, &high.issuer, &balance.currency, Some(&low.issuer), BalanceDelta::Issued(-delta))?;
+                }
+                _ => {}
+            }
+        }
+
+        changes.retain(|change| !change.value.is_zero());
+        Ok(changes)
+    }
+}
+
+/// XRP balances as exact drops; all XRP ever issued fits in an `i64`
+fn parse_drops(drops: &str) -> Result<i64> {
+    drops
+        .parse()
+        .map_err(|_| XrplError::InvalidAmount(format!("Invalid drops: {}", drops)).into())
+}
+
+fn add_change(
+    changes: &mut Vec<BalanceChange>,
+    account: &str,
+    currency: &str,
+    issuer: Option<&str>,
+    delta: BalanceDelta,
+) -> Result<()> {
+    let existing = changes.iter_mut().find(|change| {
+        change.account == account && change.currency == currency && change.issuer.as_deref() == issuer
+    });
+
+    match existing {
+        Some(change) => change.value = change.value.checked_add(delta)?,
+        None => changes.push(BalanceChange {
+            account: account.to_string(),
+            currency: currency.to_string(),
+            issuer: issuer.map(str::to_string),
+            value: delta,
+        }),
+    }
+
+    Ok(())
+}
+
+impl TransactionResult {
+    /// The typed metadata, if the server included it
+    pub fn transaction_meta(&self) -> Result<Option<TransactionMeta>> {
+        self.meta.as_ref().map(TransactionMeta::from_json).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SENDER: &str = "rSender1234567890123456789012345";
+    const RECEIVER: &str = "rReceiver12345678901234567890123";
+    const ISSUER: &str = "rIssuer123456789012345678901234";
+
+    fn usd(value: &str) -> Value {
+        json!({ "currency": "USD", "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji", "value": value })
+    }
+
+    fn payment_meta() -> Value {
+        json!({
+            "TransactionIndex": 4,
+            "TransactionResult": "tesSUCCESS",
+            "delivered_amount": "1000000",
+            "AffectedNodes": [
+                { "ModifiedNode": {
+                    "LedgerEntryType": "AccountRoot",
+                    "LedgerIndex": "A1",
+                    "FinalFields": { "Account": SENDER, "Balance": "98999988", "Sequence": 6, "OwnerCount": 0, "Flags": 0 },
+                    "PreviousFields": { "Balance": "100000000", "Sequence": 5 },
+                    "PreviousTxnID": "FF",
+                    "PreviousTxnLgrSeq": 10
+                }},
+                { "CreatedNode": {
+                    "LedgerEntryType": "AccountRoot",
+                    "LedgerIndex": "A2",
+                    "NewFields": { "Account": RECEIVER, "Balance": "1000000", "Sequence": 11 }
+                }},
+                { "ModifiedNode": {
+                    "LedgerEntryType": "DirectoryNode",
+                    "LedgerIndex": "D1",
+                    "FinalFields": { "Owner": SENDER }
+                }}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_parse_meta() {
+        let meta = TransactionMeta::from_json(&payment_meta()).unwrap();
+
+        assert!(meta.is_success());
+        assert_eq!(meta.transaction_index, 4);
+        assert_eq!(meta.delivered_amount, Some(CurrencyAmount::xrp_drops(1000000)));
+        assert_eq!(meta.affected_nodes.len(), 3);
+        assert!(meta.affected_nodes[1].is_created());
+
+        let node = meta.affected_nodes[0].node();
+        assert_eq!(node.previous_txn_id.as_deref(), Some("FF"));
+        match node.final_entry().unwrap() {
+            Some(LedgerEntry::AccountRoot(fields)) => assert_eq!(fields.sequence, Some(6)),
+            other => panic!("unexpected entry {:?}", other),
+        }
+        assert!(matches!(meta.affected_nodes[2].node().final_entry().unwrap(), Some(LedgerEntry::Other(_))));
+    }
+
+    #[test]
+    fn test_unavailable_delivered_amount() {
+        let meta = TransactionMeta::from_json(&json!({
+            "TransactionResult": "tesSUCCESS",
+            "TransactionIndex": 0,
+            "AffectedNodes": [],
+            "delivered_amount": "unavailable"
+        }))
+        .unwrap();
+
+        assert_eq!(meta.delivered_amount, None);
+    }
+
+    #[test]
+    fn test_xrp_balance_changes_include_fee() {
+        let changes = TransactionMeta::from_json(&payment_meta()).unwrap().balance_changes().unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].account, SENDER);
+        assert_eq!(changes[0].currency, "XRP");
+        assert_eq!(changes[0].value, BalanceDelta::Drops(-1000012));
+        assert_eq!(changes[1].account, RECEIVER);
+        assert_eq!(changes[1].value, BalanceDelta::Drops(1000000));
+    }
+
+    #[test]
+    fn test_large_xrp_balances_are_exact() {
+        let meta = TransactionMeta::from_json(&json!({
+            "TransactionIndex": 0,
+            "TransactionResult": "tesSUCCESS",
+            "AffectedNodes": [
+                { "ModifiedNode": {
+                    "LedgerEntryType": "AccountRoot",
+                    "LedgerIndex": "A1",
+                    "FinalFields": { "Account": SENDER, "Balance": "54999999999999988", "Sequence": 6 },
+                    "PreviousFields": { "Balance": "55000000000000000", "Sequence": 5 }
+                }}
+            ]
+        }))
+        .unwrap();
+
+        let changes = meta.balance_changes().unwrap();
+        assert_eq!(changes[0].value, BalanceDelta::Drops(-12));
+    }
+
+    #[test]
+    fn test_trust_line_balance_changes() {
+        // Low account RECEIVER holds USD issued by the high account ISSUER
+        let meta = TransactionMeta::from_json(&json!({
+            "TransactionIndex": 0,
+            "TransactionResult": "tesSUCCESS",
+            "AffectedNodes": [
+                { "ModifiedNode": {
+                    "LedgerEntryType": "RippleState",
+                    "LedgerIndex": "R1",
+                    "FinalFields": {
+                        "Balance": usd("15.5"),
+                        "LowLimit": { "currency": "USD", "issuer": RECEIVER, "value": "1000" },
+                        "HighLimit": { "currency": "USD", "issuer": ISSUER, "value": "0" },
+                        "Flags": 131072
+                    },
+                    "PreviousFields": { "Balance": usd("10") }
+                }}
+            ]
+        }))
+        .unwrap();
+
+        let changes = meta.balance_changes().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].account, RECEIVER);
+        assert_eq!(changes[0].issuer.as_deref(), Some(ISSUER));
+        assert_eq!(changes[0].value.to_string(), "5.5");
+        assert_eq!(changes[1].account, ISSUER);
+        assert_eq!(changes[1].issuer.as_deref(), Some(RECEIVER));
+        assert_eq!(changes[1].value.to_string(), "-5.5");
+    }
+
+    #[test]
+    fn test_created_tickets() {
+        let meta = TransactionMeta::from_json(&json!({
+            "TransactionIndex": 0,
+            "TransactionResult": "tesSUCCESS",
+            "AffectedNodes": [
+                { "CreatedNode": { "LedgerEntryType": "Ticket", "LedgerIndex": "T2", "NewFields": { "Account": SENDER, "TicketSequence": 12 } } },
+                { "CreatedNode": { "LedgerEntryType": "Ticket", "LedgerIndex": "T1", "NewFields": { "Account": SENDER, "TicketSequence": 11 } } },
+                { "ModifiedNode": { "LedgerEntryType": "AccountRoot", "LedgerIndex": "A1", "FinalFields": { "TicketCount": 2 } } }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(meta.created_tickets(), vec![11, 12]);
+    }
+}
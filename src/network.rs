@@ -0,0 +1,144 @@
+/// Description of an XRPL network: where to reach it and the parameters
+/// that differ between networks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    /// Human readable name, e.g. "mainnet" or "devnet"
+    pub name: String,
+    /// Network ID as used in the `NetworkID` transaction field
+    pub network_id: u32,
+    /// Default JSON-RPC endpoint
+    pub rpc_url: String,
+    /// Faucet endpoint for funding accounts, if the network has one
+    pub faucet_url: Option<String>,
+    /// Base reserve in drops, used until `server_info` says otherwise
+    pub base_reserve_drops: u64,
+    /// Owner reserve per owned object in drops
+    pub owner_reserve_drops: u64,
+    /// Set only by `Network::mainnet()`, since standalone and private
+    /// networks also use network ID 0
+    mainnet_preset: bool,
+}
+
+impl Network {
+    /// Network ID of the XRP Ledger mainnet
+    pub const MAINNET_ID: u32 = 0;
+    /// Network ID of the public testnet
+    pub const TESTNET_ID: u32 = 1;
+    /// Network ID of the public devnet
+    pub const DEVNET_ID: u32 = 2;
+
+    /// Networks with an ID above this value must set `NetworkID` on every transaction
+    pub const LEGACY_NETWORK_ID_LIMIT: u32 = 1024;
+
+    const DEFAULT_BASE_RESERVE_DROPS: u64 = 1_000_000;
+    const DEFAULT_OWNER_RESERVE_DROPS: u64 = 200_000;
+
+    /// The XRP Ledger mainnet
+    pub fn mainnet() -> Self {
+        Self {
+            name: "mainnet".to_string(),
+            network_id: Self::MAINNET_ID,
+            rpc_url: "https://xrplcluster.com".to_string(),
+            faucet_url: None,
+            base_reserve_drops: Self::DEFAULT_BASE_RESERVE_DROPS,
+            owner_reserve_drops: Self::DEFAULT_OWNER_RESERVE_DROPS,
+            mainnet_preset: true,
+        }
+    }
+
+    /// The public XRPL testnet
+    pub fn testnet() -> Self {
+        Self {
+            name: "testnet".to_string(),
+            network_id: Self::TESTNET_ID,
+            rpc_url: "https://s.altnet.rippletest.net:51234".to_string(),
+            faucet_url: Some("https://faucet.altnet.rippletest.net/accounts".to_string()),
+            base_reserve_drops: Self::DEFAULT_BASE_RESERVE_DROPS,
+            owner_reserve_drops: Self::DEFAULT_OWNER_RESERVE_DROPS,
+            mainnet_preset: false,
+        }
+    }
+
+    /// The public XRPL devnet
+    pub fn devnet() -> Self {
+        Self {
+            name: "devnet".to_string(),
+            network_id: Self::DEVNET_ID,
+            rpc_url: "https://s.devnet.rippletest.net:51234".to_string(),
+            faucet_url: Some("https://faucet.devnet.rippletest.net/accounts".to_string()),
+            base_reserve_drops: Self::DEFAULT_BASE_RESERVE_DROPS,
+            owner_reserve_drops: Self::DEFAULT_OWNER_RESERVE_DROPS,
+            mainnet_preset: false,
+        }
+    }
+
+    /// A local rippled running in standalone mode
+    pub fn standalone(rpc_url: &str) -> Self {
+        Self::custom("standalone", Self::MAINNET_ID, rpc_url)
+    }
+
+    /// Any other network, e.g. a private rippled or Clio deployment
+    pub fn custom(name: &str, network_id: u32, rpc_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            network_id,
+            rpc_url: rpc_url.to_string(),
+            faucet_url: None,
+            base_reserve_drops: Self::DEFAULT_BASE_RESERVE_DROPS,
+            owner_reserve_drops: Self::DEFAULT_OWNER_RESERVE_DROPS,
+            mainnet_preset: false,
+        }
+    }
+
+    pub fn with_faucet(mut self, faucet_url: &str) -> Self {
+        self.faucet_url = Some(faucet_url.to_string());
+        self
+    }
+
+    pub fn with_reserves(mut self, base_reserve_drops: u64, owner_reserve_drops: u64) -> Self {
+        self.base_reserve_drops = base_reserve_drops;
+        self.owner_reserve_drops = owner_reserve_drops;
+        self
+    }
+
+    /// Only a network built with `Network::mainnet()` is mainnet, whatever
+    /// the name or ID of a standalone or custom one
+    pub fn is_mainnet(&self) -> bool {
+        self.mainnet_preset && self.network_id == Self::MAINNET_ID
+    }
+
+    pub fn is_testnet(&self) -> bool {
+        !self.is_mainnet()
+    }
+
+    /// Whether transactions for this network must carry a `NetworkID` field
+    pub fn requires_network_id(&self) -> bool {
+        self.network_id > Self::LEGACY_NETWORK_ID_LIMIT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predefined_networks() {
+        assert!(Network::mainnet().is_mainnet());
+        assert!(Network::testnet().is_testnet());
+        assert!(Network::devnet().faucet_url.is_some());
+        assert!(Network::standalone("http://localhost:5005").is_testnet());
+        assert!(Network::custom("mainnet", Network::MAINNET_ID, "http://localhost:5005").is_testnet());
+        assert!(Network::custom("mainnet", Network::TESTNET_ID, "http://localhost:5005").is_testnet());
+
+        let mut cluster = Network::mainnet();
+        cluster.rpc_url = "https://s1.ripple.com:51234".to_string();
+        assert!(cluster.is_mainnet());
+    }
+
+    #[test]
+    fn test_network_id_requirement() {
+        assert!(!Network::testnet().requires_network_id());
+        assert!(!Network::custom("edge", 1024, "http://localhost:5005").requires_network_id());
+        assert!(Network::custom("sidechain", 21337, "http://localhost:5005").requires_network_id());
+    }
+}
@@ -0,0 +1,456 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::signing::TransactionSigner;
+use crate::types::*;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A signed transaction recorded before it was submitted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub hash: TransactionHash,
+    pub tx_blob: String,
+    pub account: Address,
+    pub sequence: u32,
+    /// Validated ledger when the entry was recorded; the transaction cannot
+    /// be in an earlier one
+    pub first_ledger: u32,
+    pub last_ledger_sequence: u32,
+    /// Caller's identifier for what the transaction is for, e.g. a payout row
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub status: OutboxStatus,
+}
+
+/// What is known about an outbox entry on the ledger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OutboxStatus {
+    /// Possibly submitted, not yet in a validated ledger
+    Pending,
+    /// In a validated ledger; the result may still be a `tec` failure
+    Validated { ledger_index: u32, result: String },
+    /// LastLedgerSequence passed without it being validated, so it never will be
+    Expired,
+}
+
+impl OutboxEntry {
+    /// Whether the entry has reached a state that can no longer change
+    pub fn is_final(&self) -> bool {
+        self.status != OutboxStatus::Pending
+    }
+}
+
+/// Where an `Outbox` persists its entries
+pub trait OutboxStore: Send + Sync {
+    fn load(&self) -> Result<Vec<OutboxEntry>>;
+    fn save(&self, entries: &[OutboxEntry]) -> Result<()>;
+}
+
+/// Keeps the entries in memory only; useful for tests
+#[derive(Default)]
+pub struct MemoryOutboxStore {
+    entries: Mutex<Vec<OutboxEntry>>,
+}
+
+impl MemoryOutboxStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OutboxStore for MemoryOutboxStore {
+    fn load(&self) -> Result<Vec<OutboxEntry>> {
+        Ok(self.entries.lock().unwrap().clone())
+    }
+
+    fn save(&self, entries: &[OutboxEntry]) -> Result<()> {
+        *self.entries.lock().unwrap() = entries.to_vec();
+        Ok(())
+    }
+}
+
+/// Stores the entries as JSON in a file, replaced atomically and synced to disk
+pub struct FileOutboxStore {
+    path: PathBuf,
+}
+
+impl FileOutboxStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl OutboxStore for FileOutboxStore {
+    fn load(&self) -> Result<Vec<OutboxEntry>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| XrplError::Deserialization(format!("Invalid outbox: {}", e)).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, entries: &[OutboxEntry]) -> Result<()> {
+        let temp_path = self.path.with_extension("tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(entries)?)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        // Make the rename itself survive a power loss
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(directory)?.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Records every signed transaction before it is submitted, so that after a
+/// crash it can be resubmitted or resolved instead of being lost.
+///
+/// Entries are only resolved as expired once the validated ledger is past
+/// their LastLedgerSequence, which is therefore required, and a node with
+/// complete history for the range they could be in does not have them.
+pub struct Outbox {
+    store: Box<dyn OutboxStore>,
+    entries: Mutex<Vec<OutboxEntry>>,
+}
+
+impl Outbox {
+    /// Open an outbox with the entries already in `store`
+    pub fn open(store: Box<dyn OutboxStore>) -> Result<Self> {
+        let entries = store.load()?;
+        Ok(Self {
+            store,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Open an outbox persisted in a file
+    pub fn open_file(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::open(Box::new(FileOutboxStore::new(path)))
+    }
+
+    pub fn entries(&self) -> Vec<OutboxEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Entries not yet validated or expired
+    pub fn pending(&self) -> Vec<OutboxEntry> {
+        self.entries().into_iter().filter(|entry| !entry.is_final()).collect()
+    }
+
+    /// Persist a signed transaction, then submit it
+    pub async fn submit(&self, client: &XrplClient, signed_tx: &SignedTransaction) -> Result<TransactionResult> {
+        self.record(client, signed_tx).await?;
+        client.submit_transaction(signed_tx).await
+    }
+
+    /// Persist a signed transaction without submitting it. Recording the same
+    /// transaction twice keeps a single entry.
+    pub async fn record(&self, client: &XrplClient, signed_tx: &SignedTransaction) -> Result<OutboxEntry> {
+        self.insert(signed_tx, client.get_ledger_index().await?, None)
+    }
+
+    /// Like `record`, tagging the entry so it can be found with `entries_for`
+    pub async fn record_with_reference(
+        &self,
+        client: &XrplClient,
+        signed_tx: &SignedTransaction,
+        reference: &str,
+    ) -> Result<OutboxEntry> {
+        self.insert(signed_tx, client.get_ledger_index().await?, Some(reference))
+    }
+
+    /// Entries recorded with this reference, oldest first
+    pub fn entries_for(&self, reference: &str) -> Vec<OutboxEntry> {
+        self.entries()
+            .into_iter()
+            .filter(|entry| entry.reference.as_deref() == Some(reference))
+            .collect()
+    }
+
+    pub(crate) fn insert(&self, signed_tx: &SignedTransaction, first_ledger: u32, reference: Option<&str>) -> Result<OutboxEntry> {
+        let last_ledger_sequence = signed_tx.tx_json.last_ledger_sequence.ok_or_else(|| {
+            XrplError::InvalidTransaction("LastLedgerSequence is required to resolve an outbox entry".to_string())
+        })?;
+        let entry = OutboxEntry {
+            hash: TransactionSigner::transaction_hash(&signed_tx.tx_blob)?,
+            tx_blob: signed_tx.tx_blob.clone(),
+            account: signed_tx.tx_json.account.clone(),
+            sequence: signed_tx.tx_json.sequence,
+            first_ledger,
+            last_ledger_sequence,
+            reference: reference.map(str::to_string),
+            status: OutboxStatus::Pending,
+        };
+
+        self.update(|entries| {
+            if !entries.iter().any(|existing| existing.hash == entry.hash) {
+                entries.push(entry.clone());
+            }
+        })?;
+
+        Ok(entry)
+    }
+
+    /// Check every pending entry once against the ledger and persist any
+    /// that became final. Returns the entries resolved by this call.
+    pub async fn poll(&self, client: &XrplClient) -> Result<Vec<OutboxEntry>> {
+        // Read before looking up the transactions, so an entry not found is
+        // only expired if it was already too late at that ledger
+        let validated_ledger = client.get_ledger_index().await?;
+        let mut resolved = Vec::new();
+
+        for entry in self.pending() {
+            let status = match lookup(client, &entry).await? {
+                Lookup::Validated { ledger_index, result } => OutboxStatus::Validated { ledger_index, result },
+                Lookup::Absent if validated_ledger > entry.last_ledger_sequence => OutboxStatus::Expired,
+                _ => continue,
+            };
+
+            resolved.push(OutboxEntry { status, ..entry });
+        }
+
+        if !resolved.is_empty() {
+            self.update(|entries| {
+                for entry in entries.iter_mut() {
+                    if let Some(update) = resolved.iter().find(|update| update.hash == entry.hash) {
+                        entry.status = update.status.clone();
+                    }
+                }
+            })?;
+        }
+
+        Ok(resolved)
+    }
+
+    /// After a restart: submit every pending entry again, then poll until all
+    /// of them are validated or expired. Returns the resolved entries.
+    pub async fn recover(&self, client: &XrplClient, poll_interval: Duration) -> Result<Vec<OutboxEntry>> {
+        let mut resolved = self.poll(client).await?;
+        self.resubmit_pending(client).await;
+        resolved.extend(self.wait(client, poll_interval).await?);
+        Ok(resolved)
+    }
+
+    /// Submit every pending entry again, in case it never reached the network
+    pub async fn resubmit_pending(&self, client: &XrplClient) {
+        for entry in self.pending() {
+            let signed_tx = SignedTransaction {
+                tx_blob: entry.tx_blob.clone(),
+                tx_json: Transaction {
+                    account: entry.account.clone(),
+                    sequence: entry.sequence,
+                    last_ledger_sequence: Some(entry.last_ledger_sequence),
+                    ..Transaction::default()
+                },
+            };
+            // The result does not matter: an entry already applied is
+            // rejected as a duplicate and found by the next poll
+            let _ = client.submit_transaction(&signed_tx).await;
+        }
+    }
+
+    /// Poll until no entry is pending. Returns the entries resolved meanwhile.
+    pub async fn wait(&self, client: &XrplClient, poll_interval: Duration) -> Result<Vec<OutboxEntry>> {
+        let mut resolved = Vec::new();
+        while !self.pending().is_empty() {
+            tokio::time::sleep(poll_interval).await;
+            resolved.extend(self.poll(client).await?);
+        }
+        Ok(resolved)
+    }
+
+    /// Remove final entries from the outbox and return them
+    pub fn prune(&self) -> Result<Vec<OutboxEntry>> {
+        let mut pruned = Vec::new();
+        self.update(|entries| {
+            let (done, pending) = entries.drain(..).partition(OutboxEntry::is_final);
+            pruned = done;
+            *entries = pending;
+        })?;
+        Ok(pruned)
+    }
+
+    /// Apply a change and persist the result, leaving memory untouched if saving fails
+    fn update(&self, change: impl FnOnce(&mut Vec<OutboxEntry>)) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut updated = entries.clone();
+        change(&mut updated);
+        self.store.save(&updated)?;
+        *entries = updated;
+        Ok(())
+    }
+}
+
+/// rippled searches at most this many ledgers in one bounded `tx` request
+const MAX_TX_LEDGER_RANGE: u32 = 1000;
+
+/// What `tx` knows about an entry within the ledgers it could be in
+enum Lookup {
+    Validated { ledger_index: u32, result: String },
+    /// Not validated yet, or the node lacks some ledgers of the range
+    Unknown,
+    /// In none of the ledgers of the range, all of which the node has
+    Absent,
+}
+
+async fn lookup(client: &XrplClient, entry: &OutboxEntry) -> Result<Lookup> {
+    let mut min_ledger = entry.first_ledger;
+
+    while min_ledger <= entry.last_ledger_sequence {
+        let max_ledger = entry
+            .last_ledger_sequence
+            .min(min_ledger.saturating_add(MAX_TX_LEDGER_RANGE - 1));
+        let response = client
+            .make_request(&json!({
+                "method": "tx",
+                "params": [{
+                    "transaction": entry.hash,
+                    "binary": false,
+                    "min_ledger": min_ledger,
+                    "max_ledger": max_ledger
+                }]
+            }))
+            .await?;
+        let result = &response["result"];
+
+        match result["error"].as_str() {
+            Some("txnNotFound") if result["searched_all"] == true => {}
+            Some("txnNotFound") => return Ok(Lookup::Unknown),
+            Some(error) => return Err(XrplError::ApiError(error.to_string()).into()),
+            None if result["validated"] == true => {
+                let transaction = TransactionMetadata::from_response(result)?;
+                return Ok(Lookup::Validated {
+                    ledger_index: transaction.ledger_index.unwrap_or_default(),
+                    result: transaction
+                        .meta
+                        .map(|meta| meta.transaction_result)
+                        .unwrap_or_default(),
+                });
+            }
+            None => return Ok(Lookup::Unknown),
+        }
+
+        min_ledger = max_ledger + 1;
+    }
+
+    Ok(Lookup::Absent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::test_support::{client, submit_result, validated};
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    const SECRET: &str = "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2";
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    fn signed(sequence: u32) -> SignedTransaction {
+        let transaction = Transaction {
+            account: ACCOUNT.to_string(),
+            destination: "rReceiver1234567890123456789012".to_string(),
+            amount: "1000".to_string(),
+            currency: "XRP".to_string(),
+            sequence,
+            last_ledger_sequence: Some(110),
+            ..Transaction::default()
+        };
+        TransactionSigner::with_network(Network::testnet())
+            .sign_transaction(SECRET, &transaction)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_records_before_submitting() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on_error("submit", "noNetwork");
+        let xrpl = client(&mock);
+        let outbox = Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap();
+
+        assert!(outbox.submit(&xrpl, &signed(1)).await.is_err());
+        outbox.record(&xrpl, &signed(1)).await.unwrap();
+
+        let pending = outbox.pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sequence, 1);
+        assert_eq!(pending[0].first_ledger, 100);
+        assert_eq!(pending[0].last_ledger_sequence, 110);
+
+        let mut no_expiry = signed(2);
+        no_expiry.tx_json.last_ledger_sequence = None;
+        assert!(outbox.record(&xrpl, &no_expiry).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recover_after_restart() {
+        let applied = signed(1);
+        let lost = signed(2);
+        let applied_hash = TransactionSigner::transaction_hash(&applied.tx_blob).unwrap();
+
+        let mock = Arc::new(MockTransport::new());
+        mock.on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 111 }));
+        mock.on("submit", submit_result("tefPAST_SEQ"));
+        mock.on_params("tx", json!({ "transaction": applied_hash }), validated(&applied_hash, ACCOUNT, 1, 105));
+        mock.on("tx", json!({ "error": "txnNotFound", "searched_all": true }));
+
+        let path = std::env::temp_dir().join(format!("xrpl-outbox-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let outbox = Outbox::open_file(&path).unwrap();
+            outbox.record(&client(&mock), &applied).await.unwrap();
+            outbox.record(&client(&mock), &lost).await.unwrap();
+        }
+
+        let outbox = Outbox::open_file(&path).unwrap();
+        let resolved = outbox.recover(&client(&mock), Duration::from_millis(1)).await.unwrap();
+
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(
+            resolved[0].status,
+            OutboxStatus::Validated { ledger_index: 105, result: "tesSUCCESS".to_string() }
+        );
+        assert_eq!(resolved[1].status, OutboxStatus::Expired);
+        assert_eq!(mock.request_count("submit"), 1);
+        let lookup = mock.requests_for("tx").pop().unwrap();
+        assert_eq!((lookup["min_ledger"].clone(), lookup["max_ledger"].clone()), (json!(100), json!(110)));
+
+        let reopened = Outbox::open_file(&path).unwrap();
+        assert!(reopened.pending().is_empty());
+        assert_eq!(reopened.prune().unwrap().len(), 2);
+        assert!(Outbox::open_file(&path).unwrap().entries().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_history_is_not_expired() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 500 }));
+        mock.on("tx", json!({ "error": "txnNotFound", "searched_all": false }));
+        let xrpl = client(&mock);
+        let outbox = Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap();
+        outbox.record(&xrpl, &signed(1)).await.unwrap();
+
+        assert!(outbox.poll(&xrpl).await.unwrap().is_empty());
+        assert_eq!(outbox.pending().len(), 1);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::types::*;
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+
+/// Walks a marker-paginated list method (`account_lines`, `ledger_data`, ...).
+///
+/// The first page is requested from the given ledger (`validated` unless set
+/// with `ledger_index`) and every following page is pinned to the ledger
+/// index the server answered with, so all pages describe the same state.
+/// Pages read from the open ledger cannot be pinned.
+pub struct Paginator<'a> {
+    client: &'a XrplClient,
+    method: String,
+    params: Value,
+    items_field: String,
+    marker: Option<Value>,
+    pinned_ledger: Option<u32>,
+    finished: bool,
+}
+
+impl<'a> Paginator<'a> {
+    /// Paginate `method` called with `params`, collecting the array found at
+    /// `items_field` of each result
+    pub fn new(client: &'a XrplClient, method: &str, params: Value, items_field: &str) -> Self {
+        Self {
+            client,
+            method: method.to_string(),
+            params,
+            items_field: items_field.to_string(),
+            marker: None,
+            pinned_ledger: None,
+            finished: false,
+        }
+    }
+
+    /// Read from a specific ledger instead of the latest validated one
+    pub fn ledger_index(mut self, ledger_index: u32) -> Self {
+        self.params["ledger_index"] = json!(ledger_index);
+        self
+    }
+
+    /// Page size hint; servers clamp it to their own bounds
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.params["limit"] = json!(limit);
+        self
+    }
+
+    /// Set an additional request parameter
+    pub fn param(mut self, name: &str, value: Value) -> Self {
+        self.params[name] = value;
+        self
+    }
+
+    /// The ledger all pages are read from, known after the first page
+    pub fn pinned_ledger(&self) -> Option<u32> {
+        self.pinned_ledger
+    }
+
+    /// Fetch the next page, or `None` once the last page has been returned
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Value>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut params = self.params.clone();
+        if let Some(ledger_index) = self.pinned_ledger {
+            params["ledger_index"] = json!(ledger_index);
+        } else if params.get("ledger_index").is_none() && params.get("ledger_hash").is_none() {
+            params["ledger_index"] = json!("validated");
+        }
+        if let Some(marker) = &self.marker {
+            params["marker"] = marker.clone();
+        }
+
+        let request = json!({
+            "method": self.method,
+            "params": [params]
+        });
+
+        let response: Value = self.client.make_request(&request).await?;
+        let result = &response["result"];
+
+        if let Some(error) = result["error"].as_str() {
+            return Err(XrplError::ApiError(error.to_string()).into());
+        }
+
+        // Only a closed ledger can be pinned: the open ledger behind
+        // `ledger_current_index` keeps changing under the same index
+        if self.pinned_ledger.is_none() {
+            self.pinned_ledger = result["ledger_index"].as_u64().map(|index| index as u32);
+        }
+
+        let marker = result.get("marker").filter(|marker| !marker.is_null()).cloned();
+        if marker.is_some() && marker == self.marker {
+            return Err(XrplError::ApiError(format!("{} returned the same marker twice", self.method)).into());
+        }
+
+        self.finished = marker.is_none();
+        self.marker = marker;
+
+        Ok(Some(result[self.items_field.as_str()].as_array().cloned().unwrap_or_default()))
+    }
+
+    /// Fetch every page and concatenate the items
+    pub async fn collect_all(mut self) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        while let Some(page) = self.next_page().await? {
+            items.extend(page);
+        }
+        Ok(items)
+    }
+
+    /// Items of every page, fetching pages as the stream is consumed. Ends
+    /// after the first error.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Value>> + 'a {
+        stream::unfold((self, VecDeque::new()), |(mut paginator, mut queue)| async move {
+            loop {
+                if let Some(item) = queue.pop_front() {
+                    return Some((Ok(item), (paginator, queue)));
+                }
+
+                match paginator.next_page().await {
+                    Ok(Some(page)) => queue.extend(page),
+                    Ok(None) => return None,
+                    Err(e) => {
+                        paginator.finished = true;
+                        return Some((Err(e), (paginator, queue)));
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl XrplClient {
+    /// Trust lines of an account (`lines`)
+    pub fn account_lines(&self, address: &str) -> Paginator<'_> {
+        Paginator::new(self, "account_lines", json!({ "account": address }), "lines")
+    }
+
+    /// Ledger entries owned by an account (`account_objects`)
+    pub fn account_objects(&self, address: &str) -> Paginator<'_> {
+        Paginator::new(self, "account_objects", json!({ "account": address }), "account_objects")
+    }
+
+    /// Open offers of an account (`offers`)
+    pub fn account_offers(&self, address: &str) -> Paginator<'_> {
+        Paginator::new(self, "account_offers", json!({ "account": address }), "offers")
+    }
+
+    /// Payment channels where the account is the source (`channels`)
+    pub fn account_channels(&self, address: &str) -> Paginator<'_> {
+        Paginator::new(self, "account_channels", json!({ "account": address }), "channels")
+    }
+
+    /// NFTs held by an account (`account_nfts`)
+    pub fn account_nfts(&self, address: &str) -> Paginator<'_> {
+        Paginator::new(self, "account_nfts", json!({ "account": address }), "account_nfts")
+    }
+
+    /// Offers of an order book (`offers`)
+    pub fn book_offers(&self, taker_gets: &Issue, taker_pays: &Issue) -> Paginator<'_> {
+        Paginator::new(
+            self,
+            "book_offers",
+            json!({ "taker_gets": taker_gets, "taker_pays": taker_pays }),
+            "offers",
+        )
+    }
+
+    /// Every ledger entry in a ledger (`state`)
+    pub fn ledger_data(&self) -> Paginator<'_> {
+        Paginator::new(self, "ledger_data", json!({}), "state")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_pages_are_pinned_to_first_ledger() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", json!({
+            "ledger_index": 500,
+            "account_objects": [{ "index": "A" }, { "index": "B" }],
+            "marker": "M1"
+        }))
+        .on_params("account_objects", json!({ "marker": "M1" }), json!({
+            "ledger_index": 500,
+            "account_objects": [{ "index": "C" }]
+        }));
+        let client = client(&mock);
+
+        let mut paginator = client.account_objects("rOwner").limit(2);
+        assert_eq!(paginator.next_page().await.unwrap().unwrap().len(), 2);
+        assert_eq!(paginator.pinned_ledger(), Some(500));
+        assert_eq!(paginator.next_page().await.unwrap().unwrap().len(), 1);
+        assert!(paginator.next_page().await.unwrap().is_none());
+
+        let requests = mock.requests_for("account_objects");
+        assert_eq!(requests[0]["ledger_index"], "validated");
+        assert_eq!(requests[0]["limit"], 2);
+        assert_eq!(requests[1]["ledger_index"], 500);
+        assert_eq!(requests[1]["marker"], "M1");
+    }
+
+    #[tokio::test]
+    async fn test_stream_and_repeated_marker() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("ledger_data", json!({
+            "ledger_index": 7,
+            "state": [{ "index": "A" }],
+            "marker": "SAME"
+        }));
+        let client = client(&mock);
+
+        let items: Vec<Result<Value>> = client.ledger_data().ledger_index(7).into_stream().collect().await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap()["index"], "A");
+        assert!(items[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_book_offers_params() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("book_offers", json!({ "ledger_index": 3, "offers": [] }));
+        let client = client(&mock);
+
+        let offers = client
+            .book_offers(&Issue::xrp(), &Issue::issued("USD", "rIssuer"))
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert!(offers.is_empty());
+        let params = &mock.requests_for("book_offers")[0];
+        assert_eq!(params["taker_gets"], json!({ "currency": "XRP" }));
+        assert_eq!(params["taker_pays"]["issuer"], "rIssuer");
+    }
+
+    #[tokio::test]
+    async fn test_open_ledger_is_not_pinned() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_lines", json!({ "ledger_current_index": 501, "lines": [{}], "marker": "M1" }))
+            .on_params("account_lines", json!({ "marker": "M1" }), json!({ "ledger_current_index": 502, "lines": [{}] }));
+        let client = client(&mock);
+
+        let lines = client
+            .account_lines("rOwner")
+            .param("ledger_index", json!("current"))
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(lines.len(), 2);
+        let requests = mock.requests_for("account_lines");
+        assert_eq!(requests[1]["ledger_index"], "current");
+    }
+}
@@ -0,0 +1,778 @@
+use crate::amount::IouValue;
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::fee::TransactionCost;
+use crate::outbox::{Outbox, OutboxEntry, OutboxStatus};
+use crate::precheck::PaymentPrecheck;
+use crate::sequence::SequenceManager;
+use crate::signing::TransactionSigner;
+use crate::transaction::TransactionValidator;
+use crate::types::*;
+use anyhow::Result;
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Ledgers each payout transaction stays valid for
+pub const PAYOUT_LEDGER_WINDOW: u32 = 20;
+
+/// Rows signed and submitted at once by default
+pub const PAYOUT_CHUNK_SIZE: usize = 10;
+
+/// One payment of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutRow {
+    /// Position in the batch, starting at 1
+    pub row: usize,
+    pub destination: Address,
+    pub destination_tag: Option<u32>,
+    /// Drops for XRP, otherwise the token amount
+    pub amount: Amount,
+    pub currency: CurrencyCode,
+    pub issuer: Option<Address>,
+    /// Plain text memo
+    pub memo: Option<String>,
+}
+
+impl PayoutRow {
+    /// Identifies the row's transactions in the outbox across runs
+    pub fn reference(&self) -> String {
+        format!("payout:{}:{}:{}:{}", self.row, self.destination, self.amount, self.currency)
+    }
+}
+
+/// A list of payments to make from one account
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PayoutBatch {
+    rows: Vec<PayoutRow>,
+}
+
+impl PayoutBatch {
+    pub fn new(rows: Vec<PayoutRow>) -> Self {
+        Self { rows }
+    }
+
+    /// Parse CSV with a header row naming the columns `destination`,
+    /// `destination_tag`, `amount`, `currency`, `issuer` and `memo`, in any
+    /// order. Only `destination`, `amount` and `currency` are required.
+    pub fn from_csv(input: &str) -> Result<Self> {
+        let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+        let header: Vec<String> = match lines.next() {
+            Some(line) => parse_csv_line(line)?.iter().map(|name| name.to_lowercase()).collect(),
+            None => return Ok(Self::default()),
+        };
+        let column = |name: &str| header.iter().position(|column| column == name);
+
+        let destination_column = column("destination");
+        let amount_column = column("amount");
+        let currency_column = column("currency");
+        let (Some(destination_column), Some(amount_column), Some(currency_column)) =
+            (destination_column, amount_column, currency_column)
+        else {
+            return Err(XrplError::InvalidTransaction(
+                "CSV header must name destination, amount and currency columns".to_string(),
+            )
+            .into());
+        };
+        let tag_column = column("destination_tag");
+        let issuer_column = column("issuer");
+        let memo_column = column("memo");
+
+        let mut rows = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let row = index + 1;
+            let fields = parse_csv_line(line)?;
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|column| fields.get(column))
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+
+            let destination_tag = field(tag_column)
+                .map(|tag| {
+                    tag.parse().map_err(|_| {
+                        XrplError::InvalidTransaction(format!("Row {}: invalid destination tag {}", row, tag))
+                    })
+                })
+                .transpose()?;
+
+            rows.push(PayoutRow {
+                row,
+                destination: field(Some(destination_column)).unwrap_or_default(),
+                destination_tag,
+                amount: field(Some(amount_column)).unwrap_or_default(),
+                currency: field(Some(currency_column)).unwrap_or_default(),
+                issuer: field(issuer_column),
+                memo: field(memo_column),
+            });
+        }
+
+        Ok(Self { rows })
+    }
+
+    pub fn rows(&self) -> &[PayoutRow] {
+        &self.rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// A reason a row cannot be paid as it is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutIssue {
+    pub row: usize,
+    pub message: String,
+}
+
+/// Where a row ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayoutStatus {
+    /// Validated with `tesSUCCESS`
+    Succeeded,
+    /// Validated with another result; the fee was spent but nothing delivered
+    Failed,
+    /// Never validated; running the batch again retries it
+    Expired,
+    /// Submitted, not yet validated or expired
+    Pending,
+    NotSubmitted,
+}
+
+impl PayoutStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutStatus::Succeeded => "succeeded",
+            PayoutStatus::Failed => "failed",
+            PayoutStatus::Expired => "expired",
+            PayoutStatus::Pending => "pending",
+            PayoutStatus::NotSubmitted => "not_submitted",
+        }
+    }
+}
+
+/// Result of one row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutRowResult {
+    pub row: PayoutRow,
+    pub status: PayoutStatus,
+    /// Hash of the transaction that decided the status
+    pub hash: Option<TransactionHash>,
+    /// Final result if validated, otherwise the preliminary one if known
+    pub engine_result: Option<String>,
+    /// Why this run could not sign, record or submit the row, while it is
+    /// not validated
+    pub error: Option<String>,
+}
+
+/// Per-row results of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayoutReport {
+    pub rows: Vec<PayoutRowResult>,
+}
+
+impl PayoutReport {
+    /// Whether every row succeeded
+    pub fn is_complete(&self) -> bool {
+        self.rows.iter().all(|row| row.status == PayoutStatus::Succeeded)
+    }
+
+    pub fn count(&self, status: PayoutStatus) -> usize {
+        self.rows.iter().filter(|row| row.status == status).count()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("row,destination,destination_tag,amount,currency,issuer,status,hash,engine_result,error\n");
+        for result in &self.rows {
+            let row = &result.row;
+            let fields = [
+                row.row.to_string(),
+                row.destination.clone(),
+                row.destination_tag.map(|tag| tag.to_string()).unwrap_or_default(),
+                row.amount.clone(),
+                row.currency.clone(),
+                row.issuer.clone().unwrap_or_default(),
+                result.status.as_str().to_string(),
+                result.hash.clone().unwrap_or_default(),
+                result.engine_result.clone().unwrap_or_default(),
+                result.error.clone().unwrap_or_default(),
+            ];
+            let escaped: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+            csv.push_str(&escaped.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+}
+
+/// Pays out a `PayoutBatch` from one account.
+///
+/// Every row is validated before anything is sent. Transactions are then
+/// signed with one fee and locally allocated sequences, recorded in the
+/// outbox, and submitted concurrently in chunks that each get their own
+/// LastLedgerSequence.
+/// A row that fails to submit is reported instead of stopping the batch. A
+/// batch can be run again with the same outbox after a crash or with
+/// expired rows: rows already validated are never paid twice.
+pub struct PayoutEngine<'a> {
+    client: &'a XrplClient,
+    account: Address,
+    secret: String,
+    outbox: Outbox,
+    sequences: SequenceManager,
+    poll_interval: Duration,
+    chunk_size: usize,
+    /// Preliminary results by hash, for the report
+    submit_results: Mutex<HashMap<TransactionHash, String>>,
+    /// Errors of this run by row reference, for the report
+    errors: Mutex<HashMap<String, String>>,
+}
+
+/// A row's transaction, signed and recorded in the outbox
+struct PreparedRow {
+    entry: OutboxEntry,
+    signed: SignedTransaction,
+}
+
+impl<'a> PayoutEngine<'a> {
+    pub fn new(client: &'a XrplClient, account: &str, secret: &str, outbox: Outbox) -> Self {
+        Self {
+            client,
+            account: account.to_string(),
+            secret: secret.to_string(),
+            outbox,
+            sequences: SequenceManager::new(account),
+            poll_interval: Duration::from_secs(4),
+            chunk_size: PAYOUT_CHUNK_SIZE,
+            submit_results: Mutex::new(HashMap::new()),
+            errors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Time between checks for validated transactions
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Most rows submitted at once
+    pub fn chunk_size(mut self, rows: usize) -> Self {
+        self.chunk_size = rows.max(1);
+        self
+    }
+
+    pub fn outbox(&self) -> &Outbox {
+        &self.outbox
+    }
+
+    /// Check every row: addresses and amounts, then `PaymentPrecheck` against
+    /// the ledger for anything that would fail with a `tec` code
+    pub async fn validate(&self, batch: &PayoutBatch) -> Result<Vec<PayoutIssue>> {
+        let mut issues = Vec::new();
+
+        for row in batch.rows() {
+            let mut issue = |message: String| issues.push(PayoutIssue { row: row.row, message });
+
+            if let Err(e) = TransactionValidator::validate_address(&row.destination) {
+                issue(format!("destination: {}", e));
+                continue;
+            }
+            if row.destination == self.account {
+                issue("destination is the sending account".to_string());
+                continue;
+            }
+
+            if row.currency == "XRP" {
+                if row.issuer.is_some() {
+                    issue("XRP has no issuer".to_string());
+                    continue;
+                }
+                if !matches!(row.amount.parse::<u64>(), Ok(drops) if drops > 0) {
+                    issue(format!("invalid XRP amount {}, expected a positive number of drops", row.amount));
+                    continue;
+                }
+            } else {
+                if let Err(e) = TransactionValidator::validate_currency_code(&row.currency) {
+                    issue(e.to_string());
+                    continue;
+                }
+                match &row.issuer {
+                    Some(issuer) => {
+                        if let Err(e) = TransactionValidator::validate_address(issuer) {
+                            issue(format!("issuer: {}", e));
+                            continue;
+                        }
+                    }
+                    None => {
+                        issue(format!("{} needs an issuer", row.currency));
+                        continue;
+                    }
+                }
+                if !matches!(row.amount.parse::<IouValue>(), Ok(value) if !value.is_zero() && !value.is_negative()) {
+                    issue(format!("invalid amount {}", row.amount));
+                    continue;
+                }
+            }
+
+            let precheck = PaymentPrecheck {
+                source: self.account.clone(),
+                destination: row.destination.clone(),
+                destination_tag: row.destination_tag,
+                currency: row.currency.clone(),
+                issuer: row.issuer.clone(),
+                amount: row.amount.clone(),
+            };
+            for found in precheck.run(self.client).await? {
+                issue(found.to_string());
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Validate, submit every row that is not yet paid or in flight, and
+    /// wait until all of them are validated or expired
+    pub async fn run(&self, batch: &PayoutBatch) -> Result<PayoutReport> {
+        let issues = self.validate(batch).await?;
+        if !issues.is_empty() {
+            let details: Vec<String> = issues
+                .iter()
+                .map(|issue| format!("row {}: {}", issue.row, issue.message))
+                .collect();
+            return Err(XrplError::InvalidTransaction(format!(
+                "{} rows failed validation: {}",
+                issues.len(),
+                details.join("; ")
+            ))
+            .into());
+        }
+
+        // Pick up where an earlier run stopped
+        self.outbox.poll(self.client).await?;
+        self.outbox.resubmit_pending(self.client).await;
+
+        let rows: Vec<&PayoutRow> = batch
+            .rows()
+            .iter()
+            .filter(|row| {
+                self.outbox
+                    .entries_for(&row.reference())
+                    .iter()
+                    .all(|entry| entry.status == OutboxStatus::Expired)
+            })
+            .collect();
+
+        if !rows.is_empty() {
+            let fee = self.client.estimate_fee(&TransactionCost::reference()).await?;
+
+            // Sequences are allocated and recorded in row order, then each
+            // chunk is submitted at once and expires a window after the
+            // ledger validated when it was signed
+            for chunk in rows.chunks(self.chunk_size) {
+                let validated_ledger = self.client.get_ledger_index().await?;
+                let mut prepared = Vec::new();
+                for row in chunk {
+                    match self.prepare_row(row, fee, validated_ledger).await {
+                        Ok(ready) => prepared.push((*row, ready)),
+                        Err(error) => self.record_error(row, &error),
+                    }
+                }
+
+                let submitted = join_all(prepared.iter().map(|(row, ready)| self.submit_prepared(row, ready))).await;
+                if submitted.contains(&false) {
+                    // Fill the sequence gaps that failed submits may have left
+                    self.outbox.resubmit_pending(self.client).await;
+                }
+            }
+        }
+
+        self.outbox.wait(self.client, self.poll_interval).await?;
+        Ok(self.report(batch))
+    }
+
+    /// The current state of every row, from the outbox
+    pub fn report(&self, batch: &PayoutBatch) -> PayoutReport {
+        let submit_results = self.submit_results.lock().unwrap();
+        let errors = self.errors.lock().unwrap();
+        let rows = batch
+            .rows()
+            .iter()
+            .map(|row| {
+                let entries = self.outbox.entries_for(&row.reference());
+                let validated = entries.iter().find(|entry| matches!(entry.status, OutboxStatus::Validated { .. }));
+                let pending = entries.iter().find(|entry| entry.status == OutboxStatus::Pending);
+                let decisive: Option<&OutboxEntry> = validated.or(pending).or(entries.last());
+
+                let status = match decisive.map(|entry| &entry.status) {
+                    Some(OutboxStatus::Validated { result, .. }) if result == "tesSUCCESS" => PayoutStatus::Succeeded,
+                    Some(OutboxStatus::Validated { .. }) => PayoutStatus::Failed,
+                    Some(OutboxStatus::Pending) => PayoutStatus::Pending,
+                    Some(OutboxStatus::Expired) => PayoutStatus::Expired,
+                    None => PayoutStatus::NotSubmitted,
+                };
+                let engine_result = decisive.and_then(|entry| match &entry.status {
+                    OutboxStatus::Validated { result, .. } => Some(result.clone()),
+                    _ => submit_results.get(&entry.hash).cloned(),
+                });
+
+                let error = match status {
+                    PayoutStatus::Succeeded | PayoutStatus::Failed => None,
+                    _ => errors.get(&row.reference()).cloned(),
+                };
+
+                PayoutRowResult {
+                    row: row.clone(),
+                    status,
+                    hash: decisive.map(|entry| entry.hash.clone()),
+                    engine_result,
+                    error,
+                }
+            })
+            .collect();
+
+        PayoutReport { rows }
+    }
+
+    /// Sign a row's payment with the next sequence and record it in the
+    /// outbox. It expires `PAYOUT_LEDGER_WINDOW` ledgers after `validated_ledger`.
+    async fn prepare_row(&self, row: &PayoutRow, fee: u64, validated_ledger: u32) -> Result<PreparedRow> {
+        let mut transaction = Transaction {
+            account: self.account.clone(),
+            destination: row.destination.clone(),
+            amount: row.amount.clone(),
+            currency: row.currency.clone(),
+            issuer: row.issuer.clone(),
+            destination_tag: row.destination_tag,
+            memos: row.memo.as_deref().map(|memo| vec![Memo::text(memo)]),
+            network_id: self.client.network().requires_network_id().then_some(self.client.network().network_id),
+            fee: fee.to_string(),
+            last_ledger_sequence: Some(validated_ledger + PAYOUT_LEDGER_WINDOW),
+            ..Transaction::default()
+        };
+        transaction.sequence = self.sequences.next(self.client).await?;
+
+        let signer = TransactionSigner::with_network(self.client.network().clone());
+        let recorded = match signer.sign_transaction(&self.secret, &transaction) {
+            Ok(signed) => self
+                .outbox
+                .insert(&signed, validated_ledger, Some(&row.reference()))
+                .map(|entry| PreparedRow { entry, signed }),
+            Err(error) => Err(error),
+        };
+        if recorded.is_err() {
+            self.sequences.release(transaction.sequence).await;
+        }
+
+        recorded
+    }
+
+    /// Submit a prepared row; `false` if the submit failed without a result
+    async fn submit_prepared(&self, row: &PayoutRow, prepared: &PreparedRow) -> bool {
+        let result = match self.client.submit_transaction(&prepared.signed).await {
+            Ok(result) => result,
+            Err(error) => {
+                self.record_error(row, &error);
+                return false;
+            }
+        };
+
+        self.submit_results
+            .lock()
+            .unwrap()
+            .insert(prepared.entry.hash.clone(), result.engine_result.clone());
+        // A failed resync only leaves the local sequences as they were
+        let _ = self
+            .sequences
+            .handle_result(self.client, prepared.entry.sequence, &result.engine_result)
+            .await;
+
+        true
+    }
+
+    fn record_error(&self, row: &PayoutRow, error: &anyhow::Error) {
+        self.errors.lock().unwrap().insert(row.reference(), error.to_string());
+    }
+}
+
+/// Split one CSV line into fields, honouring double quotes
+fn parse_csv_line(line: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(XrplError::Deserialization(format!("Unterminated quote in CSV line: {}", line)).into());
+    }
+    fields.push(field);
+
+    Ok(fields)
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::outbox::MemoryOutboxStore;
+    use crate::precheck::PrecheckIssue;
+    use crate::requests::{LSF_DEPOSIT_AUTH, LSF_REQUIRE_AUTH, LSF_REQUIRE_DEST_TAG};
+    use crate::retry::RetryPolicy;
+    use crate::test_support::{account_info, client, validated};
+    use crate::transport::{MockReply, MockTransport};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    const SECRET: &str = "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2";
+    const SENDER: &str = "rSender123456789012345678901234";
+    const ALICE: &str = "rAlice12345678901234567890123456";
+    const BOB: &str = "rBob1234567890123456789012345678";
+    const ISSUER: &str = "rIssuer123456789012345678901234";
+
+    fn trust_lines(limit: &str, balance: &str) -> Value {
+        json!({
+            "account": ALICE,
+            "ledger_index": 100,
+            "lines": [{ "account": ISSUER, "balance": balance, "currency": "USD", "limit": limit, "limit_peer": "0" }]
+        })
+    }
+
+    fn engine<'a>(client: &'a XrplClient, outbox: Outbox) -> PayoutEngine<'a> {
+        PayoutEngine::new(client, SENDER, SECRET, outbox).poll_interval(Duration::from_millis(1))
+    }
+
+    fn batch() -> PayoutBatch {
+        PayoutBatch::from_csv(&format!(
+            "destination,destination_tag,amount,currency,issuer,memo\n\
+             {ALICE},,25,USD,{ISSUER},\"March payout, part 1\"\n\
+             {BOB},7,1000000,XRP,,\n"
+        ))
+        .unwrap()
+    }
+
+    fn mock_ledger() -> Arc<MockTransport> {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "account": ALICE }), account_info(ALICE, 1, 0));
+        mock.on_params("account_info", json!({ "account": BOB }), account_info(BOB, 1, LSF_REQUIRE_DEST_TAG));
+        mock.on_params("account_info", json!({ "account": SENDER }), account_info(SENDER, 30, 0));
+        mock.on_params("account_info", json!({ "account": ISSUER }), account_info(ISSUER, 1, 0));
+        mock.on("account_lines", trust_lines("100", "10"));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("submit", json!({
+            "engine_result": "tesSUCCESS",
+            "engine_result_code": 0,
+            "engine_result_message": "",
+            "tx_blob": "",
+            "tx_json": {}
+        }));
+        mock
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let batch = batch();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.rows()[0].memo.as_deref(), Some("March payout, part 1"));
+        assert_eq!(batch.rows()[0].destination_tag, None);
+        assert_eq!(batch.rows()[1].destination_tag, Some(7));
+        assert_eq!(batch.rows()[1].issuer, None);
+
+        assert!(PayoutBatch::from_csv("destination,amount\nrAlice,1\n").is_err());
+        assert!(PayoutBatch::from_csv("destination,amount,currency,destination_tag\nrAlice,1,XRP,x\n").is_err());
+        assert_eq!(parse_csv_line("a,\"b \"\"c\"\"\",").unwrap(), vec!["a", "b \"c\"", ""]);
+    }
+
+    #[tokio::test]
+    async fn test_validation_issues() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "account": ALICE }), account_info(ALICE, 1, 0));
+        mock.on_params("account_info", json!({ "account": BOB }), account_info(BOB, 1, LSF_REQUIRE_DEST_TAG));
+        mock.on("account_info", json!({ "error": "actNotFound" }));
+        mock.on("account_lines", trust_lines("20", "10"));
+        mock.on("server_state", json!({
+            "state": {
+                "build_version": "2.2.3",
+                "server_state": "full",
+                "validated_ledger": { "base_fee": 10, "hash": "ABC", "reserve_base": 1000000, "reserve_inc": 200000, "seq": 99 }
+            }
+        }));
+        let xrpl = client(&mock);
+        let engine = engine(&xrpl, Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap());
+
+        let batch = PayoutBatch::from_csv(&format!(
+            "destination,amount,currency,issuer\n\
+             {ALICE},25,USD,{ISSUER}\n\
+             {BOB},1000000,XRP,\n\
+             rUnfunded12345678901234567890123,500,XRP,\n\
+             bad,1,XRP,\n\
+             {ALICE},5,USD,{ISSUER}\n"
+        ))
+        .unwrap();
+        let issues = engine.validate(&batch).await.unwrap();
+
+        let rows: Vec<usize> = issues.iter().map(|issue| issue.row).collect();
+        assert_eq!(rows, vec![1, 2, 3, 4]);
+        assert!(issues[0].message.contains("room for only 10"));
+        assert!(issues[1].message.contains("destination tag"));
+        assert!(issues[2].message.contains("base reserve"));
+        assert_eq!(mock.requests_for("account_lines")[0]["peer"], ISSUER);
+    }
+
+    #[tokio::test]
+    async fn test_validation_checks_authorization() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "account": ALICE }), account_info(ALICE, 1, 0));
+        mock.on_params("account_info", json!({ "account": BOB }), account_info(BOB, 1, LSF_DEPOSIT_AUTH));
+        mock.on_params("account_info", json!({ "account": ISSUER }), account_info(ISSUER, 1, LSF_REQUIRE_AUTH));
+        mock.on("account_lines", trust_lines("100", "10"));
+        mock.on("deposit_authorized", json!({
+            "deposit_authorized": false,
+            "source_account": SENDER,
+            "destination_account": BOB,
+            "ledger_index": 100,
+            "validated": true
+        }));
+        let xrpl = client(&mock);
+        let engine = engine(&xrpl, Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap());
+
+        let issues = engine.validate(&batch()).await.unwrap();
+        let messages: Vec<String> = issues.into_iter().map(|issue| issue.message).collect();
+        assert_eq!(
+            messages,
+            vec![
+                PrecheckIssue::NotAuthorizedByIssuer.to_string(),
+                PrecheckIssue::DepositNotAuthorized.to_string()
+            ]
+        );
+        assert_eq!(mock.requests_for("deposit_authorized")[0]["source_account"], SENDER);
+    }
+
+    #[tokio::test]
+    async fn test_run_pipelines_and_reports() {
+        let mock = mock_ledger();
+        mock.on("tx", validated("ANY", SENDER, 30, 101));
+        let xrpl = client(&mock);
+        let engine = engine(&xrpl, Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap());
+        let batch = batch();
+
+        let report = engine.run(&batch).await.unwrap();
+        assert!(report.is_complete());
+
+        let sequences: Vec<u32> = engine.outbox().entries().iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![30, 31]);
+        assert_eq!(mock.request_count("account_info"), 4);
+
+        let csv = report.to_csv();
+        let bob = csv.lines().nth(2).unwrap();
+        assert!(bob.starts_with(&format!("2,{},7,1000000,XRP,,succeeded,", BOB)));
+        assert!(bob.ends_with(",tesSUCCESS,"));
+
+        // Running again pays nothing twice
+        engine.run(&batch).await.unwrap();
+        assert_eq!(mock.request_count("submit"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_error_does_not_stop_the_batch() {
+        let mock = mock_ledger();
+        mock.reply("submit", MockReply::NetworkError("connection reset".to_string()))
+            .on("submit", json!({
+                "engine_result": "tesSUCCESS",
+                "engine_result_code": 0,
+                "engine_result_message": "",
+                "tx_blob": "",
+                "tx_json": {}
+            }));
+        mock.on("tx", validated("ANY", SENDER, 30, 101));
+        let xrpl = XrplClient::builder(Network::testnet())
+            .transport(mock.clone())
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let engine = engine(&xrpl, Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap());
+
+        let report = engine.run(&batch()).await.unwrap();
+        assert_eq!(report.count(PayoutStatus::Succeeded), 2);
+        assert!(report.rows.iter().all(|row| row.error.is_none()));
+        // Both pending entries were submitted again after the failure
+        assert_eq!(mock.request_count("submit"), 4);
+        let expiries: Vec<u32> = engine.outbox().entries().iter().map(|entry| entry.last_ledger_sequence).collect();
+        assert_eq!(expiries, vec![120, 120]);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_get_their_own_expiry() {
+        // The fee estimate and the first chunk see ledger 100, the second 105
+        let mock = mock_ledger();
+        mock.on("ledger", json!({ "ledger_index": 100 }))
+            .on("ledger", json!({ "ledger_index": 105 }));
+        mock.on("tx", validated("ANY", SENDER, 30, 106));
+        let xrpl = client(&mock);
+        let engine = engine(&xrpl, Outbox::open(Box::new(MemoryOutboxStore::new())).unwrap()).chunk_size(1);
+
+        let report = engine.run(&batch()).await.unwrap();
+        assert!(report.is_complete());
+        let expiries: Vec<u32> = engine.outbox().entries().iter().map(|entry| entry.last_ledger_sequence).collect();
+        assert_eq!(expiries, vec![120, 125]);
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_crash() {
+        let batch = batch();
+        let path = std::env::temp_dir().join(format!("xrpl-payout-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // The first row was recorded and submitted, then the process died
+        let mock = mock_ledger();
+        let xrpl = client(&mock);
+        let crashed = engine(&xrpl, Outbox::open_file(&path).unwrap());
+        let prepared = crashed.prepare_row(&batch.rows()[0], 12, 100).await.unwrap();
+        assert!(crashed.submit_prepared(&batch.rows()[0], &prepared).await);
+        let paid = crashed.outbox().entries()[0].hash.clone();
+
+        let mock = mock_ledger();
+        mock.on("tx", validated("ANY", SENDER, 30, 101));
+        let xrpl = client(&mock);
+        let resumed = engine(&xrpl, Outbox::open_file(&path).unwrap());
+
+        let report = resumed.run(&batch).await.unwrap();
+        assert_eq!(report.count(PayoutStatus::Succeeded), 2);
+        assert_eq!(report.rows[0].hash.as_deref(), Some(paid.as_str()));
+        assert_eq!(resumed.outbox().entries_for(&batch.rows()[0].reference()).len(), 1);
+        assert_eq!(mock.request_count("submit"), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
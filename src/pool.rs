@@ -0,0 +1,243 @@
+use crate::transport::Transport;
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Server states in which a node is considered in sync with the network
+const SYNCED_STATES: [&str; 4] = ["full", "proposing", "validating", "tracking"];
+
+/// rippled errors that mean the node itself is out of sync and another node should be tried
+pub const FAILOVER_ERRORS: [&str; 2] = ["noCurrent", "noNetwork"];
+
+/// Last known health of a single endpoint
+#[derive(Debug, Clone, Default)]
+pub struct NodeHealth {
+    pub server_state: Option<String>,
+    pub validated_ledger_seq: Option<u32>,
+    pub validated_ledger_age: Option<u32>,
+    pub consecutive_failures: u32,
+    pub last_checked: Option<Instant>,
+}
+
+impl NodeHealth {
+    /// Whether the node looks usable given the maximum tolerated validated ledger age
+    pub fn is_healthy(&self, max_ledger_age: u32) -> bool {
+        if self.consecutive_failures > 0 {
+            return false;
+        }
+
+        if let Some(state) = &self.server_state {
+            if !SYNCED_STATES.contains(&state.as_str()) {
+                return false;
+            }
+        }
+
+        match self.validated_ledger_age {
+            Some(age) => age <= max_ledger_age,
+            None => true,
+        }
+    }
+}
+
+struct Node {
+    transport: Arc<dyn Transport>,
+    health: RwLock<NodeHealth>,
+}
+
+/// A set of JSON-RPC endpoints for the same network, ordered by health
+pub struct NodePool {
+    nodes: Vec<Node>,
+    max_ledger_age: u32,
+    health_check_interval: Duration,
+}
+
+impl NodePool {
+    /// Default maximum age in seconds of a node's validated ledger
+    pub const DEFAULT_MAX_LEDGER_AGE: u32 = 60;
+    /// Default interval between `server_info` health checks of a multi-node pool
+    pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+    pub fn new(transports: Vec<Arc<dyn Transport>>) -> Self {
+        let nodes = transports
+            .into_iter()
+            .map(|transport| Node {
+                transport,
+                health: RwLock::new(NodeHealth::default()),
+            })
+            .collect();
+
+        Self {
+            nodes,
+            max_ledger_age: Self::DEFAULT_MAX_LEDGER_AGE,
+            health_check_interval: Self::DEFAULT_HEALTH_CHECK_INTERVAL,
+        }
+    }
+
+    pub fn with_max_ledger_age(mut self, seconds: u32) -> Self {
+        self.max_ledger_age = seconds;
+        self
+    }
+
+    pub fn with_health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn transport(&self, index: usize) -> &Arc<dyn Transport> {
+        &self.nodes[index].transport
+    }
+
+    pub fn endpoint(&self, index: usize) -> &str {
+        self.nodes[index].transport.endpoint()
+    }
+
+    pub fn endpoints(&self) -> Vec<&str> {
+        self.nodes.iter().map(|node| node.transport.endpoint()).collect()
+    }
+
+    pub fn health(&self, index: usize) -> NodeHealth {
+        self.nodes[index].health.read().unwrap().clone()
+    }
+
+    /// Node indices in the order they should be tried: healthy nodes with the
+    /// freshest validated ledger first, then the rest in configuration order
+    pub fn ordered(&self) -> Vec<usize> {
+        let mut healthy = Vec::new();
+        let mut unhealthy = Vec::new();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let health = node.health.read().unwrap();
+            if health.is_healthy(self.max_ledger_age) {
+                healthy.push((index, health.validated_ledger_seq.unwrap_or(0)));
+            } else {
+                unhealthy.push((index, health.consecutive_failures));
+            }
+        }
+
+        healthy.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        unhealthy.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        healthy
+            .into_iter()
+            .chain(unhealthy)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Whether any node is due for a health check. A single node has nothing
+    /// to be ordered against, so it is never checked.
+    pub fn needs_health_check(&self) -> bool {
+        if self.nodes.len() < 2 {
+            return false;
+        }
+
+        self.nodes.iter().any(|node| match node.health.read().unwrap().last_checked {
+            Some(checked) => checked.elapsed() >= self.health_check_interval,
+            None => true,
+        })
+    }
+
+    pub fn mark_success(&self, index: usize) {
+        self.nodes[index].health.write().unwrap().consecutive_failures = 0;
+    }
+
+    pub fn mark_failure(&self, index: usize) {
+        let mut health = self.nodes[index].health.write().unwrap();
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+    }
+
+    /// Record the outcome of a `server_info` health check
+    pub fn update_from_server_info(&self, index: usize, response: &Value) {
+        let info = &response["result"]["info"];
+        let mut health = self.nodes[index].health.write().unwrap();
+
+        health.last_checked = Some(Instant::now());
+        health.server_state = info["server_state"].as_str().map(|s| s.to_string());
+        health.validated_ledger_seq = info["validated_ledger"]["seq"].as_u64().map(|v| v as u32);
+        health.validated_ledger_age = info["validated_ledger"]["age"].as_u64().map(|v| v as u32);
+        health.consecutive_failures = if info.is_object() { 0 } else { 1 };
+    }
+
+    /// Record a failed health check
+    pub fn update_unreachable(&self, index: usize) {
+        let mut health = self.nodes[index].health.write().unwrap();
+        health.last_checked = Some(Instant::now());
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+    use serde_json::json;
+
+    fn pool_of(size: usize) -> NodePool {
+        let transports: Vec<Arc<dyn Transport>> = (0..size)
+            .map(|_| Arc::new(MockTransport::new()) as Arc<dyn Transport>)
+            .collect();
+        NodePool::new(transports)
+    }
+
+    fn server_info(state: &str, seq: u32, age: u32) -> Value {
+        json!({
+            "result": {
+                "info": {
+                    "server_state": state,
+                    "validated_ledger": { "seq": seq, "age": age }
+                },
+                "status": "success"
+            }
+        })
+    }
+
+    #[test]
+    fn test_prefers_freshest_validated_ledger() {
+        let pool = pool_of(3);
+        pool.update_from_server_info(0, &server_info("full", 100, 2));
+        pool.update_from_server_info(1, &server_info("full", 105, 1));
+        pool.update_from_server_info(2, &server_info("syncing", 110, 1));
+
+        assert_eq!(pool.ordered(), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_failed_nodes_move_to_the_back() {
+        let pool = pool_of(2);
+        pool.mark_failure(0);
+        assert_eq!(pool.ordered(), vec![1, 0]);
+
+        pool.mark_success(0);
+        assert_eq!(pool.ordered(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_stale_ledger_is_unhealthy() {
+        let pool = pool_of(2).with_max_ledger_age(30);
+        pool.update_from_server_info(0, &server_info("full", 100, 120));
+        pool.update_from_server_info(1, &server_info("full", 90, 3));
+
+        assert!(!pool.health(0).is_healthy(30));
+        assert_eq!(pool.ordered(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_health_checks_by_default() {
+        let pool = pool_of(2);
+        assert!(pool.needs_health_check());
+
+        pool.update_from_server_info(0, &server_info("full", 100, 1));
+        pool.update_from_server_info(1, &server_info("full", 100, 1));
+        assert!(!pool.needs_health_check());
+
+        assert!(!pool_of(1).needs_health_check());
+    }
+}
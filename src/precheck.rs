@@ -0,0 +1,318 @@
+use crate::amount::IouValue;
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::requests::{AccountInfoRequest, AccountRoot, DepositAuthorizedRequest};
+use crate::types::*;
+use anyhow::Result;
+use serde_json::{json, Value};
+
+/// A ledger condition that would make a payment fail with a `tec` code
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrecheckIssue {
+    /// The destination does not exist and the XRP sent is too little to create it
+    #[error("destination account does not exist and the amount is less than the base reserve of {base_reserve} drops")]
+    DestinationUnfunded { base_reserve: u64 },
+    /// The destination does not exist, and only XRP can create it
+    #[error("destination account does not exist")]
+    DestinationMissing,
+    #[error("destination requires a destination tag")]
+    DestinationTagRequired,
+    /// The destination has Deposit Authorization on and has not preauthorized the sender
+    #[error("destination does not accept deposits from the sender")]
+    DepositNotAuthorized,
+    /// Not enforced by the ledger, but the destination asked not to be sent XRP
+    #[error("destination does not want XRP")]
+    XrpDisallowed,
+    #[error("destination has no trust line for {currency}/{issuer}")]
+    NoTrustLine { currency: CurrencyCode, issuer: Address },
+    /// The trust line limit leaves too little room for the amount
+    #[error("trust line limit {limit} leaves room for only {room} {currency}")]
+    TrustLineLimitReached { currency: CurrencyCode, limit: String, room: String },
+    /// The issuer requires authorized trust lines and has not authorized this one
+    #[error("issuer has not authorized the destination's trust line")]
+    NotAuthorizedByIssuer,
+}
+
+impl PrecheckIssue {
+    /// The result code rippled would return, if the ledger enforces it
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            PrecheckIssue::DestinationUnfunded { .. } => Some("tecNO_DST_INSUF_XRP"),
+            PrecheckIssue::DestinationMissing => Some("tecNO_DST"),
+            PrecheckIssue::DestinationTagRequired => Some("tecDST_TAG_NEEDED"),
+            PrecheckIssue::DepositNotAuthorized => Some("tecNO_PERMISSION"),
+            PrecheckIssue::XrpDisallowed => None,
+            PrecheckIssue::NoTrustLine { .. } => Some("tecPATH_DRY"),
+            PrecheckIssue::TrustLineLimitReached { .. } => Some("tecPATH_PARTIAL"),
+            PrecheckIssue::NotAuthorizedByIssuer => Some("tecPATH_DRY"),
+        }
+    }
+}
+
+/// A payment to check against the current validated ledger before sending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentPrecheck {
+    pub source: Address,
+    pub destination: Address,
+    pub destination_tag: Option<u32>,
+    /// "XRP" or the issued currency code
+    pub currency: CurrencyCode,
+    /// Required for issued currencies
+    pub issuer: Option<Address>,
+    /// Drops for XRP, the decimal value for issued currencies
+    pub amount: Amount,
+}
+
+impl PaymentPrecheck {
+    pub fn xrp(source: &str, destination: &str, drops: u64) -> Self {
+        Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            destination_tag: None,
+            currency: "XRP".to_string(),
+            issuer: None,
+            amount: drops.to_string(),
+        }
+    }
+
+    pub fn issued(source: &str, destination: &str, currency: &str, issuer: &str, value: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            destination_tag: None,
+            currency: currency.to_string(),
+            issuer: Some(issuer.to_string()),
+            amount: value.to_string(),
+        }
+    }
+
+    /// The payment a `Transaction` would make
+    pub fn from_transaction(transaction: &Transaction) -> Self {
+        Self {
+            source: transaction.account.clone(),
+            destination: transaction.destination.clone(),
+            destination_tag: transaction.destination_tag,
+            currency: transaction.currency.clone(),
+            issuer: transaction.issuer.clone(),
+            amount: transaction.amount.clone(),
+        }
+    }
+
+    pub fn with_destination_tag(mut self, tag: u32) -> Self {
+        self.destination_tag = Some(tag);
+        self
+    }
+
+    /// Look up the destination, and the issuer for tokens, and return every
+    /// issue found. An empty list means no `tec` failure is foreseeable.
+    pub async fn run(&self, client: &XrplClient) -> Result<Vec<PrecheckIssue>> {
+        let is_xrp = self.currency == "XRP";
+        let mut issues = Vec::new();
+
+        let Some(destination) = client.account_root(&self.destination).await? else {
+            if is_xrp {
+                let base_reserve = client.reserves().await?.base;
+                if self.amount.parse::<u64>().unwrap_or_default() < base_reserve {
+                    issues.push(PrecheckIssue::DestinationUnfunded { base_reserve });
+                }
+            } else {
+                issues.push(PrecheckIssue::DestinationMissing);
+            }
+            return Ok(issues);
+        };
+
+        if destination.requires_destination_tag() && self.destination_tag.is_none() {
+            issues.push(PrecheckIssue::DestinationTagRequired);
+        }
+
+        if destination.requires_deposit_auth() && self.source != self.destination {
+            let authorized = client
+                .request(&DepositAuthorizedRequest::new(&self.source, &self.destination))
+                .await?;
+            if !authorized.deposit_authorized {
+                issues.push(PrecheckIssue::DepositNotAuthorized);
+            }
+        }
+
+        if is_xrp {
+            if destination.disallows_xrp() {
+                issues.push(PrecheckIssue::XrpDisallowed);
+            }
+            return Ok(issues);
+        }
+
+        let issuer = self
+            .issuer
+            .as_deref()
+            .ok_or_else(|| XrplError::InvalidTransaction(format!("{} needs an issuer", self.currency)))?;
+        if issuer == self.destination {
+            return Ok(issues);
+        }
+
+        let lines = client
+            .account_lines(&self.destination)
+            .param("peer", json!(issuer))
+            .collect_all()
+            .await?;
+        let issuer_requires_auth = client
+            .account_root(issuer)
+            .await?
+            .is_some_and(|issuer| issuer.requires_auth());
+        issues.extend(trust_line_issue(&lines, &self.currency, issuer, &self.amount, issuer_requires_auth));
+
+        Ok(issues)
+    }
+}
+
+impl XrplClient {
+    /// Check a payment for foreseeable `tec` failures; see `PaymentPrecheck`
+    pub async fn precheck_payment(&self, transaction: &Transaction) -> Result<Vec<PrecheckIssue>> {
+        PaymentPrecheck::from_transaction(transaction).run(self).await
+    }
+
+    /// The account's AccountRoot on the validated ledger, or `None` if it is not funded
+    pub(crate) async fn account_root(&self, address: &str) -> Result<Option<AccountRoot>> {
+        match self.request(&AccountInfoRequest::new(address)).await {
+            Ok(info) => Ok(Some(info.account_data)),
+            Err(error) => match error.downcast_ref::<XrplError>() {
+                Some(XrplError::ApiError(code)) if code == "actNotFound" => Ok(None),
+                _ => Err(error),
+            },
+        }
+    }
+}
+
+/// Why a destination's trust lines with `issuer` (from `account_lines` with
+/// `peer` set) cannot receive `amount` of `currency`, if they cannot
+pub(crate) fn trust_line_issue(
+    lines: &[Value],
+    currency: &str,
+    issuer: &str,
+    amount: &str,
+    issuer_requires_auth: bool,
+) -> Option<PrecheckIssue> {
+    let Some(line) = lines.iter().find(|line| line["currency"] == currency) else {
+        return Some(PrecheckIssue::NoTrustLine {
+            currency: currency.to_string(),
+            issuer: issuer.to_string(),
+        });
+    };
+
+    if issuer_requires_auth && line["peer_authorized"] != true {
+        return Some(PrecheckIssue::NotAuthorizedByIssuer);
+    }
+
+    let value = |field: &str| line[field].as_str().and_then(|value| value.parse::<IouValue>().ok());
+    let (Some(limit), Some(balance), Ok(amount)) = (value("limit"), value("balance"), amount.parse::<IouValue>())
+    else {
+        return None;
+    };
+
+    match limit.checked_sub(balance) {
+        Ok(room) if room < amount => Some(PrecheckIssue::TrustLineLimitReached {
+            currency: currency.to_string(),
+            limit: limit.to_string(),
+            room: room.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::{LSF_DEPOSIT_AUTH, LSF_DISALLOW_XRP, LSF_REQUIRE_AUTH, LSF_REQUIRE_DEST_TAG};
+    use crate::test_support::{account_info, client};
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    const SENDER: &str = "rSender123456789012345678901234";
+    const RECEIVER: &str = "rReceiver1234567890123456789012";
+    const ISSUER: &str = "rIssuer123456789012345678901234";
+
+    #[tokio::test]
+    async fn test_unfunded_destination() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_error("account_info", "actNotFound");
+        mock.on("server_state", json!({
+            "state": {
+                "build_version": "2.2.3",
+                "server_state": "full",
+                "validated_ledger": { "base_fee": 10, "hash": "ABC", "reserve_base": 1000000, "reserve_inc": 200000, "seq": 99 }
+            }
+        }));
+        let xrpl = client(&mock);
+
+        let issues = PaymentPrecheck::xrp(SENDER, RECEIVER, 500_000).run(&xrpl).await.unwrap();
+        assert_eq!(issues, vec![PrecheckIssue::DestinationUnfunded { base_reserve: 1_000_000 }]);
+        assert_eq!(issues[0].code(), Some("tecNO_DST_INSUF_XRP"));
+
+        assert!(PaymentPrecheck::xrp(SENDER, RECEIVER, 1_000_000).run(&xrpl).await.unwrap().is_empty());
+
+        let token = PaymentPrecheck::issued(SENDER, RECEIVER, "USD", ISSUER, "5");
+        assert_eq!(token.run(&xrpl).await.unwrap(), vec![PrecheckIssue::DestinationMissing]);
+    }
+
+    #[tokio::test]
+    async fn test_destination_flags() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", account_info(RECEIVER, 1, LSF_REQUIRE_DEST_TAG | LSF_DEPOSIT_AUTH | LSF_DISALLOW_XRP));
+        mock.on("deposit_authorized", json!({
+            "deposit_authorized": false,
+            "source_account": SENDER,
+            "destination_account": RECEIVER,
+            "ledger_index": 100,
+            "validated": true
+        }));
+        let xrpl = client(&mock);
+
+        let issues = PaymentPrecheck::xrp(SENDER, RECEIVER, 1000).run(&xrpl).await.unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                PrecheckIssue::DestinationTagRequired,
+                PrecheckIssue::DepositNotAuthorized,
+                PrecheckIssue::XrpDisallowed
+            ]
+        );
+        assert_eq!(issues[2].code(), None);
+
+        let tagged = PaymentPrecheck::xrp(SENDER, RECEIVER, 1000).with_destination_tag(9);
+        assert_eq!(tagged.run(&xrpl).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_trust_lines() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("account_info", json!({ "account": RECEIVER }), account_info(RECEIVER, 1, 0));
+        mock.on_params("account_info", json!({ "account": ISSUER }), account_info(ISSUER, 1, LSF_REQUIRE_AUTH));
+        mock.on("account_lines", json!({
+            "account": RECEIVER,
+            "ledger_index": 100,
+            "lines": [
+                { "account": ISSUER, "balance": "95", "currency": "USD", "limit": "100", "limit_peer": "0", "peer_authorized": true },
+                { "account": ISSUER, "balance": "0", "currency": "EUR", "limit": "100", "limit_peer": "0" }
+            ]
+        }));
+        let xrpl = client(&mock);
+
+        let transaction = Transaction {
+            account: SENDER.to_string(),
+            destination: RECEIVER.to_string(),
+            amount: "10".to_string(),
+            currency: "USD".to_string(),
+            issuer: Some(ISSUER.to_string()),
+            ..Transaction::default()
+        };
+        let issues = xrpl.precheck_payment(&transaction).await.unwrap();
+        assert_eq!(issues[0].to_string(), "trust line limit 100 leaves room for only 5 USD");
+        assert_eq!(issues[0].code(), Some("tecPATH_PARTIAL"));
+
+        let eur = PaymentPrecheck::issued(SENDER, RECEIVER, "EUR", ISSUER, "10");
+        assert_eq!(eur.run(&xrpl).await.unwrap(), vec![PrecheckIssue::NotAuthorizedByIssuer]);
+
+        let gbp = PaymentPrecheck::issued(SENDER, RECEIVER, "GBP", ISSUER, "10");
+        assert!(matches!(gbp.run(&xrpl).await.unwrap()[..], [PrecheckIssue::NoTrustLine { .. }]));
+        assert_eq!(mock.requests_for("account_lines")[0]["peer"], ISSUER);
+    }
+}
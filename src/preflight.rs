@@ -0,0 +1,489 @@
+use crate::amount::IouValue;
+use crate::transaction::MAX_TICKET_COUNT;
+use crate::types::*;
+use std::collections::HashSet;
+
+/// Flag valid on every transaction type
+pub const TF_FULLY_CANONICAL_SIG: u32 = 0x8000_0000;
+
+pub const TF_NO_RIPPLE_DIRECT: u32 = 0x0001_0000;
+/// Payment flag allowing the delivered amount to be less than `Amount`
+pub const TF_PARTIAL_PAYMENT: u32 = 0x0002_0000;
+pub const TF_LIMIT_QUALITY: u32 = 0x0004_0000;
+
+pub const TF_SETF_AUTH: u32 = 0x0001_0000;
+pub const TF_SET_NO_RIPPLE: u32 = 0x0002_0000;
+pub const TF_CLEAR_NO_RIPPLE: u32 = 0x0004_0000;
+pub const TF_SET_FREEZE: u32 = 0x0010_0000;
+pub const TF_CLEAR_FREEZE: u32 = 0x0020_0000;
+
+pub const TF_REQUIRE_DEST_TAG: u32 = 0x0001_0000;
+pub const TF_OPTIONAL_DEST_TAG: u32 = 0x0002_0000;
+pub const TF_REQUIRE_AUTH: u32 = 0x0004_0000;
+pub const TF_OPTIONAL_AUTH: u32 = 0x0008_0000;
+pub const TF_DISALLOW_XRP: u32 = 0x0010_0000;
+pub const TF_ALLOW_XRP: u32 = 0x0020_0000;
+
+pub const TF_PASSIVE: u32 = 0x0001_0000;
+pub const TF_IMMEDIATE_OR_CANCEL: u32 = 0x0002_0000;
+pub const TF_FILL_OR_KILL: u32 = 0x0004_0000;
+pub const TF_SELL: u32 = 0x0008_0000;
+
+/// Most XRP that can exist, in drops
+const MAX_DROPS: u64 = 100_000_000_000_000_000;
+/// Most paths a payment may carry, and most steps in one path
+const MAX_PATHS: usize = 6;
+const MAX_PATH_LENGTH: usize = 8;
+/// Largest serialized size of all memos together
+const MAX_MEMO_BYTES: usize = 1024;
+/// Characters besides letters and digits allowed in 3 character currency codes
+const ISO_SYMBOLS: &str = "<>(){}[]|?!@#$%^&*";
+
+/// A reason rippled would reject a transaction as malformed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{code}: {field} {message}")]
+pub struct PreflightError {
+    /// The `tem` code rippled would return
+    pub code: &'static str,
+    /// The transaction field at fault, as named on the ledger
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl PreflightError {
+    fn new(code: &'static str, field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check a transaction locally against the `tem` rules rippled applies
+/// before looking at the ledger. Returns every problem found.
+pub fn preflight(transaction: &Transaction) -> Vec<PreflightError> {
+    let mut errors = Vec::new();
+
+    check_common(transaction, &mut errors);
+    match transaction.transaction_type.as_str() {
+        "Payment" => check_payment(transaction, &mut errors),
+        "TrustSet" => check_trust_set(transaction, &mut errors),
+        "AccountSet" => check_account_set(transaction, &mut errors),
+        "TicketCreate" => check_ticket_create(transaction, &mut errors),
+        "OfferCreate" => check_offer_create(transaction, &mut errors),
+        _ => {}
+    }
+
+    // PaymentChannelFund also has an Expiration, checked against the ledger
+    if matches!(transaction.transaction_type.as_str(), "OfferCreate" | "CheckCreate" | "NFTokenCreateOffer")
+        && transaction.expiration == Some(0)
+    {
+        errors.push(PreflightError::new("temBAD_EXPIRATION", "Expiration", "must not be 0"));
+    }
+
+    errors
+}
+
+fn check_common(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    if transaction.account.is_empty() {
+        errors.push(PreflightError::new("temBAD_SRC_ACCOUNT", "Account", "is required"));
+    }
+
+    if !matches!(transaction.fee.parse::<u64>(), Ok(drops) if drops <= MAX_DROPS) {
+        errors.push(PreflightError::new(
+            "temBAD_FEE",
+            "Fee",
+            format!("must be a whole number of drops, got {:?}", transaction.fee),
+        ));
+    }
+
+    if transaction.ticket_sequence.is_some() && transaction.sequence != 0 {
+        errors.push(PreflightError::new("temSEQ_AND_TICKET", "Sequence", "must be 0 when using a ticket"));
+    }
+
+    if let Some(memos) = &transaction.memos {
+        let mut size = 0;
+        for memo in memos {
+            for field in [&memo.memo_type, &memo.memo_data, &memo.memo_format].into_iter().flatten() {
+                if hex::decode(field).is_err() {
+                    errors.push(PreflightError::new("temINVALID", "Memos", "fields must be hex encoded"));
+                }
+                size += field.len() / 2;
+            }
+        }
+        if size > MAX_MEMO_BYTES {
+            errors.push(PreflightError::new(
+                "temINVALID",
+                "Memos",
+                format!("are {} bytes, more than {}", size, MAX_MEMO_BYTES),
+            ));
+        }
+    }
+}
+
+/// Flags outside `allowed` (and the universal ones) are invalid
+fn check_flags(transaction: &Transaction, allowed: u32, errors: &mut Vec<PreflightError>) {
+    let flags = transaction.flags.unwrap_or(0);
+    let invalid = flags & !(allowed | TF_FULLY_CANONICAL_SIG);
+    if invalid != 0 {
+        errors.push(PreflightError::new(
+            "temINVALID_FLAG",
+            "Flags",
+            format!("0x{:08X} not valid for {}", invalid, transaction.transaction_type),
+        ));
+    }
+}
+
+/// Reject flags that are set together with their opposite
+fn check_exclusive_flags(transaction: &Transaction, pairs: &[(u32, u32)], errors: &mut Vec<PreflightError>) {
+    let flags = transaction.flags.unwrap_or(0);
+    for (first, second) in pairs {
+        if flags & first != 0 && flags & second != 0 {
+            errors.push(PreflightError::new(
+                "temINVALID_FLAG",
+                "Flags",
+                format!("0x{:08X} and 0x{:08X} cannot both be set", first, second),
+            ));
+        }
+    }
+}
+
+/// Check a positive amount in `currency`: drops for XRP, a token value otherwise
+fn check_amount(amount: &str, currency: &str, field: &'static str, errors: &mut Vec<PreflightError>) {
+    let valid = if currency == "XRP" {
+        matches!(amount.parse::<u64>(), Ok(drops) if drops > 0 && drops <= MAX_DROPS)
+    } else {
+        matches!(amount.parse::<IouValue>(), Ok(value) if !value.is_negative() && !value.is_zero())
+    };
+
+    if !valid {
+        let expected = if currency == "XRP" { "a whole number of drops" } else { "a token amount" };
+        errors.push(PreflightError::new(
+            "temBAD_AMOUNT",
+            field,
+            format!("must be a positive {}, got {:?}", expected, amount),
+        ));
+    }
+}
+
+fn check_currency(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    if transaction.currency == "XRP" {
+        if transaction.issuer.is_some() {
+            errors.push(PreflightError::new("temBAD_ISSUER", "Amount", "XRP has no issuer"));
+        }
+        return;
+    }
+
+    if let Err(message) = check_currency_code(&transaction.currency) {
+        errors.push(PreflightError::new("temBAD_CURRENCY", "Amount", message));
+    }
+    if transaction.issuer.as_deref().is_none_or(str::is_empty) {
+        errors.push(PreflightError::new("temBAD_ISSUER", "Amount", "a token needs an issuer"));
+    }
+}
+
+/// rippled's currency code rule: three characters of the ISO set other than
+/// "XRP", or 40 hex digits that are not all zero
+fn check_currency_code(currency: &str) -> Result<(), String> {
+    let valid = match currency.len() {
+        3 => currency != "XRP" && currency.chars().all(|c| c.is_ascii_alphanumeric() || ISO_SYMBOLS.contains(c)),
+        40 => currency.chars().all(|c| c.is_ascii_hexdigit()) && currency.chars().any(|c| c != '0'),
+        _ => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{:?} is neither a 3 character code nor 40 hex digits", currency))
+    }
+}
+
+fn check_payment(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    check_flags(transaction, TF_NO_RIPPLE_DIRECT | TF_PARTIAL_PAYMENT | TF_LIMIT_QUALITY, errors);
+
+    if transaction.destination.is_empty() {
+        errors.push(PreflightError::new("temDST_NEEDED", "Destination", "is required"));
+    }
+
+    check_currency(transaction, errors);
+    check_amount(&transaction.amount, &transaction.currency, "Amount", errors);
+    if let Some(send_max) = &transaction.send_max {
+        check_amount(send_max, &transaction.currency, "SendMax", errors);
+    }
+
+    let flags = transaction.flags.unwrap_or(0);
+    let paths = transaction.paths.as_deref().unwrap_or_default();
+
+    // `send_max` carries no currency of its own, so it is in the Amount's currency
+    if transaction.currency == "XRP" {
+        if !paths.is_empty() {
+            errors.push(PreflightError::new("temBAD_SEND_XRP_PATHS", "Paths", "XRP to XRP payments cannot use paths"));
+        }
+        if transaction.send_max.is_some() {
+            errors.push(PreflightError::new("temBAD_SEND_XRP_MAX", "SendMax", "XRP to XRP payments cannot use SendMax"));
+        }
+        if flags & TF_PARTIAL_PAYMENT != 0 {
+            errors.push(PreflightError::new("temBAD_SEND_XRP_PARTIAL", "Flags", "XRP to XRP payments cannot be partial"));
+        }
+        if flags & TF_LIMIT_QUALITY != 0 {
+            errors.push(PreflightError::new("temBAD_SEND_XRP_LIMIT", "Flags", "XRP to XRP payments cannot limit quality"));
+        }
+        if flags & TF_NO_RIPPLE_DIRECT != 0 {
+            errors.push(PreflightError::new(
+                "temBAD_SEND_XRP_NO_DIRECT",
+                "Flags",
+                "XRP to XRP payments cannot skip the direct path",
+            ));
+        }
+    }
+
+    if !transaction.account.is_empty()
+        && transaction.account == transaction.destination
+        && paths.is_empty()
+    {
+        errors.push(PreflightError::new(
+            "temREDUNDANT",
+            "Destination",
+            "a payment to the sending account needs paths",
+        ));
+    }
+
+    if paths.len() > MAX_PATHS || paths.iter().any(|path| path.len() > MAX_PATH_LENGTH) {
+        errors.push(PreflightError::new(
+            "temMALFORMED",
+            "Paths",
+            format!("at most {} paths of {} steps are allowed", MAX_PATHS, MAX_PATH_LENGTH),
+        ));
+    }
+    if paths.iter().any(|path| path.is_empty()) {
+        errors.push(PreflightError::new("temBAD_PATH", "Paths", "contains an empty path"));
+    }
+    let mut seen = HashSet::new();
+    if paths.iter().any(|path| !seen.insert(serde_json::to_string(path).unwrap_or_default())) {
+        errors.push(PreflightError::new("temREDUNDANT", "Paths", "contains the same path more than once"));
+    }
+
+    if let Some(deliver_min) = &transaction.deliver_min {
+        if flags & TF_PARTIAL_PAYMENT == 0 {
+            errors.push(PreflightError::new("temBAD_AMOUNT", "DeliverMin", "requires tfPartialPayment"));
+        }
+        check_amount(deliver_min, &transaction.currency, "DeliverMin", errors);
+    }
+}
+
+fn check_trust_set(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    check_flags(
+        transaction,
+        TF_SETF_AUTH | TF_SET_NO_RIPPLE | TF_CLEAR_NO_RIPPLE | TF_SET_FREEZE | TF_CLEAR_FREEZE,
+        errors,
+    );
+    check_exclusive_flags(
+        transaction,
+        &[(TF_SET_NO_RIPPLE, TF_CLEAR_NO_RIPPLE), (TF_SET_FREEZE, TF_CLEAR_FREEZE)],
+        errors,
+    );
+
+    if transaction.currency == "XRP" {
+        errors.push(PreflightError::new("temBAD_CURRENCY", "LimitAmount", "cannot be XRP"));
+    } else if let Err(message) = check_currency_code(&transaction.currency) {
+        errors.push(PreflightError::new("temBAD_CURRENCY", "LimitAmount", message));
+    }
+
+    match transaction.issuer.as_deref() {
+        None | Some("") => errors.push(PreflightError::new("temDST_NEEDED", "LimitAmount", "needs an issuer")),
+        Some(issuer) if issuer == transaction.account => {
+            errors.push(PreflightError::new("temDST_IS_SRC", "LimitAmount", "issuer is the sending account"))
+        }
+        Some(_) => {}
+    }
+
+    if !matches!(transaction.amount.parse::<IouValue>(), Ok(limit) if !limit.is_negative()) {
+        errors.push(PreflightError::new(
+            "temBAD_LIMIT",
+            "LimitAmount",
+            format!("must be a non-negative token amount, got {:?}", transaction.amount),
+        ));
+    }
+}
+
+fn check_account_set(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    check_flags(
+        transaction,
+        TF_REQUIRE_DEST_TAG | TF_OPTIONAL_DEST_TAG | TF_REQUIRE_AUTH | TF_OPTIONAL_AUTH | TF_DISALLOW_XRP | TF_ALLOW_XRP,
+        errors,
+    );
+    check_exclusive_flags(
+        transaction,
+        &[
+            (TF_REQUIRE_DEST_TAG, TF_OPTIONAL_DEST_TAG),
+            (TF_REQUIRE_AUTH, TF_OPTIONAL_AUTH),
+            (TF_DISALLOW_XRP, TF_ALLOW_XRP),
+        ],
+        errors,
+    );
+}
+
+fn check_ticket_create(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    check_flags(transaction, 0, errors);
+
+    if !matches!(transaction.ticket_count, Some(count) if (1..=MAX_TICKET_COUNT).contains(&count)) {
+        errors.push(PreflightError::new(
+            "temINVALID_COUNT",
+            "TicketCount",
+            format!("must be between 1 and {}", MAX_TICKET_COUNT),
+        ));
+    }
+}
+
+fn check_offer_create(transaction: &Transaction, errors: &mut Vec<PreflightError>) {
+    check_flags(transaction, TF_PASSIVE | TF_IMMEDIATE_OR_CANCEL | TF_FILL_OR_KILL | TF_SELL, errors);
+    check_exclusive_flags(transaction, &[(TF_IMMEDIATE_OR_CANCEL, TF_FILL_OR_KILL)], errors);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SENDER: &str = "rSender123456789012345678901234";
+    const RECEIVER: &str = "rReceiver1234567890123456789012";
+    const ISSUER: &str = "rIssuer123456789012345678901234";
+
+    fn payment(amount: &str, currency: &str, issuer: Option<&str>) -> Transaction {
+        Transaction {
+            account: SENDER.to_string(),
+            destination: RECEIVER.to_string(),
+            amount: amount.to_string(),
+            currency: currency.to_string(),
+            issuer: issuer.map(str::to_string),
+            sequence: 1,
+            ..Transaction::default()
+        }
+    }
+
+    fn codes(transaction: &Transaction) -> Vec<&'static str> {
+        preflight(transaction).iter().map(|error| error.code).collect()
+    }
+
+    #[test]
+    fn test_valid_transactions() {
+        assert!(preflight(&payment("1000", "XRP", None)).is_empty());
+        assert!(preflight(&payment("1.5", "USD", Some(ISSUER))).is_empty());
+
+        let trust_set = Transaction {
+            transaction_type: "TrustSet".to_string(),
+            destination: String::new(),
+            flags: Some(TF_SET_NO_RIPPLE),
+            ..payment("100", "USD", Some(ISSUER))
+        };
+        assert!(preflight(&trust_set).is_empty());
+    }
+
+    #[test]
+    fn test_bad_amounts() {
+        assert_eq!(codes(&payment("0", "XRP", None)), vec!["temBAD_AMOUNT"]);
+        assert_eq!(codes(&payment("1.5", "XRP", None)), vec!["temBAD_AMOUNT"]);
+        assert_eq!(codes(&payment("-1", "USD", Some(ISSUER))), vec!["temBAD_AMOUNT"]);
+        assert_eq!(codes(&payment("1", "USD", None)), vec!["temBAD_ISSUER"]);
+        assert!(codes(&payment("1", "usd", Some(ISSUER))).is_empty());
+        assert!(codes(&payment("1", "0158415500000000C1F76FF6ECB0BAC600000000", Some(ISSUER))).is_empty());
+        assert_eq!(codes(&payment("1", "USDC", Some(ISSUER))), vec!["temBAD_CURRENCY"]);
+        assert_eq!(codes(&payment("1", "U D", Some(ISSUER))), vec!["temBAD_CURRENCY"]);
+        assert_eq!(codes(&payment("1", &"0".repeat(40), Some(ISSUER))), vec!["temBAD_CURRENCY"]);
+
+        let errors = preflight(&Transaction { fee: "ten".to_string(), ..payment("1", "XRP", None) });
+        assert_eq!(errors[0].field, "Fee");
+    }
+
+    #[test]
+    fn test_xrp_payment_rules() {
+        let tx = Transaction {
+            flags: Some(TF_PARTIAL_PAYMENT),
+            paths: Some(vec![vec![json!({ "currency": "USD" })]]),
+            ..payment("1000", "XRP", None)
+        };
+        assert_eq!(codes(&tx), vec!["temBAD_SEND_XRP_PATHS", "temBAD_SEND_XRP_PARTIAL"]);
+    }
+
+    #[test]
+    fn test_redundant_payments_and_paths() {
+        let to_self = Transaction {
+            destination: SENDER.to_string(),
+            ..payment("1", "USD", Some(ISSUER))
+        };
+        let errors = preflight(&to_self);
+        assert_eq!(errors[0].code, "temREDUNDANT");
+        assert_eq!(errors[0].field, "Destination");
+
+        // SendMax has the Amount's issue in this model, so it changes nothing
+        let with_send_max = Transaction {
+            send_max: Some("2".to_string()),
+            ..to_self.clone()
+        };
+        assert_eq!(codes(&with_send_max), vec!["temREDUNDANT"]);
+
+        let step = json!({ "account": ISSUER });
+        let tx = Transaction {
+            paths: Some(vec![vec![step.clone()], vec![step], vec![]]),
+            ..to_self
+        };
+        assert_eq!(codes(&tx), vec!["temBAD_PATH", "temREDUNDANT"]);
+    }
+
+    #[test]
+    fn test_flags_and_expiration() {
+        let tx = Transaction {
+            flags: Some(TF_SET_FREEZE),
+            ..payment("1", "USD", Some(ISSUER))
+        };
+        assert_eq!(codes(&tx), vec!["temINVALID_FLAG"]);
+
+        let trust_set = Transaction {
+            transaction_type: "TrustSet".to_string(),
+            flags: Some(TF_SET_NO_RIPPLE | TF_CLEAR_NO_RIPPLE | TF_FULLY_CANONICAL_SIG),
+            ..payment("100", "USD", Some(SENDER))
+        };
+        assert_eq!(codes(&trust_set), vec!["temINVALID_FLAG", "temDST_IS_SRC"]);
+
+        let offer = Transaction {
+            transaction_type: "OfferCreate".to_string(),
+            flags: Some(TF_IMMEDIATE_OR_CANCEL | TF_FILL_OR_KILL),
+            expiration: Some(0),
+            ..Transaction::default()
+        };
+        let errors = preflight(&Transaction { account: SENDER.to_string(), ..offer });
+        assert_eq!(errors[0].code, "temINVALID_FLAG");
+        assert_eq!(errors[1].code, "temBAD_EXPIRATION");
+        assert_eq!(errors[1].field, "Expiration");
+
+        let channel_fund = Transaction {
+            transaction_type: "PaymentChannelFund".to_string(),
+            account: SENDER.to_string(),
+            expiration: Some(750_000_000),
+            ..Transaction::default()
+        };
+        assert!(codes(&channel_fund).is_empty());
+
+        let nft_offer = Transaction {
+            transaction_type: "NFTokenCreateOffer".to_string(),
+            account: SENDER.to_string(),
+            expiration: Some(0),
+            ..Transaction::default()
+        };
+        assert_eq!(codes(&nft_offer), vec!["temBAD_EXPIRATION"]);
+    }
+
+    #[test]
+    fn test_ticket_create() {
+        let tx = Transaction {
+            transaction_type: "TicketCreate".to_string(),
+            account: SENDER.to_string(),
+            sequence: 3,
+            ticket_sequence: Some(2),
+            ticket_count: Some(0),
+            ..Transaction::default()
+        };
+        assert_eq!(codes(&tx), vec!["temSEQ_AND_TICKET", "temINVALID_COUNT"]);
+    }
+}
@@ -0,0 +1,537 @@
+use super::{lenient_u32, LedgerIndex, Request};
+use crate::amount::CurrencyAmount;
+use crate::history::AccountTxRequest;
+use crate::types::*;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// AccountRoot flag: incoming payments must carry a destination tag
+pub const LSF_REQUIRE_DEST_TAG: u32 = 0x0002_0000;
+/// AccountRoot flag: trust lines to this issuer must be authorized
+pub const LSF_REQUIRE_AUTH: u32 = 0x0004_0000;
+/// AccountRoot flag: the owner asks not to be sent XRP; not enforced
+pub const LSF_DISALLOW_XRP: u32 = 0x0008_0000;
+/// AccountRoot flag: only preauthorized senders may deposit
+pub const LSF_DEPOSIT_AUTH: u32 = 0x0100_0000;
+
+/// An `AccountRoot` ledger entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccountRoot {
+    pub account: Address,
+    /// Drops
+    pub balance: Amount,
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default)]
+    pub owner_count: u32,
+    pub sequence: Sequence,
+    #[serde(rename = "PreviousTxnID", default, skip_serializing_if = "Option::is_none")]
+    pub previous_txn_id: Option<TransactionHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_txn_lgr_seq: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_rate: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regular_key: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick_size: Option<u8>,
+    #[serde(rename = "index", default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+}
+
+impl AccountRoot {
+    /// Whether payments to this account need a destination tag
+    pub fn requires_destination_tag(&self) -> bool {
+        self.flags & LSF_REQUIRE_DEST_TAG != 0
+    }
+
+    /// Whether trust lines to this account need its authorization
+    pub fn requires_auth(&self) -> bool {
+        self.flags & LSF_REQUIRE_AUTH != 0
+    }
+
+    pub fn disallows_xrp(&self) -> bool {
+        self.flags & LSF_DISALLOW_XRP != 0
+    }
+
+    /// Whether deposits need the owner's preauthorization
+    pub fn requires_deposit_auth(&self) -> bool {
+        self.flags & LSF_DEPOSIT_AUTH != 0
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountInfoRequest {
+    pub account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    /// Include queued transactions; requires the current ledger
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer_lists: Option<bool>,
+}
+
+impl AccountInfoRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ledger_index: Some(LedgerIndex::Validated),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountInfoResponse {
+    pub account_data: AccountRoot,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub queue_data: Option<Value>,
+    #[serde(default)]
+    pub signer_lists: Option<Value>,
+}
+
+impl Request for AccountInfoRequest {
+    type Response = AccountInfoResponse;
+    const METHOD: &'static str = "account_info";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountChannelsRequest {
+    pub account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_account: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl AccountChannelsRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountChannel {
+    pub account: Address,
+    /// Total drops allocated to the channel
+    pub amount: Amount,
+    /// Drops already paid out
+    pub balance: Amount,
+    pub channel_id: String,
+    pub destination_account: Address,
+    pub settle_delay: u32,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub public_key_hex: Option<String>,
+    #[serde(default)]
+    pub expiration: Option<u32>,
+    #[serde(default)]
+    pub cancel_after: Option<u32>,
+    #[serde(default)]
+    pub source_tag: Option<u32>,
+    #[serde(default)]
+    pub destination_tag: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountChannelsResponse {
+    pub account: Address,
+    pub channels: Vec<AccountChannel>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountChannelsRequest {
+    type Response = AccountChannelsResponse;
+    const METHOD: &'static str = "account_channels";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountCurrenciesRequest {
+    pub account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+}
+
+impl AccountCurrenciesRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountCurrenciesResponse {
+    pub receive_currencies: Vec<CurrencyCode>,
+    pub send_currencies: Vec<CurrencyCode>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for AccountCurrenciesRequest {
+    type Response = AccountCurrenciesResponse;
+    const METHOD: &'static str = "account_currencies";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountLinesRequest {
+    pub account: Address,
+    /// Only lines with this counterparty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl AccountLinesRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountLinesResponse {
+    pub account: Address,
+    pub lines: Vec<TrustLine>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountLinesRequest {
+    type Response = AccountLinesResponse;
+    const METHOD: &'static str = "account_lines";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountNftsRequest {
+    pub account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl AccountNftsRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountNft {
+    #[serde(rename = "Flags")]
+    pub flags: u32,
+    #[serde(rename = "Issuer")]
+    pub issuer: Address,
+    #[serde(rename = "NFTokenID")]
+    pub nftoken_id: String,
+    #[serde(rename = "NFTokenTaxon")]
+    pub nftoken_taxon: u32,
+    #[serde(rename = "URI", default)]
+    pub uri: Option<String>,
+    pub nft_serial: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountNftsResponse {
+    pub account: Address,
+    pub account_nfts: Vec<AccountNft>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountNftsRequest {
+    type Response = AccountNftsResponse;
+    const METHOD: &'static str = "account_nfts";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountObjectsRequest {
+    pub account: Address,
+    /// Only objects of this type, e.g. `offer` or `state`
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+    /// Only objects that would block deleting the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_blockers_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl AccountObjectsRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountObjectsResponse {
+    pub account: Address,
+    /// Raw ledger entries; their shape depends on `LedgerEntryType`
+    pub account_objects: Vec<Value>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountObjectsRequest {
+    type Response = AccountObjectsResponse;
+    const METHOD: &'static str = "account_objects";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccountOffersRequest {
+    pub account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl AccountOffersRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountOffer {
+    pub flags: u32,
+    pub seq: Sequence,
+    pub taker_gets: CurrencyAmount,
+    pub taker_pays: CurrencyAmount,
+    /// Exchange rate as a decimal string
+    pub quality: String,
+    #[serde(default)]
+    pub expiration: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountOffersResponse {
+    pub account: Address,
+    pub offers: Vec<AccountOffer>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountOffersRequest {
+    type Response = AccountOffersResponse;
+    const METHOD: &'static str = "account_offers";
+}
+
+/// Serialized without a marker; use `XrplClient::account_tx` or
+/// `account_tx_stream` to page through history
+impl Serialize for AccountTxRequest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_params(None).serialize(serializer)
+    }
+}
+
+/// One raw page of `account_tx`; `AccountTransaction::from_entry` parses the
+/// entries
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountTxResponse {
+    pub account: Address,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index_min: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index_max: Option<u32>,
+    pub transactions: Vec<Value>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for AccountTxRequest {
+    type Response = AccountTxResponse;
+    const METHOD: &'static str = "account_tx";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GatewayBalancesRequest {
+    pub account: Address,
+    /// Operational addresses to list separately from obligations
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hotwallet: Vec<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+}
+
+impl GatewayBalancesRequest {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyBalance {
+    pub currency: CurrencyCode,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GatewayBalancesResponse {
+    pub account: Address,
+    /// Total issued per currency, excluding hot wallets
+    #[serde(default)]
+    pub obligations: HashMap<CurrencyCode, String>,
+    /// Balances held by the hot wallets
+    #[serde(default)]
+    pub balances: HashMap<Address, Vec<CurrencyBalance>>,
+    /// Balances the gateway holds issued by others
+    #[serde(default)]
+    pub assets: HashMap<Address, Vec<CurrencyBalance>>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for GatewayBalancesRequest {
+    type Response = GatewayBalancesResponse;
+    const METHOD: &'static str = "gateway_balances";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NoRippleCheckRequest {
+    pub account: Address,
+    /// `gateway` or `user`
+    pub role: String,
+    /// Include suggested transactions fixing the problems
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+}
+
+impl NoRippleCheckRequest {
+    pub fn new(account: &str, role: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            role: role.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NoRippleCheckResponse {
+    pub problems: Vec<String>,
+    #[serde(default)]
+    pub transactions: Vec<Value>,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for NoRippleCheckRequest {
+    type Response = NoRippleCheckResponse;
+    const METHOD: &'static str = "noripple_check";
+}
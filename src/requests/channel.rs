@@ -0,0 +1,48 @@
+use super::Request;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+/// Sign a payment channel claim on the server.
+///
+/// This sends the secret to the server; only use it with a server you run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChannelAuthorizeRequest {
+    pub channel_id: String,
+    /// Cumulative drops the claim authorizes
+    pub amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChannelAuthorizeResponse {
+    pub signature: String,
+}
+
+impl Request for ChannelAuthorizeRequest {
+    type Response = ChannelAuthorizeResponse;
+    const METHOD: &'static str = "channel_authorize";
+}
+
+/// Check a payment channel claim signature
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChannelVerifyRequest {
+    pub channel_id: String,
+    pub amount: Amount,
+    pub public_key: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChannelVerifyResponse {
+    pub signature_verified: bool,
+}
+
+impl Request for ChannelVerifyRequest {
+    type Response = ChannelVerifyResponse;
+    const METHOD: &'static str = "channel_verify";
+}
@@ -0,0 +1,215 @@
+use super::{lenient_u32, LedgerIndex, Request};
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    /// Include the ledger's transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<bool>,
+    /// Return transactions as JSON instead of hashes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expand: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_funds: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<bool>,
+}
+
+impl LedgerRequest {
+    pub fn new(ledger_index: LedgerIndex) -> Self {
+        Self {
+            ledger_index: Some(ledger_index),
+            ..Default::default()
+        }
+    }
+}
+
+/// The header of a ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerHeader {
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    #[serde(default)]
+    pub parent_hash: Option<String>,
+    #[serde(default)]
+    pub account_hash: Option<String>,
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    /// Seconds since the Ripple epoch
+    #[serde(default)]
+    pub close_time: Option<Timestamp>,
+    #[serde(default)]
+    pub close_time_iso: Option<String>,
+    #[serde(default)]
+    pub parent_close_time: Option<Timestamp>,
+    #[serde(default)]
+    pub close_time_resolution: Option<u32>,
+    /// Total drops in existence
+    #[serde(default)]
+    pub total_coins: Option<String>,
+    #[serde(default)]
+    pub closed: bool,
+    /// Hashes or expanded transactions, when requested
+    #[serde(default)]
+    pub transactions: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LedgerResponse {
+    pub ledger: LedgerHeader,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub queue_data: Option<Vec<Value>>,
+}
+
+impl Request for LedgerRequest {
+    type Response = LedgerResponse;
+    const METHOD: &'static str = "ledger";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerClosedRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LedgerClosedResponse {
+    pub ledger_hash: String,
+    pub ledger_index: u32,
+}
+
+impl Request for LedgerClosedRequest {
+    type Response = LedgerClosedResponse;
+    const METHOD: &'static str = "ledger_closed";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerCurrentRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LedgerCurrentResponse {
+    pub ledger_current_index: u32,
+}
+
+impl Request for LedgerCurrentRequest {
+    type Response = LedgerCurrentResponse;
+    const METHOD: &'static str = "ledger_current";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerDataRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+    /// Only entries of this type, e.g. `account` or `offer`
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub entry_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LedgerDataResponse {
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    /// Raw ledger entries, or `{data, index}` pairs in binary mode
+    pub state: Vec<Value>,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for LedgerDataRequest {
+    type Response = LedgerDataResponse;
+    const METHOD: &'static str = "ledger_data";
+}
+
+/// Look up a single ledger entry. Set exactly one selector: `index` or one of
+/// the typed ones (`account_root`, `offer`, `ripple_state`, ...), which take
+/// rippled's JSON form.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerEntryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_root: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ripple_state: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escrow: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_preauth: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+}
+
+impl LedgerEntryRequest {
+    pub fn by_index(index: &str) -> Self {
+        Self {
+            index: Some(index.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn account_root(account: &str) -> Self {
+        Self {
+            account_root: Some(account.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LedgerEntryResponse {
+    pub index: String,
+    /// The entry as JSON; absent in binary mode
+    #[serde(default)]
+    pub node: Option<Value>,
+    /// The entry as hex; only in binary mode
+    #[serde(default)]
+    pub node_binary: Option<String>,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for LedgerEntryRequest {
+    type Response = LedgerEntryResponse;
+    const METHOD: &'static str = "ledger_entry";
+}
@@ -0,0 +1,282 @@
+//! Typed models of the public rippled API, except the streaming methods
+//! handled by WebSocket subscriptions, plus Clio's NFT methods.
+//!
+//! Every request struct implements `Request`, naming its method and response
+//! type, and can be sent with `XrplClient::request`. Responses model the
+//! `result` object; fields rippled only includes in some cases are optional.
+//! `XrplClient::request_raw` sends arbitrary JSON for anything not covered.
+
+mod account;
+mod channel;
+mod ledger;
+mod path;
+mod server;
+mod transaction;
+mod utility;
+
+pub use account::*;
+pub use channel::*;
+pub use ledger::*;
+pub use path::*;
+pub use server::*;
+pub use transaction::*;
+pub use utility::*;
+
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::retry::NON_IDEMPOTENT_METHODS;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+
+/// A rippled API method with typed parameters and result
+pub trait Request: Serialize {
+    type Response: DeserializeOwned;
+
+    /// The method name, e.g. `account_info`
+    const METHOD: &'static str;
+
+    /// Whether sending the request twice has the same effect as once. Other
+    /// requests are never retried blindly.
+    const IDEMPOTENT: bool = true;
+}
+
+/// Which ledger a request reads from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LedgerIndex {
+    /// The most recent validated ledger
+    #[default]
+    Validated,
+    /// The most recently closed, possibly not yet validated, ledger
+    Closed,
+    /// The open ledger still receiving transactions
+    Current,
+    Index(u32),
+}
+
+impl Serialize for LedgerIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            LedgerIndex::Validated => serializer.serialize_str("validated"),
+            LedgerIndex::Closed => serializer.serialize_str("closed"),
+            LedgerIndex::Current => serializer.serialize_str("current"),
+            LedgerIndex::Index(index) => serializer.serialize_u32(*index),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LedgerIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(name) => match name.as_str() {
+                "validated" => Ok(LedgerIndex::Validated),
+                "closed" => Ok(LedgerIndex::Closed),
+                "current" => Ok(LedgerIndex::Current),
+                index => index
+                    .parse()
+                    .map(LedgerIndex::Index)
+                    .map_err(|_| serde::de::Error::custom(format!("invalid ledger index {}", index))),
+            },
+            Value::Number(index) => index
+                .as_u64()
+                .map(|index| LedgerIndex::Index(index as u32))
+                .ok_or_else(|| serde::de::Error::custom("invalid ledger index")),
+            other => Err(serde::de::Error::custom(format!("invalid ledger index {}", other))),
+        }
+    }
+}
+
+/// Ledger sequence numbers are strings in some API v1 responses
+pub(crate) fn lenient_u32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(number)) => Ok(number.as_u64().map(|n| n as u32)),
+        Some(Value::String(text)) => text.parse().map(Some).map_err(serde::de::Error::custom),
+        Some(other) => Err(serde::de::Error::custom(format!("expected a ledger sequence, got {}", other))),
+    }
+}
+
+/// Drop amounts and counters are strings in `fee` and some other results
+pub(crate) fn string_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => number.as_u64().ok_or_else(|| serde::de::Error::custom("expected an unsigned integer")),
+        Value::String(text) => text.parse().map_err(serde::de::Error::custom),
+        other => Err(serde::de::Error::custom(format!("expected an unsigned integer, got {}", other))),
+    }
+}
+
+impl XrplClient {
+    /// Send a typed request and parse its result.
+    ///
+    /// Transient failures are retried unless the request is not idempotent,
+    /// like submits; use `submit_transaction` to have those retried once a
+    /// hash lookup shows the earlier attempt did not land.
+    pub async fn request<R: Request>(&self, request: &R) -> Result<R::Response> {
+        let params = serde_json::to_value(request).map_err(|e| XrplError::Serialization(e.to_string()))?;
+        let result = self.send_method(R::METHOD, params, R::IDEMPOTENT).await?;
+
+        serde_json::from_value(result).map_err(|e| XrplError::Deserialization(e.to_string()).into())
+    }
+
+    /// Call any method with raw JSON params and return the raw `result`.
+    /// `submit` and `submit_multisigned` are sent once, without retries.
+    pub async fn request_raw(&self, method: &str, params: Value) -> Result<Value> {
+        self.send_method(method, params, true).await
+    }
+
+    async fn send_method(&self, method: &str, params: Value, idempotent: bool) -> Result<Value> {
+        let request = json!({
+            "method": method,
+            "params": [params]
+        });
+
+        // A repeated submit could apply twice, and a sign-and-submit would be
+        // signed again with a fresh Sequence
+        let response: Value = if idempotent && !NON_IDEMPOTENT_METHODS.contains(&method) {
+            self.make_request(&request).await?
+        } else {
+            self.send_to_pool(&request).await?
+        };
+
+        if let Some(error) = response["result"]["error"].as_str() {
+            return Err(XrplError::ApiError(error.to_string()).into());
+        }
+
+        Ok(response["result"].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ledger_index_serde() {
+        assert_eq!(serde_json::to_value(LedgerIndex::Validated).unwrap(), json!("validated"));
+        assert_eq!(serde_json::to_value(LedgerIndex::Index(5)).unwrap(), json!(5));
+        assert_eq!(serde_json::from_value::<LedgerIndex>(json!("12")).unwrap(), LedgerIndex::Index(12));
+        assert_eq!(serde_json::from_value::<LedgerIndex>(json!("current")).unwrap(), LedgerIndex::Current);
+    }
+
+    #[tokio::test]
+    async fn test_typed_request() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", json!({
+            "account_data": {
+                "Account": "rAccount",
+                "Balance": "25000000",
+                "Flags": 0,
+                "LedgerEntryType": "AccountRoot",
+                "OwnerCount": 1,
+                "Sequence": 9
+            },
+            "ledger_index": 77,
+            "validated": true
+        }));
+        let client = client(&mock);
+
+        let response = client.request(&AccountInfoRequest::new("rAccount")).await.unwrap();
+        assert_eq!(response.account_data.sequence, 9);
+        assert_eq!(response.account_data.balance, "25000000");
+        assert_eq!(response.ledger_index, Some(77));
+
+        let params = &mock.requests_for("account_info")[0];
+        assert_eq!(params["account"], "rAccount");
+        assert_eq!(params["ledger_index"], "validated");
+    }
+
+    #[tokio::test]
+    async fn test_tx_request_v1_shape() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("tx", json!({
+            "hash": "ABC",
+            "ledger_index": 9,
+            "validated": true,
+            "TransactionType": "AccountSet",
+            "Account": "rAccount",
+            "Fee": "12",
+            "Sequence": 4,
+            "meta": { "TransactionIndex": 0, "TransactionResult": "tesSUCCESS", "AffectedNodes": [] }
+        }));
+        let client = client(&mock);
+
+        let tx = client.request(&TxRequest::new("ABC")).await.unwrap();
+        assert_eq!(tx.tx_json.sequence, 4);
+        assert_eq!(tx.transaction_result(), Some("tesSUCCESS"));
+        assert_eq!(mock.requests_for("tx")[0], json!({ "transaction": "ABC", "api_version": 1 }));
+    }
+
+    #[tokio::test]
+    async fn test_raw_request() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("vl", json!({ "version": 2 }));
+        mock.on_error("feature", "noPermission");
+        let client = client(&mock);
+
+        assert_eq!(client.request_raw("vl", json!({})).await.unwrap()["version"], 2);
+        assert_eq!(mock.request_count("vl"), 1);
+        assert_eq!(
+            client.request(&FeatureRequest::default()).await.unwrap_err().to_string(),
+            "XRPL API error: noPermission"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_version() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("sign", json!({ "tx_blob": "1200", "tx_json": { "TransactionType": "AccountSet" } }));
+        mock.on("version", json!({ "version": { "first": "1.0.0", "good": "2.0.0", "last": "2.0.0" } }));
+        mock.on("nft_info", json!({
+            "nft_id": "000800",
+            "ledger_index": 270,
+            "owner": "rOwner",
+            "is_burned": false,
+            "flags": 8,
+            "transfer_fee": 0,
+            "issuer": "rIssuer",
+            "nft_taxon": 0,
+            "nft_serial": 4,
+            "uri": "697066733A2F2F",
+            "validated": true
+        }));
+        let client = client(&mock);
+
+        let signed = client
+            .request(&SignRequest::new(json!({ "TransactionType": "AccountSet" }), "s"))
+            .await
+            .unwrap();
+        assert_eq!(signed.tx_blob, "1200");
+        assert_eq!(mock.requests_for("sign")[0]["secret"], "s");
+
+        let version = client.request(&VersionRequest::default()).await.unwrap();
+        assert_eq!(version.version.last, "2.0.0");
+
+        let nft = client.request(&NftInfoRequest::new("000800")).await.unwrap();
+        assert_eq!((nft.owner.as_str(), nft.nft_serial, nft.is_burned), ("rOwner", 4, false));
+    }
+
+    #[tokio::test]
+    async fn test_submits_are_not_retried() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_error("submit", "tooBusy");
+        mock.on_error("submit_multisigned", "tooBusy");
+        mock.on_error("ledger", "tooBusy").on("ledger", json!({ "ledger_index": 5 }));
+        let client = client(&mock);
+
+        assert!(client.request(&SubmitRequest::new("1200")).await.is_err());
+        assert!(client
+            .request_raw("submit", json!({ "tx_json": {}, "secret": "s" }))
+            .await
+            .is_err());
+        assert!(client.request_raw("submit_multisigned", json!({ "tx_json": {} })).await.is_err());
+        assert_eq!(mock.request_count("submit"), 2);
+        assert_eq!(mock.request_count("submit_multisigned"), 1);
+
+        client.request_raw("ledger", json!({})).await.unwrap();
+        assert_eq!(mock.request_count("ledger"), 2);
+    }
+}
@@ -0,0 +1,411 @@
+use super::{lenient_u32, LedgerIndex, Request};
+use crate::amount::CurrencyAmount;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Offers on one side of an order book
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BookOffersRequest {
+    pub taker_gets: Issue,
+    pub taker_pays: Issue,
+    /// Account whose funds are used to compute `taker_gets_funded`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taker: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl BookOffersRequest {
+    pub fn new(taker_gets: Issue, taker_pays: Issue) -> Self {
+        Self {
+            taker_gets,
+            taker_pays,
+            taker: None,
+            ledger_index: Some(LedgerIndex::Validated),
+            limit: None,
+            marker: None,
+        }
+    }
+}
+
+/// An offer in an order book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BookOffer {
+    pub account: Address,
+    pub taker_gets: CurrencyAmount,
+    pub taker_pays: CurrencyAmount,
+    pub sequence: u32,
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default)]
+    pub expiration: Option<u32>,
+    #[serde(rename = "index")]
+    pub index: String,
+    /// Exchange rate as TakerPays / TakerGets
+    #[serde(rename = "quality", default)]
+    pub quality: Option<String>,
+    /// Amount the owner can actually fund, when less than `TakerGets`
+    #[serde(rename = "taker_gets_funded", default)]
+    pub taker_gets_funded: Option<CurrencyAmount>,
+    #[serde(rename = "taker_pays_funded", default)]
+    pub taker_pays_funded: Option<CurrencyAmount>,
+    #[serde(rename = "owner_funds", default)]
+    pub owner_funds: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookOffersResponse {
+    pub offers: Vec<BookOffer>,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub marker: Option<Value>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for BookOffersRequest {
+    type Response = BookOffersResponse;
+    const METHOD: &'static str = "book_offers";
+}
+
+/// Order book changes in a single ledger
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BookChangesRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookChange {
+    pub currency_a: String,
+    pub currency_b: String,
+    pub volume_a: String,
+    pub volume_b: String,
+    pub high: String,
+    pub low: String,
+    pub open: String,
+    pub close: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookChangesResponse {
+    pub changes: Vec<BookChange>,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    #[serde(default)]
+    pub ledger_time: Option<Timestamp>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for BookChangesRequest {
+    type Response = BookChangesResponse;
+    const METHOD: &'static str = "book_changes";
+}
+
+/// Whether `source_account` may send to `destination_account`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DepositAuthorizedRequest {
+    pub source_account: Address,
+    pub destination_account: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+}
+
+impl DepositAuthorizedRequest {
+    pub fn new(source: &str, destination: &str) -> Self {
+        Self {
+            source_account: source.to_string(),
+            destination_account: destination.to_string(),
+            ledger_index: Some(LedgerIndex::Validated),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DepositAuthorizedResponse {
+    pub deposit_authorized: bool,
+    pub source_account: Address,
+    pub destination_account: Address,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for DepositAuthorizedRequest {
+    type Response = DepositAuthorizedResponse;
+    const METHOD: &'static str = "deposit_authorized";
+}
+
+/// Open buy offers for an NFT
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NftBuyOffersRequest {
+    pub nft_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+/// Open sell offers for an NFT
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NftSellOffersRequest {
+    pub nft_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NftOffer {
+    pub nft_offer_index: String,
+    pub owner: Address,
+    pub amount: CurrencyAmount,
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default)]
+    pub destination: Option<Address>,
+    #[serde(default)]
+    pub expiration: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NftOffersResponse {
+    pub nft_id: String,
+    pub offers: Vec<NftOffer>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for NftBuyOffersRequest {
+    type Response = NftOffersResponse;
+    const METHOD: &'static str = "nft_buy_offers";
+}
+
+impl Request for NftSellOffersRequest {
+    type Response = NftOffersResponse;
+    const METHOD: &'static str = "nft_sell_offers";
+}
+
+/// Current state of an NFT; served by Clio, not rippled
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NftInfoRequest {
+    pub nft_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+}
+
+impl NftInfoRequest {
+    pub fn new(nft_id: &str) -> Self {
+        Self {
+            nft_id: nft_id.to_string(),
+            ledger_index: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NftInfoResponse {
+    pub nft_id: String,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    pub owner: Address,
+    pub is_burned: bool,
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default)]
+    pub transfer_fee: u32,
+    pub issuer: Address,
+    pub nft_taxon: u32,
+    pub nft_serial: u32,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for NftInfoRequest {
+    type Response = NftInfoResponse;
+    const METHOD: &'static str = "nft_info";
+}
+
+/// Transactions that touched an NFT; served by Clio, not rippled
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NftHistoryRequest {
+    pub nft_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index_min: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index_max: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl NftHistoryRequest {
+    pub fn new(nft_id: &str) -> Self {
+        Self {
+            nft_id: nft_id.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Entries have the same shape as `account_tx` ones
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NftHistoryResponse {
+    pub nft_id: String,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index_min: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index_max: Option<u32>,
+    pub transactions: Vec<Value>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+impl Request for NftHistoryRequest {
+    type Response = NftHistoryResponse;
+    const METHOD: &'static str = "nft_history";
+}
+
+/// One-shot path search
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RipplePathFindRequest {
+    pub source_account: Address,
+    pub destination_account: Address,
+    pub destination_amount: CurrencyAmount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_max: Option<CurrencyAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_currencies: Option<Vec<Issue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+}
+
+impl RipplePathFindRequest {
+    pub fn new(source: &str, destination: &str, destination_amount: CurrencyAmount) -> Self {
+        Self {
+            source_account: source.to_string(),
+            destination_account: destination.to_string(),
+            destination_amount,
+            send_max: None,
+            source_currencies: None,
+            ledger_index: None,
+        }
+    }
+}
+
+/// A way to deliver the destination amount
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PathAlternative {
+    /// What the source would spend
+    pub source_amount: CurrencyAmount,
+    /// Goes into the `Paths` field of a Payment
+    pub paths_computed: Vec<Vec<Value>>,
+    #[serde(default)]
+    pub destination_amount: Option<CurrencyAmount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RipplePathFindResponse {
+    pub alternatives: Vec<PathAlternative>,
+    pub destination_account: Address,
+    #[serde(default)]
+    pub destination_currencies: Vec<CurrencyCode>,
+    #[serde(default)]
+    pub source_account: Option<Address>,
+    #[serde(default)]
+    pub full_reply: Option<bool>,
+    #[serde(default)]
+    pub ledger_current_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for RipplePathFindRequest {
+    type Response = RipplePathFindResponse;
+    const METHOD: &'static str = "ripple_path_find";
+}
+
+/// State of the AMM for an asset pair
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AmmInfoRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<Issue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset2: Option<Issue>,
+    /// Look the AMM up by its account instead of the asset pair
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amm_account: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+}
+
+impl AmmInfoRequest {
+    pub fn new(asset: Issue, asset2: Issue) -> Self {
+        Self {
+            asset: Some(asset),
+            asset2: Some(asset2),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AmmDescription {
+    pub account: Address,
+    pub amount: CurrencyAmount,
+    pub amount2: CurrencyAmount,
+    pub lp_token: CurrencyAmount,
+    /// In units of 1/100,000
+    pub trading_fee: u32,
+    #[serde(default)]
+    pub asset_frozen: Option<bool>,
+    #[serde(default)]
+    pub asset2_frozen: Option<bool>,
+    #[serde(default)]
+    pub auction_slot: Option<Value>,
+    #[serde(default)]
+    pub vote_slots: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AmmInfoResponse {
+    pub amm: AmmDescription,
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+impl Request for AmmInfoRequest {
+    type Response = AmmInfoResponse;
+    const METHOD: &'static str = "amm_info";
+}
@@ -0,0 +1,321 @@
+use super::{string_u64, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Human-oriented server status; amounts are in XRP
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ServerInfoRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counters: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerInfoResponse {
+    pub info: ServerInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerInfo {
+    pub build_version: String,
+    /// e.g. `full`, `syncing` or `disconnected`
+    pub server_state: String,
+    #[serde(default)]
+    pub complete_ledgers: Option<String>,
+    #[serde(default)]
+    pub hostid: Option<String>,
+    #[serde(default)]
+    pub network_id: Option<u32>,
+    #[serde(default)]
+    pub load_factor: Option<f64>,
+    #[serde(default)]
+    pub peers: Option<u32>,
+    #[serde(default)]
+    pub pubkey_node: Option<String>,
+    #[serde(default)]
+    pub uptime: Option<u64>,
+    #[serde(default)]
+    pub validation_quorum: Option<u32>,
+    /// Absent while the server has no validated ledger
+    #[serde(default)]
+    pub validated_ledger: Option<ValidatedLedgerInfo>,
+    #[serde(default)]
+    pub closed_ledger: Option<ValidatedLedgerInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ValidatedLedgerInfo {
+    /// Seconds since the ledger closed
+    #[serde(default)]
+    pub age: Option<u32>,
+    pub base_fee_xrp: f64,
+    pub reserve_base_xrp: f64,
+    pub reserve_inc_xrp: f64,
+    pub hash: String,
+    pub seq: u32,
+}
+
+impl ServerInfo {
+    /// Seconds since the last validated ledger closed
+    pub fn validated_ledger_age(&self) -> Option<u32> {
+        self.validated_ledger.as_ref().and_then(|ledger| ledger.age)
+    }
+
+    /// Multiplier on the base fee due to server and network load
+    pub fn load_factor(&self) -> f64 {
+        self.load_factor.unwrap_or(1.0)
+    }
+}
+
+impl ValidatedLedgerInfo {
+    pub fn base_fee_drops(&self) -> u64 {
+        xrp_to_drops(self.base_fee_xrp)
+    }
+
+    pub fn reserve_base_drops(&self) -> u64 {
+        xrp_to_drops(self.reserve_base_xrp)
+    }
+
+    /// Reserve per owned object
+    pub fn reserve_inc_drops(&self) -> u64 {
+        xrp_to_drops(self.reserve_inc_xrp)
+    }
+}
+
+fn xrp_to_drops(xrp: f64) -> u64 {
+    (xrp * 1_000_000.0).round() as u64
+}
+
+impl Request for ServerInfoRequest {
+    type Response = ServerInfoResponse;
+    const METHOD: &'static str = "server_info";
+}
+
+/// Machine-oriented server status; amounts are in drops
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ServerStateRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerStateResponse {
+    pub state: ServerState,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerState {
+    pub build_version: String,
+    pub server_state: String,
+    #[serde(default)]
+    pub complete_ledgers: Option<String>,
+    #[serde(default)]
+    pub network_id: Option<u32>,
+    /// Divide `load_factor` by this for the multiplier on the base fee
+    #[serde(default)]
+    pub load_base: Option<u64>,
+    #[serde(default)]
+    pub load_factor: Option<u64>,
+    #[serde(default)]
+    pub peers: Option<u32>,
+    #[serde(default)]
+    pub validated_ledger: Option<ValidatedLedgerState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ValidatedLedgerState {
+    pub base_fee: u64,
+    pub reserve_base: u64,
+    pub reserve_inc: u64,
+    pub hash: String,
+    pub seq: u32,
+    #[serde(default)]
+    pub close_time: Option<u32>,
+}
+
+impl ServerState {
+    /// Multiplier on the base fee due to server and network load
+    pub fn load_multiplier(&self) -> f64 {
+        match (self.load_factor, self.load_base) {
+            (Some(factor), Some(base)) if base > 0 => factor as f64 / base as f64,
+            _ => 1.0,
+        }
+    }
+}
+
+impl Request for ServerStateRequest {
+    type Response = ServerStateResponse;
+    const METHOD: &'static str = "server_state";
+}
+
+/// Current transaction cost and queue state
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeeRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeeResponse {
+    /// Transactions in the open ledger
+    #[serde(deserialize_with = "string_u64")]
+    pub current_ledger_size: u64,
+    /// Transactions waiting in the queue
+    #[serde(deserialize_with = "string_u64")]
+    pub current_queue_size: u64,
+    /// Transactions the server expects in the next ledger before escalating fees
+    #[serde(deserialize_with = "string_u64")]
+    pub expected_ledger_size: u64,
+    pub ledger_current_index: u32,
+    #[serde(default, deserialize_with = "optional_string_u64")]
+    pub max_queue_size: Option<u64>,
+    pub drops: FeeDrops,
+    pub levels: FeeLevels,
+}
+
+/// Transaction costs in drops
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeeDrops {
+    /// The reference transaction cost without load scaling
+    #[serde(deserialize_with = "string_u64")]
+    pub base_fee: u64,
+    /// Median fee of the transactions in the last validated ledger
+    #[serde(deserialize_with = "string_u64")]
+    pub median_fee: u64,
+    /// Minimum to get into the queue
+    #[serde(deserialize_with = "string_u64")]
+    pub minimum_fee: u64,
+    /// Minimum to get into the open ledger right now
+    #[serde(deserialize_with = "string_u64")]
+    pub open_ledger_fee: u64,
+}
+
+/// Fee levels, where `reference_level` corresponds to the base fee. A
+/// transaction's level is its fee relative to its own base cost, which makes
+/// them comparable across multisigned and other non-standard transactions.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeeLevels {
+    #[serde(deserialize_with = "string_u64")]
+    pub median_level: u64,
+    #[serde(deserialize_with = "string_u64")]
+    pub minimum_level: u64,
+    #[serde(deserialize_with = "string_u64")]
+    pub open_ledger_level: u64,
+    #[serde(deserialize_with = "string_u64")]
+    pub reference_level: u64,
+}
+
+impl FeeResponse {
+    /// Whether the queue cannot take more transactions at `minimum_fee`
+    pub fn queue_is_full(&self) -> bool {
+        self.max_queue_size
+            .is_some_and(|max| self.current_queue_size >= max)
+    }
+}
+
+fn optional_string_u64<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    string_u64(deserializer).map(Some)
+}
+
+impl Request for FeeRequest {
+    type Response = FeeResponse;
+    const METHOD: &'static str = "fee";
+}
+
+/// Latest manifest of a validator
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ManifestRequest {
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ManifestResponse {
+    pub requested: String,
+    #[serde(default)]
+    pub manifest: Option<String>,
+    #[serde(default)]
+    pub details: Option<Value>,
+}
+
+impl Request for ManifestRequest {
+    type Response = ManifestResponse;
+    const METHOD: &'static str = "manifest";
+}
+
+/// Amendment status; admin-only unless just reading
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeatureRequest {
+    /// A single amendment by name or ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeatureStatus {
+    pub enabled: bool,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub supported: Option<bool>,
+    #[serde(default)]
+    pub vetoed: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeatureResponse {
+    /// Keyed by amendment ID; when asking for one feature rippled returns it
+    /// at the top level instead, which ends up in `other`
+    #[serde(default)]
+    pub features: HashMap<String, FeatureStatus>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Request for FeatureRequest {
+    type Response = FeatureResponse;
+    const METHOD: &'static str = "feature";
+}
+
+/// rippled's build version and the API versions it supports
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VersionRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VersionResponse {
+    pub version: ApiVersions,
+}
+
+/// Supported API versions; numbers or version strings depending on the server
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiVersions {
+    pub first: Value,
+    pub good: Value,
+    pub last: Value,
+}
+
+impl Request for VersionRequest {
+    type Response = VersionResponse;
+    const METHOD: &'static str = "version";
+}
+
+/// The definitions needed to serialize transactions for this server
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ServerDefinitionsRequest {
+    /// Hash of definitions already held; if it matches, only `hash` is returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerDefinitionsResponse {
+    pub hash: String,
+    #[serde(rename = "FIELDS", default)]
+    pub fields: Option<Vec<Value>>,
+    #[serde(rename = "LEDGER_ENTRY_TYPES", default)]
+    pub ledger_entry_types: Option<HashMap<String, i32>>,
+    #[serde(rename = "TRANSACTION_RESULTS", default)]
+    pub transaction_results: Option<HashMap<String, i32>>,
+    #[serde(rename = "TRANSACTION_TYPES", default)]
+    pub transaction_types: Option<HashMap<String, i32>>,
+    #[serde(rename = "TYPES", default)]
+    pub types: Option<HashMap<String, i32>>,
+}
+
+impl Request for ServerDefinitionsRequest {
+    type Response = ServerDefinitionsResponse;
+    const METHOD: &'static str = "server_definitions";
+}
@@ -0,0 +1,205 @@
+use super::{lenient_u32, LedgerIndex, Request};
+use crate::meta::TransactionMeta;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Submit a signed transaction blob
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SubmitRequest {
+    pub tx_blob: String,
+    /// Do not retry or relay the transaction if it fails locally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_hard: Option<bool>,
+}
+
+impl SubmitRequest {
+    pub fn new(tx_blob: &str) -> Self {
+        Self {
+            tx_blob: tx_blob.to_string(),
+            fail_hard: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SubmitResponse {
+    /// Preliminary result; final only once validated
+    pub engine_result: String,
+    pub engine_result_code: i32,
+    pub engine_result_message: String,
+    pub tx_blob: String,
+    pub tx_json: Value,
+    #[serde(default)]
+    pub accepted: Option<bool>,
+    #[serde(default)]
+    pub applied: Option<bool>,
+    #[serde(default)]
+    pub broadcast: Option<bool>,
+    #[serde(default)]
+    pub kept: Option<bool>,
+    #[serde(default)]
+    pub queued: Option<bool>,
+    #[serde(default)]
+    pub account_sequence_available: Option<u32>,
+    #[serde(default)]
+    pub account_sequence_next: Option<u32>,
+    /// Drops needed to get into the open ledger right now
+    #[serde(default)]
+    pub open_ledger_cost: Option<String>,
+    #[serde(default)]
+    pub validated_ledger_index: Option<u32>,
+}
+
+impl Request for SubmitRequest {
+    type Response = SubmitResponse;
+    const METHOD: &'static str = "submit";
+    const IDEMPOTENT: bool = false;
+}
+
+/// Submit a transaction carrying its `Signers`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SubmitMultisignedRequest {
+    pub tx_json: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_hard: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SubmitMultisignedResponse {
+    pub engine_result: String,
+    pub engine_result_code: i32,
+    pub engine_result_message: String,
+    pub tx_blob: String,
+    pub tx_json: Value,
+}
+
+impl Request for SubmitMultisignedRequest {
+    type Response = SubmitMultisignedResponse;
+    const METHOD: &'static str = "submit_multisigned";
+    const IDEMPOTENT: bool = false;
+}
+
+/// Have the server sign a transaction. The secret is sent to the server,
+/// so only use this with one you run; `TransactionSigner` signs locally.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SignRequest {
+    pub tx_json: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    /// Do not autofill fields that need the ledger, like Sequence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<bool>,
+    /// Limit on the autofilled Fee, as a multiple of the reference fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_mult_max: Option<u32>,
+}
+
+impl SignRequest {
+    pub fn new(tx_json: Value, secret: &str) -> Self {
+        Self {
+            tx_json,
+            secret: Some(secret.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SignResponse {
+    pub tx_blob: String,
+    pub tx_json: Value,
+}
+
+impl Request for SignRequest {
+    type Response = SignResponse;
+    const METHOD: &'static str = "sign";
+}
+
+/// Have the server add one signature to a multi-signed transaction; the
+/// secret is sent to the server like with `SignRequest`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SignForRequest {
+    /// The signer's address
+    pub account: Address,
+    pub tx_json: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+}
+
+impl SignForRequest {
+    pub fn new(account: &str, tx_json: Value, secret: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            tx_json,
+            secret: Some(secret.to_string()),
+            key_type: None,
+        }
+    }
+}
+
+impl Request for SignForRequest {
+    type Response = SignResponse;
+    const METHOD: &'static str = "sign_for";
+}
+
+/// Look up a transaction by hash
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TxRequest {
+    pub transaction: TransactionHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+    /// With `max_ledger`, lets the server say whether its history covering
+    /// the range is complete (`searched_all`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_ledger: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ledger: Option<u32>,
+}
+
+impl TxRequest {
+    pub fn new(hash: &str) -> Self {
+        Self {
+            transaction: hash.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Request for TxRequest {
+    type Response = TransactionMetadata;
+    const METHOD: &'static str = "tx";
+}
+
+/// Look up a transaction in a specific ledger
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TransactionEntryRequest {
+    pub tx_hash: TransactionHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<LedgerIndex>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TransactionEntryResponse {
+    #[serde(default, deserialize_with = "lenient_u32")]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub ledger_hash: Option<String>,
+    #[serde(default)]
+    pub hash: Option<TransactionHash>,
+    /// `metadata` in API v1
+    #[serde(alias = "metadata")]
+    pub meta: TransactionMeta,
+    pub tx_json: Value,
+}
+
+impl Request for TransactionEntryRequest {
+    type Response = TransactionEntryResponse;
+    const METHOD: &'static str = "transaction_entry";
+}
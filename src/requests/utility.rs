@@ -0,0 +1,27 @@
+use super::Request;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PingRequest {}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PingResponse {}
+
+impl Request for PingRequest {
+    type Response = PingResponse;
+    const METHOD: &'static str = "ping";
+}
+
+/// 256 bits of randomness from the server
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RandomRequest {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RandomResponse {
+    pub random: String,
+}
+
+impl Request for RandomRequest {
+    type Response = RandomResponse;
+    const METHOD: &'static str = "random";
+}
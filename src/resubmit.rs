@@ -0,0 +1,325 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::signing::TransactionSigner;
+use crate::types::*;
+use anyhow::Result;
+use std::time::Duration;
+
+/// Preliminary results meaning the fee was too low to get in right away
+pub const FEE_BUMP_RESULTS: &[&str] = &["terQUEUED", "telINSUF_FEE_P", "telCAN_NOT_QUEUE_FEE"];
+
+/// Minimum increase, in percent, for rippled to replace a queued transaction
+pub const QUEUE_REPLACE_PERCENT: u64 = 25;
+
+/// How far `submit_with_fee_bump` may raise the fee
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeBumpPolicy {
+    /// Highest fee in drops any version may pay
+    pub max_fee: u64,
+    /// Raise the fee by this percentage on every bump, at least
+    /// `QUEUE_REPLACE_PERCENT`
+    pub bump_percent: u64,
+    /// Time between checks for a validated version
+    pub poll_interval: Duration,
+}
+
+impl FeeBumpPolicy {
+    pub fn new(max_fee: u64) -> Self {
+        Self {
+            max_fee,
+            bump_percent: 50,
+            poll_interval: Duration::from_secs(4),
+        }
+    }
+
+    /// Smaller bumps than `QUEUE_REPLACE_PERCENT` could not replace the
+    /// queued version, so they are raised to it
+    pub fn with_bump_percent(mut self, percent: u64) -> Self {
+        self.bump_percent = percent.max(QUEUE_REPLACE_PERCENT);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// The next fee after `fee`, or `None` if the maximum is reached
+    pub fn next_fee(&self, fee: u64) -> Option<u64> {
+        let percent = self.bump_percent.max(QUEUE_REPLACE_PERCENT);
+        let bumped = (fee + (fee * percent).div_ceil(100)).max(fee + 1).min(self.max_fee);
+        (bumped > fee).then_some(bumped)
+    }
+}
+
+/// One signed version of a transaction that was submitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionAttempt {
+    pub hash: TransactionHash,
+    pub fee: u64,
+    /// Preliminary result of the submission, empty if it failed
+    pub engine_result: String,
+    /// Why the submission failed. The version may still have reached the
+    /// network, so it is tracked like the others.
+    pub error: Option<String>,
+}
+
+/// The version of a transaction that made it into a validated ledger
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBumpOutcome {
+    /// The validated version; its result may still be a `tec` failure
+    pub transaction: TransactionMetadata,
+    /// Every version submitted, oldest first
+    pub attempts: Vec<SubmissionAttempt>,
+}
+
+impl XrplClient {
+    /// Submit a transaction and re-sign it with a higher fee while it is
+    /// stuck in the queue or rejected for its fee.
+    ///
+    /// All versions share the same Sequence, so at most one of them can be
+    /// validated; each of them is tracked until one is or LastLedgerSequence
+    /// passes, which makes the call fail.
+    pub async fn submit_with_fee_bump(
+        &self,
+        secret: &str,
+        transaction: &Transaction,
+        policy: &FeeBumpPolicy,
+    ) -> Result<FeeBumpOutcome> {
+        let last_ledger_sequence = transaction.last_ledger_sequence.ok_or_else(|| {
+            XrplError::InvalidTransaction("LastLedgerSequence is required to resubmit safely".to_string())
+        })?;
+        let mut fee: u64 = transaction
+            .fee
+            .parse()
+            .map_err(|_| XrplError::InvalidTransaction("Invalid fee format".to_string()))?;
+        if fee > policy.max_fee {
+            return Err(XrplError::InvalidTransaction(format!(
+                "Fee {} exceeds the maximum of {}",
+                fee, policy.max_fee
+            ))
+            .into());
+        }
+
+        let signer = TransactionSigner::with_network(self.network().clone());
+        let mut attempts = Vec::new();
+        let mut transaction = transaction.clone();
+
+        let (first, error) = self.submit_version(&signer, secret, &transaction).await?;
+        if let Some(error) = error {
+            return Err(error);
+        }
+        if is_hard_failure(&first.engine_result) {
+            return Err(XrplError::TransactionFailed(first.engine_result).into());
+        }
+        attempts.push(first);
+
+        loop {
+            tokio::time::sleep(policy.poll_interval).await;
+
+            // Read before checking the attempts, so that a version validated
+            // in between is not reported as expired
+            let validated_ledger = self.get_ledger_index().await?;
+
+            for attempt in &attempts {
+                if let Some(validated) = self.validated_transaction(&attempt.hash).await? {
+                    return Ok(FeeBumpOutcome {
+                        transaction: validated,
+                        attempts,
+                    });
+                }
+            }
+
+            if validated_ledger > last_ledger_sequence {
+                return Err(XrplError::TransactionFailed(format!(
+                    "No version validated before LastLedgerSequence {}",
+                    last_ledger_sequence
+                ))
+                .into());
+            }
+
+            let stuck = attempts
+                .last()
+                .is_some_and(|attempt| FEE_BUMP_RESULTS.contains(&attempt.engine_result.as_str()));
+            if let Some(next_fee) = policy.next_fee(fee).filter(|_| stuck) {
+                fee = next_fee;
+                transaction.fee = fee.to_string();
+                // Earlier versions may still validate, so a failed submit
+                // does not end the tracking
+                let (attempt, _) = self.submit_version(&signer, secret, &transaction).await?;
+                attempts.push(attempt);
+            }
+        }
+    }
+
+    /// Sign and submit one version. Signing errors fail the call; a failed
+    /// submit is recorded in the attempt and returned next to it.
+    async fn submit_version(
+        &self,
+        signer: &TransactionSigner,
+        secret: &str,
+        transaction: &Transaction,
+    ) -> Result<(SubmissionAttempt, Option<anyhow::Error>)> {
+        let signed = signer.sign_transaction(secret, transaction)?;
+        let hash = TransactionSigner::transaction_hash(&signed.tx_blob)?;
+        let (engine_result, error) = match self.submit_transaction(&signed).await {
+            Ok(result) => (result.engine_result, None),
+            Err(error) => (String::new(), Some(error)),
+        };
+
+        let attempt = SubmissionAttempt {
+            hash,
+            fee: transaction.fee.parse().unwrap_or_default(),
+            engine_result,
+            error: error.as_ref().map(ToString::to_string),
+        };
+        Ok((attempt, error))
+    }
+
+    /// The transaction with this hash if it is in a validated ledger
+    pub(crate) async fn validated_transaction(&self, tx_hash: &str) -> Result<Option<TransactionMetadata>> {
+        match self.get_transaction(tx_hash).await {
+            Ok(transaction) if transaction.validated => Ok(Some(transaction)),
+            Ok(_) => Ok(None),
+            Err(error) => match error.downcast_ref::<XrplError>() {
+                Some(XrplError::ApiError(code)) if code == "txnNotFound" => Ok(None),
+                _ => Err(error),
+            },
+        }
+    }
+}
+
+/// Results after which the transaction can never be applied
+fn is_hard_failure(engine_result: &str) -> bool {
+    engine_result.starts_with("tem")
+        || engine_result.starts_with("tef")
+        || (engine_result.starts_with("tel") && !FEE_BUMP_RESULTS.contains(&engine_result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::test_support::{client, submit_result, validated};
+    use crate::transport::MockTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const SECRET: &str = "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2";
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    fn payment(fee: &str) -> Transaction {
+        Transaction {
+            account: ACCOUNT.to_string(),
+            destination: "rReceiver1234567890123456789012".to_string(),
+            amount: "1000".to_string(),
+            currency: "XRP".to_string(),
+            fee: fee.to_string(),
+            sequence: 5,
+            last_ledger_sequence: Some(110),
+            ..Transaction::default()
+        }
+    }
+
+    fn hash_of(transaction: &Transaction) -> String {
+        let signed = TransactionSigner::with_network(Network::testnet())
+            .sign_transaction(SECRET, transaction)
+            .unwrap();
+        TransactionSigner::transaction_hash(&signed.tx_blob).unwrap()
+    }
+
+    #[test]
+    fn test_next_fee() {
+        let policy = FeeBumpPolicy::new(30);
+        assert_eq!(policy.next_fee(12), Some(18));
+        assert_eq!(policy.next_fee(1), Some(2));
+        assert_eq!(policy.next_fee(25), Some(30));
+        assert_eq!(policy.next_fee(30), None);
+
+        // Below 25% a bump could not replace the queued version
+        let small = FeeBumpPolicy::new(1000).with_bump_percent(10);
+        assert_eq!(small.bump_percent, QUEUE_REPLACE_PERCENT);
+        assert_eq!(small.next_fee(100), Some(125));
+        assert_eq!(FeeBumpPolicy { bump_percent: 5, ..small }.next_fee(10), Some(13));
+    }
+
+    #[tokio::test]
+    async fn test_bumps_until_validated() {
+        let bumped = hash_of(&payment("18"));
+
+        let mock = Arc::new(MockTransport::new());
+        mock.on("submit", submit_result("terQUEUED"))
+            .on("submit", submit_result("tesSUCCESS"));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on_params("tx", json!({ "transaction": bumped }), validated(&bumped, ACCOUNT, 5, 101));
+        mock.on_error("tx", "txnNotFound");
+
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(1));
+        let outcome = client(&mock)
+            .submit_with_fee_bump(SECRET, &payment("12"), &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.transaction.hash, bumped);
+        assert_eq!(outcome.attempts.len(), 2);
+        assert_eq!(outcome.attempts[0].engine_result, "terQUEUED");
+        assert_eq!(outcome.attempts[1].fee, 18);
+        assert_eq!(mock.request_count("submit"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_last_ledger_sequence() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("submit", submit_result("telINSUF_FEE_P"));
+        mock.on("ledger", json!({ "ledger_index": 111 }));
+        mock.on_error("tx", "txnNotFound");
+
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(1));
+        let error = client(&mock)
+            .submit_with_fee_bump(SECRET, &payment("12"), &policy)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("LastLedgerSequence 110"));
+        assert_eq!(mock.request_count("submit"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_hard_failures_and_short_input() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("submit", submit_result("temBAD_AMOUNT"));
+        let client = client(&mock);
+        let policy = FeeBumpPolicy::new(100);
+
+        let error = client.submit_with_fee_bump(SECRET, &payment("12"), &policy).await.unwrap_err();
+        assert_eq!(error.to_string(), "Transaction failed: temBAD_AMOUNT");
+
+        let mut no_expiry = payment("12");
+        no_expiry.last_ledger_sequence = None;
+        assert!(client.submit_with_fee_bump(SECRET, &no_expiry, &policy).await.is_err());
+        assert!(client.submit_with_fee_bump(SECRET, &payment("500"), &policy).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_bump_keeps_tracking_earlier_versions() {
+        let original = hash_of(&payment("12"));
+
+        let mock = Arc::new(MockTransport::new());
+        mock.on("submit", submit_result("terQUEUED"))
+            .on_error("submit", "internal");
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on_params("tx", json!({ "transaction": original }), json!({ "error": "txnNotFound" }))
+            .on_params("tx", json!({ "transaction": original }), validated(&original, ACCOUNT, 5, 101));
+        mock.on_error("tx", "txnNotFound");
+
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(1));
+        let outcome = client(&mock)
+            .submit_with_fee_bump(SECRET, &payment("12"), &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.transaction.hash, original);
+        assert_eq!(outcome.attempts.len(), 2);
+        assert_eq!(outcome.attempts[1].error.as_deref(), Some("XRPL API error: internal"));
+    }
+}
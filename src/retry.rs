@@ -0,0 +1,135 @@
+use crate::error::XrplError;
+use rand::Rng;
+use std::time::Duration;
+
+/// rippled errors that indicate a transient condition worth retrying
+pub const RETRYABLE_API_ERRORS: [&str; 3] = ["slowDown", "tooBusy", "noCurrent"];
+
+/// Methods that may change the ledger if repeated, so they are never sent
+/// again without first checking whether the earlier attempt landed
+pub const NON_IDEMPOTENT_METHODS: [&str; 2] = ["submit", "submit_multisigned"];
+
+/// HTTP status codes that indicate rate limiting or a temporarily unavailable server
+pub const RETRYABLE_HTTP_STATUSES: [u16; 2] = [429, 503];
+
+/// Retry policy with exponential backoff and jitter
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for a single backoff
+    pub max_backoff: Duration,
+    /// Factor applied to the backoff after each retry
+    pub multiplier: f64,
+    /// Randomize each backoff between half and the full computed value
+    pub jitter: bool,
+    /// Allow retrying `submit` after checking that the transaction hash is unknown
+    pub retry_submits: bool,
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_retry_submits(mut self, retry_submits: bool) -> Self {
+        self.retry_submits = retry_submits;
+        self
+    }
+
+    /// Backoff to wait before retry number `attempt` (starting at 0)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powi(attempt.min(32) as i32);
+        let backoff = self.initial_backoff.mul_f64(factor).min(self.max_backoff);
+
+        if self.jitter {
+            let ratio = rand::thread_rng().gen_range(0.5..=1.0);
+            backoff.mul_f64(ratio)
+        } else {
+            backoff
+        }
+    }
+
+    /// Whether an error returned by a request is transient
+    pub fn is_retryable(error: &anyhow::Error) -> bool {
+        match error.downcast_ref::<XrplError>() {
+            Some(XrplError::Network(_)) => true,
+            Some(XrplError::HttpStatus(status)) => RETRYABLE_HTTP_STATUSES.contains(status),
+            Some(XrplError::ApiError(code)) => RETRYABLE_API_ERRORS.contains(&code.as_str()),
+            _ => false,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            retry_submits: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(false);
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy::default().with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(RetryPolicy::is_retryable(&XrplError::HttpStatus(429).into()));
+        assert!(RetryPolicy::is_retryable(&XrplError::HttpStatus(503).into()));
+        assert!(RetryPolicy::is_retryable(&XrplError::ApiError("slowDown".to_string()).into()));
+        assert!(RetryPolicy::is_retryable(&XrplError::ApiError("tooBusy".to_string()).into()));
+        assert!(!RetryPolicy::is_retryable(&XrplError::HttpStatus(400).into()));
+        assert!(!RetryPolicy::is_retryable(&XrplError::ApiError("actNotFound".to_string()).into()));
+        assert!(!RetryPolicy::is_retryable(&XrplError::InvalidTransaction("bad".to_string()).into()));
+    }
+}
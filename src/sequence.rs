@@ -0,0 +1,237 @@
+use crate::client::XrplClient;
+use crate::requests::{AccountInfoRequest, LedgerIndex};
+use crate::types::*;
+use anyhow::Result;
+use std::collections::BTreeSet;
+use tokio::sync::Mutex;
+
+/// Hands out the sequence numbers of one account locally so that many
+/// transactions can be signed and submitted concurrently.
+///
+/// The first sequence comes from `account_info` on the open ledger, counting
+/// queued transactions. After that, sequences are allocated without network
+/// calls. Rejected transactions give their sequence back to be reused by the
+/// next one, and `tefPAST_SEQ` moves the local state forward to the ledger's.
+/// `terPRE_SEQ` transactions are held by rippled until the gap fills, so they
+/// keep their sequence.
+pub struct SequenceManager {
+    account: Address,
+    state: Mutex<SequenceState>,
+}
+
+#[derive(Default)]
+struct SequenceState {
+    /// Next never-used sequence, `None` until synced
+    next: Option<u32>,
+    /// Sequences given back by rejected transactions, reused lowest first
+    released: BTreeSet<u32>,
+}
+
+impl SequenceManager {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            state: Mutex::new(SequenceState::default()),
+        }
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// The sequence for the next transaction
+    pub async fn next(&self, client: &XrplClient) -> Result<u32> {
+        let mut state = self.state.lock().await;
+
+        if let Some(sequence) = state.released.pop_first() {
+            return Ok(sequence);
+        }
+
+        let sequence = match state.next {
+            Some(next) => next,
+            None => self.fetch_next(client).await?,
+        };
+        state.next = Some(sequence + 1);
+
+        Ok(sequence)
+    }
+
+    /// Give back a sequence whose transaction was rejected without using it,
+    /// so that a later transaction fills the gap
+    pub async fn release(&self, sequence: u32) {
+        let mut state = self.state.lock().await;
+
+        match state.next {
+            Some(next) if sequence + 1 == next => {
+                let mut next = sequence;
+                while next > 0 && state.released.remove(&(next - 1)) {
+                    next -= 1;
+                }
+                state.next = Some(next);
+            }
+            Some(next) if sequence < next => {
+                state.released.insert(sequence);
+            }
+            _ => {}
+        }
+    }
+
+    /// Catch up with the account's sequence on the open ledger if it is
+    /// ahead of the local state. Never rewinds, since sequences already handed
+    /// out may still be in flight. Returns the next sequence.
+    pub async fn resync(&self, client: &XrplClient) -> Result<u32> {
+        let mut state = self.state.lock().await;
+        let fetched = self.fetch_next(client).await?;
+        let next = state.next.map_or(fetched, |next| next.max(fetched));
+
+        state.released.retain(|sequence| *sequence >= fetched);
+        state.next = Some(next);
+
+        Ok(next)
+    }
+
+    /// Settle a sequence whose submit failed without a result: it stays used
+    /// if the network knows the transaction, and is given back otherwise so
+    /// that later transactions do not stall behind the gap
+    pub async fn reconcile(&self, client: &XrplClient, sequence: u32, tx_hash: &str) {
+        if !matches!(client.find_submitted_transaction(tx_hash).await, Ok(Some(_))) {
+            self.release(sequence).await;
+        }
+    }
+
+    /// Update the local state from the preliminary result of submitting the
+    /// transaction that used `sequence`
+    pub async fn handle_result(&self, client: &XrplClient, sequence: u32, engine_result: &str) -> Result<()> {
+        match engine_result {
+            // Used by a transaction this manager did not hand out
+            "tefPAST_SEQ" => {
+                self.resync(client).await?;
+            }
+            // Held until earlier sequences are applied, or already applied
+            "terPRE_SEQ" | "tefALREADY" => {}
+            result if result.starts_with("tem") || result.starts_with("tef") || result.starts_with("tel") => {
+                self.release(sequence).await;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// The account's next sequence on the open ledger, after any queued transactions
+    async fn fetch_next(&self, client: &XrplClient) -> Result<u32> {
+        let info = client
+            .request(&AccountInfoRequest {
+                queue: Some(true),
+                ledger_index: Some(LedgerIndex::Current),
+                ..AccountInfoRequest::new(&self.account)
+            })
+            .await?;
+
+        let queued = info
+            .queue_data
+            .as_ref()
+            .and_then(|queue_data| queue_data["highest_sequence"].as_u64())
+            .map(|highest| highest as u32 + 1);
+
+        Ok(queued.unwrap_or(0).max(info.account_data.sequence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, queued_account_info, validated};
+    use crate::transport::MockTransport;
+    use futures::future::join_all;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    #[tokio::test]
+    async fn test_concurrent_allocation() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", queued_account_info(ACCOUNT, 10, json!({ "txn_count": 2, "highest_sequence": 11 })));
+        let client = client(&mock);
+        let manager = SequenceManager::new(ACCOUNT);
+
+        let mut sequences: Vec<u32> = join_all((0..100).map(|_| manager.next(&client)))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        sequences.sort();
+
+        assert_eq!(sequences, (12..112).collect::<Vec<_>>());
+        assert_eq!(mock.request_count("account_info"), 1);
+        assert_eq!(mock.requests_for("account_info")[0]["queue"], true);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_sequences_are_reused() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", queued_account_info(ACCOUNT, 5, json!(null)));
+        let client = client(&mock);
+        let manager = SequenceManager::new(ACCOUNT);
+
+        for _ in 0..4 {
+            manager.next(&client).await.unwrap();
+        }
+        manager.handle_result(&client, 6, "temBAD_AMOUNT").await.unwrap();
+        manager.handle_result(&client, 7, "tesSUCCESS").await.unwrap();
+        assert_eq!(manager.next(&client).await.unwrap(), 6);
+        assert_eq!(manager.next(&client).await.unwrap(), 9);
+
+        manager.release(9).await;
+        manager.release(8).await;
+        assert_eq!(manager.next(&client).await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_sequence_errors() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", queued_account_info(ACCOUNT, 5, json!(null)))
+            .on("account_info", queued_account_info(ACCOUNT, 20, json!(null)));
+        let client = client(&mock);
+        let manager = SequenceManager::new(ACCOUNT);
+
+        assert_eq!(manager.next(&client).await.unwrap(), 5);
+        manager.handle_result(&client, 5, "tefPAST_SEQ").await.unwrap();
+        assert_eq!(manager.next(&client).await.unwrap(), 20);
+        assert_eq!(mock.request_count("account_info"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_failed_submit() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", queued_account_info(ACCOUNT, 5, json!(null)))
+            .on_params("tx", json!({ "transaction": "KNOWN" }), validated("KNOWN", ACCOUNT, 5, 101))
+            .on("tx", json!({ "error": "txnNotFound" }));
+        let client = client(&mock);
+        let manager = SequenceManager::new(ACCOUNT);
+
+        assert_eq!(manager.next(&client).await.unwrap(), 5);
+        assert_eq!(manager.next(&client).await.unwrap(), 6);
+        manager.reconcile(&client, 6, "UNKNOWN").await;
+        manager.reconcile(&client, 5, "KNOWN").await;
+        assert_eq!(manager.next(&client).await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_no_rewind_while_in_flight() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_info", queued_account_info(ACCOUNT, 5, json!(null)));
+        let client = client(&mock);
+        let manager = SequenceManager::new(ACCOUNT);
+
+        for _ in 0..3 {
+            manager.next(&client).await.unwrap();
+        }
+        manager.handle_result(&client, 7, "terPRE_SEQ").await.unwrap();
+        assert_eq!(mock.request_count("account_info"), 1);
+
+        manager.handle_result(&client, 6, "tefPAST_SEQ").await.unwrap();
+        assert_eq!(manager.next(&client).await.unwrap(), 8);
+    }
+}
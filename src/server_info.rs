@@ -0,0 +1,151 @@
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::requests::{FeeRequest, FeeResponse, ServerInfo, ServerInfoRequest, ServerState, ServerStateRequest};
+use anyhow::Result;
+
+/// Account reserves of the last validated ledger, in drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reserves {
+    /// Held by every account
+    pub base: u64,
+    /// Held per owned ledger object: trust lines, offers, tickets, ...
+    pub owner: u64,
+}
+
+impl Reserves {
+    /// Total reserve of an account owning `owner_count` objects
+    pub fn total(&self, owner_count: u32) -> u64 {
+        self.base + self.owner * owner_count as u64
+    }
+}
+
+impl XrplClient {
+    /// Status of the server, with fees and reserves in XRP
+    pub async fn server_info(&self) -> Result<ServerInfo> {
+        Ok(self.request(&ServerInfoRequest::default()).await?.info)
+    }
+
+    /// Status of the server, with fees and reserves in drops
+    pub async fn server_state(&self) -> Result<ServerState> {
+        Ok(self.request(&ServerStateRequest::default()).await?.state)
+    }
+
+    /// Open ledger cost, queue state and fee levels from the `fee` method
+    pub async fn fee(&self) -> Result<FeeResponse> {
+        self.request(&FeeRequest::default()).await
+    }
+
+    /// Current base and owner reserves
+    pub async fn reserves(&self) -> Result<Reserves> {
+        let state = self.server_state().await?;
+        let ledger = state
+            .validated_ledger
+            .ok_or_else(|| XrplError::ApiError("Server has no validated ledger".to_string()))?;
+
+        Ok(Reserves {
+            base: ledger.reserve_base,
+            owner: ledger.reserve_inc,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_server_info() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("server_info", json!({
+            "info": {
+                "build_version": "2.2.3",
+                "complete_ledgers": "32570-90000",
+                "load_factor": 1.5,
+                "network_id": 1,
+                "server_state": "full",
+                "validated_ledger": {
+                    "age": 2,
+                    "base_fee_xrp": 0.00001,
+                    "hash": "ABC",
+                    "reserve_base_xrp": 10,
+                    "reserve_inc_xrp": 2,
+                    "seq": 90000
+                }
+            }
+        }));
+
+        let info = client(&mock).server_info().await.unwrap();
+        let ledger = info.validated_ledger.as_ref().unwrap();
+        assert_eq!(info.server_state, "full");
+        assert_eq!(info.network_id, Some(1));
+        assert_eq!(info.validated_ledger_age(), Some(2));
+        assert_eq!(info.load_factor(), 1.5);
+        assert_eq!(ledger.base_fee_drops(), 10);
+        assert_eq!(ledger.reserve_base_drops(), 10_000_000);
+        assert_eq!(ledger.reserve_inc_drops(), 2_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_server_state_and_reserves() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("server_state", json!({
+            "state": {
+                "build_version": "2.2.3",
+                "load_base": 256,
+                "load_factor": 512,
+                "server_state": "full",
+                "validated_ledger": {
+                    "base_fee": 10,
+                    "close_time": 750000000,
+                    "hash": "ABC",
+                    "reserve_base": 1000000,
+                    "reserve_inc": 200000,
+                    "seq": 90000
+                }
+            }
+        }));
+        let client = client(&mock);
+
+        assert_eq!(client.server_state().await.unwrap().load_multiplier(), 2.0);
+
+        let reserves = client.reserves().await.unwrap();
+        assert_eq!(reserves, Reserves { base: 1_000_000, owner: 200_000 });
+        assert_eq!(reserves.total(3), 1_600_000);
+    }
+
+    #[tokio::test]
+    async fn test_fee() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("fee", json!({
+            "current_ledger_size": "14",
+            "current_queue_size": "0",
+            "drops": {
+                "base_fee": "10",
+                "median_fee": "11000",
+                "minimum_fee": "10",
+                "open_ledger_fee": "10"
+            },
+            "expected_ledger_size": "24",
+            "ledger_current_index": 26575101,
+            "levels": {
+                "median_level": "281600",
+                "minimum_level": "256",
+                "open_ledger_level": "256",
+                "reference_level": "256"
+            },
+            "max_queue_size": "480"
+        }));
+
+        let fee = client(&mock).fee().await.unwrap();
+        assert_eq!(fee.drops.open_ledger_fee, 10);
+        assert_eq!(fee.drops.median_fee, 11000);
+        assert_eq!(fee.levels.median_level, 281600);
+        assert_eq!(fee.expected_ledger_size, 24);
+        assert_eq!(fee.max_queue_size, Some(480));
+        assert!(!fee.queue_is_full());
+    }
+}
@@ -1,22 +1,32 @@
 use crate::error::XrplError;
+use crate::network::Network;
 use crate::types::*;
 use anyhow::Result;
 use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer, Verifier};
 use sha2::{Sha512, Digest};
-use serde_json::json;
+use serde_json::{json, Value};
 
 /// Transaction signer for offline signing
-pub struct TransactionSigner;
+pub struct TransactionSigner {
+    network: Option<Network>,
+}
 
 impl TransactionSigner {
     /// Create a new transaction signer
     pub fn new() -> Self {
-        Self
+        Self { network: None }
+    }
+
+    /// Create a new transaction signer that only signs transactions valid on `network`
+    pub fn with_network(network: Network) -> Self {
+        Self {
+            network: Some(network),
+        }
     }
 
-    /// Create a new transaction signer with network specification
-    pub fn with_network(_testnet: bool) -> Self {
-        Self
+    /// The network this signer is bound to, if any
+    pub fn network(&self) -> Option<&Network> {
+        self.network.as_ref()
     }
 
     /// Sign a transaction offline (produce a signed blob, but don't submit)
@@ -43,6 +53,20 @@ impl TransactionSigner {
         Ok(signed_tx)
     }
 
+    /// Compute the hash identifying a signed transaction blob on the ledger
+    /// (SHA-512Half of the `TXN\0` prefix followed by the blob)
+    pub fn transaction_hash(tx_blob: &str) -> Result<TransactionHash> {
+        let blob_bytes = hex::decode(tx_blob)
+            .map_err(|e| XrplError::Serialization(e.to_string()))?;
+
+        let mut hasher = Sha512::new();
+        hasher.update(b"TXN\0");
+        hasher.update(&blob_bytes);
+        let digest = hasher.finalize();
+
+        Ok(hex::encode_upper(&digest[..32]))
+    }
+
     /// Verify a signed transaction
     /// 
     /// # Arguments
@@ -88,14 +112,44 @@ impl TransactionSigner {
             return Err(XrplError::InvalidTransaction("Account is required".to_string()).into());
         }
 
-        if transaction.sequence == 0 {
-            return Err(XrplError::InvalidTransaction("Sequence number is required".to_string()).into());
+        match (transaction.sequence, transaction.ticket_sequence) {
+            (0, None) => {
+                return Err(XrplError::InvalidTransaction("Sequence number is required".to_string()).into());
+            }
+            (sequence, Some(_)) if sequence != 0 => {
+                return Err(XrplError::InvalidTransaction("Sequence must be 0 when using a ticket".to_string()).into());
+            }
+            _ => {}
         }
 
         if transaction.fee.is_empty() {
             return Err(XrplError::InvalidTransaction("Fee is required".to_string()).into());
         }
 
+        if let Some(network) = &self.network {
+            let expected = if network.requires_network_id() {
+                Some(network.network_id)
+            } else {
+                None
+            };
+
+            if transaction.network_id.is_some() && transaction.network_id != Some(network.network_id) {
+                return Err(XrplError::InvalidTransaction(format!(
+                    "NetworkID does not match network {}",
+                    network.name
+                ))
+                .into());
+            }
+
+            if expected.is_some() && transaction.network_id.is_none() {
+                return Err(XrplError::InvalidTransaction(format!(
+                    "NetworkID is required on network {}",
+                    network.name
+                ))
+                .into());
+            }
+        }
+
         Ok(())
     }
 
@@ -163,6 +217,27 @@ impl TransactionSigner {
             tx_json["InvoiceID"] = json!(invoice_id);
         }
 
+        if let Some(network_id) = transaction.network_id {
+            tx_json["NetworkID"] = json!(network_id);
+        }
+
+        if let Some(ticket_sequence) = transaction.ticket_sequence {
+            tx_json["TicketSequence"] = json!(ticket_sequence);
+        }
+
+        if let Some(ticket_count) = transaction.ticket_count {
+            tx_json["TicketCount"] = json!(ticket_count);
+        }
+
+        if let Some(expiration) = transaction.expiration {
+            tx_json["Expiration"] = json!(expiration);
+        }
+
+        if let Some(memos) = &transaction.memos {
+            let memos: Vec<Value> = memos.iter().map(|memo| json!({ "Memo": memo })).collect();
+            tx_json["Memos"] = json!(memos);
+        }
+
         let canonical_json = serde_json::to_string(&tx_json)
             .map_err(|e| XrplError::Serialization(e.to_string()))?;
 
@@ -187,6 +262,7 @@ impl TransactionSigner {
     ) -> Result<bool> {
         let sig_array: [u8; 64] = signature.try_into()
             .map_err(|_| XrplError::SigningFailed("Invalid signature length".to_string()))?;
+        #[allow(clippy::unnecessary_fallible_conversions)]
         let signature = Signature::try_from(&sig_array)
             .map_err(|e| XrplError::SigningFailed(e.to_string()))?;
 
@@ -281,18 +357,44 @@ mod tests {
     #[test]
     fn test_signer_creation() {
         let signer = TransactionSigner::new();
-        // Verify the signer was created successfully
-        assert!(std::mem::size_of_val(&signer) == 0); // Unit struct has size 0
+        assert!(signer.network().is_none());
     }
 
     #[test]
     fn test_signer_with_network() {
-        let signer = TransactionSigner::with_network(true);
-        // Verify the signer was created successfully
-        assert!(std::mem::size_of_val(&signer) == 0); // Unit struct has size 0
+        let signer = TransactionSigner::with_network(Network::testnet());
+        assert_eq!(signer.network().unwrap().network_id, Network::TESTNET_ID);
     }
 
     #[test]
+    fn test_network_id_validation() {
+        let signer = TransactionSigner::with_network(Network::custom("sidechain", 21337, "http://localhost:5005"));
+        let mut transaction = Transaction {
+            account: "rAccount123".to_string(),
+            sequence: 1,
+            ..Transaction::default()
+        };
+
+        assert!(signer.validate_transaction_for_signing(&transaction).is_err());
+
+        transaction.network_id = Some(1);
+        assert!(signer.validate_transaction_for_signing(&transaction).is_err());
+
+        transaction.network_id = Some(21337);
+        assert!(signer.validate_transaction_for_signing(&transaction).is_ok());
+    }
+
+    #[test]
+    fn test_transaction_hash() {
+        let hash = TransactionSigner::transaction_hash("1200002280000000").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, TransactionSigner::transaction_hash("1200002280000000").unwrap());
+        assert_ne!(hash, TransactionSigner::transaction_hash("1200002280000001").unwrap());
+        assert!(TransactionSigner::transaction_hash("not hex").is_err());
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_transaction_validation() {
         let signer = TransactionSigner::new();
         let mut transaction = Transaction::default();
@@ -303,6 +405,21 @@ mod tests {
         assert!(signer.validate_transaction_for_signing(&transaction).is_ok());
     }
 
+    #[test]
+    fn test_ticket_validation() {
+        let signer = TransactionSigner::new();
+        let mut transaction = Transaction {
+            account: "rAccount123".to_string(),
+            ticket_sequence: Some(42),
+            ..Transaction::default()
+        };
+
+        assert!(signer.validate_transaction_for_signing(&transaction).is_ok());
+
+        transaction.sequence = 7;
+        assert!(signer.validate_transaction_for_signing(&transaction).is_err());
+    }
+
     #[test]
     fn test_invalid_transaction_validation() {
         let signer = TransactionSigner::new();
@@ -312,6 +429,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_canonical_format() {
         let signer = TransactionSigner::new();
         let mut transaction = Transaction::default();
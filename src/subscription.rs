@@ -0,0 +1,432 @@
+use crate::error::XrplError;
+use crate::types::*;
+use crate::websocket::WebSocketTransport;
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::broadcast;
+
+/// A `ledgerClosed` message from the `ledger` stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerClosed {
+    pub ledger_index: u32,
+    pub ledger_hash: TransactionHash,
+    pub ledger_time: Timestamp,
+    pub fee_base: Option<u64>,
+    pub reserve_base: Option<u64>,
+    pub reserve_inc: Option<u64>,
+    pub txn_count: Option<u32>,
+    pub validated_ledgers: Option<String>,
+}
+
+/// A `transaction` message from the `transactions`, `accounts` or `books` streams
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionEvent {
+    #[serde(alias = "tx_json")]
+    pub transaction: Value,
+    pub meta: Option<Value>,
+    pub hash: Option<TransactionHash>,
+    pub engine_result: String,
+    pub engine_result_code: i32,
+    pub engine_result_message: Option<String>,
+    pub ledger_index: Option<u32>,
+    pub ledger_hash: Option<TransactionHash>,
+    #[serde(default)]
+    pub validated: bool,
+}
+
+/// A `validationReceived` message from the `validations` stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationEvent {
+    pub ledger_hash: TransactionHash,
+    pub ledger_index: String,
+    pub validation_public_key: String,
+    pub signing_time: Timestamp,
+    #[serde(default)]
+    pub full: bool,
+    pub flags: u32,
+    pub signature: String,
+    pub network_id: Option<u32>,
+}
+
+/// A `serverStatus` message from the `server` stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatusEvent {
+    pub server_status: String,
+    pub load_base: u32,
+    pub load_factor: u32,
+    pub base_fee: Option<u64>,
+    pub hostid: Option<String>,
+}
+
+/// An order book to follow with `subscribe_book`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub taker_gets: Issue,
+    pub taker_pays: Issue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taker: Option<Address>,
+    /// Return the current offers in the subscribe response
+    pub snapshot: bool,
+    /// Follow both sides of the book
+    pub both: bool,
+}
+
+impl OrderBook {
+    pub fn new(taker_gets: Issue, taker_pays: Issue) -> Self {
+        Self {
+            taker_gets,
+            taker_pays,
+            taker: None,
+            snapshot: true,
+            both: false,
+        }
+    }
+}
+
+/// Offers returned by a book subscription with `snapshot` set
+#[derive(Debug, Clone, Default)]
+pub struct BookSnapshot {
+    /// Offers of a single-sided subscription
+    pub offers: Vec<Value>,
+    /// Offers taking `taker_gets` when `both` is set
+    pub asks: Vec<Value>,
+    /// Offers taking `taker_pays` when `both` is set
+    pub bids: Vec<Value>,
+}
+
+type EventFilter = Box<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// A typed stream of subscription messages.
+///
+/// The stream keeps delivering across reconnects because the transport
+/// replays the `subscribe` command on every new connection. Messages a slow
+/// consumer falls too far behind on are skipped.
+pub struct SubscriptionStream<T> {
+    key: u64,
+    inner: Pin<Box<dyn Stream<Item = T> + Send>>,
+}
+
+impl<T> SubscriptionStream<T> {
+    /// Identifier to pass to `WebSocketTransport::unsubscribe`
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+fn typed_stream<T: DeserializeOwned + Send + 'static>(
+    key: u64,
+    events: broadcast::Receiver<Value>,
+    filter: EventFilter,
+) -> SubscriptionStream<T> {
+    let inner = stream::unfold(
+        (events, filter, PhantomData::<T>),
+        |(mut events, filter, marker)| async move {
+            loop {
+                match events.recv().await {
+                    Ok(message) => {
+                        if !filter(&message) {
+                            continue;
+                        }
+                        if let Ok(event) = serde_json::from_value::<T>(message) {
+                            return Some((event, (events, filter, marker)));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    SubscriptionStream {
+        key,
+        inner: Box::pin(inner),
+    }
+}
+
+/// Whether any string in the transaction or its metadata names one of `accounts`
+fn mentions_account(value: &Value, accounts: &HashSet<String>) -> bool {
+    match value {
+        Value::String(s) => accounts.contains(s),
+        Value::Array(items) => items.iter().any(|item| mentions_account(item, accounts)),
+        Value::Object(map) => map.values().any(|item| mentions_account(item, accounts)),
+        _ => false,
+    }
+}
+
+fn issue_matches(amount: &Value, issue: &Issue) -> bool {
+    match amount {
+        Value::String(_) => issue.currency == "XRP",
+        _ => {
+            amount["currency"].as_str() == Some(issue.currency.as_str())
+                && amount["issuer"].as_str() == issue.issuer.as_deref()
+        }
+    }
+}
+
+/// Whether a transaction message created, modified or deleted an offer in `book`
+fn touches_book(message: &Value, book: &OrderBook) -> bool {
+    let nodes = match message["meta"]["AffectedNodes"].as_array() {
+        Some(nodes) => nodes,
+        None => return false,
+    };
+
+    nodes.iter().any(|node| {
+        let inner = node
+            .get("CreatedNode")
+            .or_else(|| node.get("ModifiedNode"))
+            .or_else(|| node.get("DeletedNode"));
+        let inner = match inner {
+            Some(inner) if inner["LedgerEntryType"] == "Offer" => inner,
+            _ => return false,
+        };
+
+        let fields = if inner["FinalFields"].is_object() {
+            &inner["FinalFields"]
+        } else {
+            &inner["NewFields"]
+        };
+
+        let forward = issue_matches(&fields["TakerGets"], &book.taker_gets)
+            && issue_matches(&fields["TakerPays"], &book.taker_pays);
+        let reverse = issue_matches(&fields["TakerGets"], &book.taker_pays)
+            && issue_matches(&fields["TakerPays"], &book.taker_gets);
+
+        forward || (book.both && reverse)
+    })
+}
+
+fn is_type(message: &Value, message_type: &str) -> bool {
+    message["type"] == message_type
+}
+
+impl WebSocketTransport {
+    /// Send `subscribe` and remember it for replay on reconnect
+    async fn start_subscription(&self, params: Value) -> Result<(u64, Value)> {
+        let response = self.request("subscribe", params.clone()).await?;
+
+        if response["status"] == "error" {
+            return Err(XrplError::ApiError(
+                response["error"].as_str().unwrap_or("subscribe failed").to_string(),
+            )
+            .into());
+        }
+
+        let key = self.register_subscription(params);
+        Ok((key, response["result"].clone()))
+    }
+
+    /// Follow closed ledgers
+    pub async fn subscribe_ledger(&self) -> Result<SubscriptionStream<LedgerClosed>> {
+        let events = self.events();
+        let (key, _) = self.start_subscription(json!({ "streams": ["ledger"] })).await?;
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(|message| is_type(message, "ledgerClosed")),
+        ))
+    }
+
+    /// Follow every validated transaction
+    pub async fn subscribe_transactions(&self) -> Result<SubscriptionStream<TransactionEvent>> {
+        let events = self.events();
+        let (key, _) = self
+            .start_subscription(json!({ "streams": ["transactions"] }))
+            .await?;
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(|message| is_type(message, "transaction") && message["validated"] == true),
+        ))
+    }
+
+    /// Follow validation messages from the network's validators
+    pub async fn subscribe_validations(&self) -> Result<SubscriptionStream<ValidationEvent>> {
+        let events = self.events();
+        let (key, _) = self
+            .start_subscription(json!({ "streams": ["validations"] }))
+            .await?;
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(|message| is_type(message, "validationReceived")),
+        ))
+    }
+
+    /// Follow changes of the server's status and load
+    pub async fn subscribe_server(&self) -> Result<SubscriptionStream<ServerStatusEvent>> {
+        let events = self.events();
+        let (key, _) = self.start_subscription(json!({ "streams": ["server"] })).await?;
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(|message| is_type(message, "serverStatus")),
+        ))
+    }
+
+    /// Follow validated transactions affecting any of `accounts`
+    pub async fn subscribe_accounts(&self, accounts: &[&str]) -> Result<SubscriptionStream<TransactionEvent>> {
+        let events = self.events();
+        let (key, _) = self
+            .start_subscription(json!({ "accounts": accounts }))
+            .await?;
+        let watched: HashSet<String> = accounts.iter().map(|a| a.to_string()).collect();
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(move |message| {
+                is_type(message, "transaction")
+                    && message["validated"] == true
+                    && mentions_account(message, &watched)
+            }),
+        ))
+    }
+
+    /// Follow not yet validated transactions affecting any of `accounts`
+    pub async fn subscribe_accounts_proposed(
+        &self,
+        accounts: &[&str],
+    ) -> Result<SubscriptionStream<TransactionEvent>> {
+        let events = self.events();
+        let (key, _) = self
+            .start_subscription(json!({ "accounts_proposed": accounts }))
+            .await?;
+        let watched: HashSet<String> = accounts.iter().map(|a| a.to_string()).collect();
+        Ok(typed_stream(
+            key,
+            events,
+            Box::new(move |message| {
+                is_type(message, "transaction")
+                    && message["validated"] != true
+                    && mentions_account(message, &watched)
+            }),
+        ))
+    }
+
+    /// Follow transactions that change offers in `book`, returning the
+    /// current offers first when `book.snapshot` is set
+    pub async fn subscribe_book(
+        &self,
+        book: &OrderBook,
+    ) -> Result<(BookSnapshot, SubscriptionStream<TransactionEvent>)> {
+        let events = self.events();
+        let (key, result) = self
+            .start_subscription(json!({ "books": [book] }))
+            .await?;
+
+        let offers = |field: &str| -> Vec<Value> {
+            result[field].as_array().cloned().unwrap_or_default()
+        };
+        let snapshot = BookSnapshot {
+            offers: offers("offers"),
+            asks: offers("asks"),
+            bids: offers("bids"),
+        };
+
+        let book = book.clone();
+        let stream = typed_stream(
+            key,
+            events,
+            Box::new(move |message| {
+                is_type(message, "transaction")
+                    && message["validated"] == true
+                    && touches_book(message, &book)
+            }),
+        );
+
+        Ok((snapshot, stream))
+    }
+
+    /// Stop a subscription. The server is only told to unsubscribe once no
+    /// other stream depends on the same subscription.
+    pub async fn unsubscribe<T>(&self, stream: SubscriptionStream<T>) -> Result<()> {
+        let (mut params, still_needed) = match self.remove_subscription(stream.key()) {
+            Some(removed) => removed,
+            None => return Ok(()),
+        };
+
+        if still_needed {
+            return Ok(());
+        }
+
+        if let Some(map) = params.as_object_mut() {
+            map.remove("snapshot");
+        }
+        if let Some(books) = params["books"].as_array_mut() {
+            for book in books {
+                if let Some(map) = book.as_object_mut() {
+                    map.remove("snapshot");
+                }
+            }
+        }
+
+        let response = self.request("unsubscribe", params).await?;
+        if response["status"] == "error" {
+            return Err(XrplError::ApiError(
+                response["error"].as_str().unwrap_or("unsubscribe failed").to_string(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offer_change(gets: Value, pays: Value) -> Value {
+        json!({
+            "type": "transaction",
+            "validated": true,
+            "meta": {
+                "AffectedNodes": [{
+                    "ModifiedNode": {
+                        "LedgerEntryType": "Offer",
+                        "FinalFields": { "TakerGets": gets, "TakerPays": pays }
+                    }
+                }]
+            }
+        })
+    }
+
+    #[test]
+    fn test_touches_book() {
+        let usd = Issue::issued("USD", "rIssuer123456789012345678901234");
+        let book = OrderBook::new(Issue::xrp(), usd.clone());
+        let usd_amount = json!({ "currency": "USD", "issuer": "rIssuer123456789012345678901234", "value": "10" });
+
+        assert!(touches_book(&offer_change(json!("1000000"), usd_amount.clone()), &book));
+        assert!(!touches_book(&offer_change(usd_amount.clone(), json!("1000000")), &book));
+
+        let both = OrderBook { both: true, ..book };
+        assert!(touches_book(&offer_change(usd_amount, json!("1000000")), &both));
+    }
+
+    #[test]
+    fn test_mentions_account() {
+        let accounts: HashSet<String> = ["rWatched123".to_string()].into_iter().collect();
+        let message = json!({
+            "transaction": { "Account": "rOther", "Destination": "rWatched123" }
+        });
+
+        assert!(mentions_account(&message, &accounts));
+        assert!(!mentions_account(&json!({ "transaction": { "Account": "rOther" } }), &accounts));
+    }
+}
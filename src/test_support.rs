@@ -0,0 +1,60 @@
+//! Fixtures shared by the unit tests: a client on top of `MockTransport`
+//! and canned results of the methods most tests mock.
+
+use crate::client::XrplClient;
+use crate::network::Network;
+use crate::transport::MockTransport;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// A testnet client sending everything to `mock`
+pub fn client(mock: &Arc<MockTransport>) -> XrplClient {
+    XrplClient::builder(Network::testnet())
+        .transport(mock.clone())
+        .build()
+        .unwrap()
+}
+
+/// `submit` result with the given preliminary result
+pub fn submit_result(engine_result: &str) -> Value {
+    json!({
+        "engine_result": engine_result,
+        "engine_result_code": 0,
+        "engine_result_message": "",
+        "tx_blob": "",
+        "tx_json": {}
+    })
+}
+
+/// `tx` result of a payment validated with `tesSUCCESS`
+pub fn validated(hash: &str, account: &str, sequence: u32, ledger_index: u32) -> Value {
+    json!({
+        "hash": hash,
+        "ledger_index": ledger_index,
+        "validated": true,
+        "TransactionType": "Payment",
+        "Account": account,
+        "Fee": "12",
+        "Sequence": sequence,
+        "meta": { "TransactionIndex": 0, "TransactionResult": "tesSUCCESS", "AffectedNodes": [] }
+    })
+}
+
+/// `account_info` result of an account holding 50 XRP on validated ledger 100
+pub fn account_info(account: &str, sequence: u32, flags: u32) -> Value {
+    json!({
+        "account_data": { "Account": account, "Balance": "50000000", "Flags": flags, "Sequence": sequence },
+        "ledger_index": 100,
+        "validated": true
+    })
+}
+
+/// `account_info` result on open ledger 101, with the account's `queue_data`
+pub fn queued_account_info(account: &str, sequence: u32, queue_data: Value) -> Value {
+    json!({
+        "account_data": { "Account": account, "Balance": "50000000", "Sequence": sequence },
+        "ledger_current_index": 101,
+        "queue_data": queue_data,
+        "validated": false
+    })
+}
@@ -0,0 +1,413 @@
+use crate::cancel::CANCEL_LEDGER_WINDOW;
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::fee::TransactionCost;
+use crate::resubmit::FeeBumpPolicy;
+use crate::sequence::SequenceManager;
+use crate::signing::TransactionSigner;
+use crate::transaction::TransactionBuilder;
+use crate::types::*;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Hands out an account's tickets so transactions can be prepared and
+/// signed out of order, e.g. while collecting multisig signatures.
+///
+/// Available tickets are read from `account_objects` on first use. A reserved
+/// ticket stays out of the pool until it is released, if the transaction is
+/// abandoned, or consumed once it has been submitted. A consumed ticket is
+/// never handed out again, even while it is still on the validated ledger
+/// because its transaction is pending.
+pub struct TicketPool {
+    account: Address,
+    top_up: Option<TopUp>,
+    state: Mutex<TicketState>,
+}
+
+/// Creates more tickets when the pool runs low
+struct TopUp {
+    secret: String,
+    low_water: usize,
+    count: u32,
+    policy: FeeBumpPolicy,
+    sequences: Arc<SequenceManager>,
+    /// Held while a TicketCreate is in flight, so only one runs at a time
+    creating: Mutex<()>,
+}
+
+#[derive(Default)]
+struct TicketState {
+    available: BTreeSet<u32>,
+    /// Handed out and not yet released or consumed
+    reserved: BTreeSet<u32>,
+    /// Used by a submitted transaction, until the ticket leaves the ledger
+    consumed: BTreeSet<u32>,
+    synced: bool,
+}
+
+impl TicketPool {
+    pub fn new(account: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            top_up: None,
+            state: Mutex::new(TicketState::default()),
+        }
+    }
+
+    /// Submit a TicketCreate for `count` tickets, signed with `secret`,
+    /// whenever fewer than `low_water` are available on `reserve`
+    pub fn with_top_up(mut self, secret: &str, low_water: usize, count: u32, policy: FeeBumpPolicy) -> Self {
+        self.top_up = Some(TopUp {
+            secret: secret.to_string(),
+            low_water,
+            count,
+            policy,
+            sequences: Arc::new(SequenceManager::new(&self.account)),
+            creating: Mutex::new(()),
+        });
+        self
+    }
+
+    /// Take the TicketCreate's sequence from the manager used for the
+    /// account's other transactions, e.g. `XrplLib::sequence_manager`, so a
+    /// top-up does not collide with them. Call after `with_top_up`.
+    pub fn with_sequence_manager(mut self, sequences: Arc<SequenceManager>) -> Self {
+        if let Some(top_up) = &mut self.top_up {
+            top_up.sequences = sequences;
+        }
+        self
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Number of tickets that can be reserved without a top-up
+    pub async fn available(&self) -> usize {
+        self.state.lock().await.available.len()
+    }
+
+    /// Reload the account's tickets from the validated ledger. Reserved and
+    /// consumed tickets that still exist stay out of the pool.
+    pub async fn refresh(&self, client: &XrplClient) -> Result<usize> {
+        let mut state = self.state.lock().await;
+        self.sync(client, &mut state).await?;
+        Ok(state.available.len())
+    }
+
+    /// Take the lowest available ticket, creating more first if configured.
+    /// The pool stays usable while the TicketCreate waits for validation.
+    pub async fn reserve(&self, client: &XrplClient) -> Result<u32> {
+        let low = {
+            let mut state = self.state.lock().await;
+            if !state.synced {
+                self.sync(client, &mut state).await?;
+            }
+            self.top_up.as_ref().filter(|top_up| state.available.len() < top_up.low_water)
+        };
+
+        if let Some(top_up) = low {
+            let _creating = top_up.creating.lock().await;
+            // Another reserve may have topped up while this one waited
+            if self.state.lock().await.available.len() < top_up.low_water {
+                let created = self.create_tickets(client, top_up).await?;
+                self.state.lock().await.available.extend(created);
+            }
+        }
+
+        let mut state = self.state.lock().await;
+        let ticket = state.available.pop_first().ok_or_else(|| {
+            XrplError::InvalidTransaction(format!("No tickets available for {}", self.account))
+        })?;
+        state.reserved.insert(ticket);
+
+        Ok(ticket)
+    }
+
+    /// Put back a reserved ticket whose transaction was abandoned
+    pub async fn release(&self, ticket: u32) {
+        let mut state = self.state.lock().await;
+        if state.reserved.remove(&ticket) {
+            state.available.insert(ticket);
+        }
+    }
+
+    /// Mark a reserved ticket as used once its transaction has been submitted
+    pub async fn consume(&self, ticket: u32) {
+        let mut state = self.state.lock().await;
+        if state.reserved.remove(&ticket) {
+            state.consumed.insert(ticket);
+        }
+    }
+
+    async fn sync(&self, client: &XrplClient, state: &mut TicketState) -> Result<()> {
+        let objects = client
+            .account_objects(&self.account)
+            .param("type", json!("ticket"))
+            .collect_all()
+            .await?;
+        let tickets: BTreeSet<u32> = objects
+            .iter()
+            .filter_map(|object| object["TicketSequence"].as_u64())
+            .map(|ticket| ticket as u32)
+            .collect();
+
+        state.reserved.retain(|ticket| tickets.contains(ticket));
+        state.consumed.retain(|ticket| tickets.contains(ticket));
+        state.available = tickets
+            .iter()
+            .filter(|ticket| !state.reserved.contains(ticket) && !state.consumed.contains(ticket))
+            .copied()
+            .collect();
+        state.synced = true;
+
+        Ok(())
+    }
+
+    /// Submit a TicketCreate and wait for it to be validated
+    async fn create_tickets(&self, client: &XrplClient, top_up: &TopUp) -> Result<Vec<u32>> {
+        let fee = client.estimate_fee(&TransactionCost::reference()).await?;
+        let last_ledger_sequence = client.get_ledger_index().await? + CANCEL_LEDGER_WINDOW;
+        let sequence = top_up.sequences.next(client).await?;
+
+        let builder = TransactionBuilder::with_network(client.network().clone());
+        let signer = TransactionSigner::with_network(client.network().clone());
+        let prepared = builder
+            .build_ticket_create_transaction(
+                &self.account,
+                top_up.count,
+                Some(&fee.to_string()),
+                sequence,
+                Some(last_ledger_sequence),
+            )
+            .and_then(|transaction| {
+                let signed = signer.sign_transaction(&top_up.secret, &transaction)?;
+                Ok((transaction, TransactionSigner::transaction_hash(&signed.tx_blob)?))
+            });
+        let (transaction, hash) = match prepared {
+            Ok(prepared) => prepared,
+            Err(error) => {
+                // Never submitted
+                top_up.sequences.release(sequence).await;
+                return Err(error);
+            }
+        };
+
+        let outcome = match client.submit_with_fee_bump(&top_up.secret, &transaction, &top_up.policy).await {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                self.settle_sequence(client, top_up, sequence, last_ledger_sequence, &hash, &error).await;
+                return Err(error);
+            }
+        };
+        let meta = outcome
+            .transaction
+            .meta
+            .ok_or_else(|| XrplError::Deserialization("TicketCreate has no metadata".to_string()))?;
+        if meta.transaction_result != "tesSUCCESS" {
+            return Err(XrplError::TransactionFailed(meta.transaction_result).into());
+        }
+
+        Ok(meta.created_tickets())
+    }
+
+    /// Decide whether a TicketCreate that did not come back validated used
+    /// its sequence. Only a rejection or an expired LastLedgerSequence frees
+    /// it for sure; after a failed poll the TicketCreate may still validate.
+    async fn settle_sequence(
+        &self,
+        client: &XrplClient,
+        top_up: &TopUp,
+        sequence: u32,
+        last_ledger_sequence: u32,
+        hash: &str,
+        error: &anyhow::Error,
+    ) {
+        if let Some(XrplError::TransactionFailed(result)) = error.downcast_ref::<XrplError>() {
+            if is_engine_result(result) {
+                let _ = top_up.sequences.handle_result(client, sequence, result).await;
+                return;
+            }
+        }
+
+        match client.get_ledger_index().await {
+            Ok(validated_ledger) if validated_ledger > last_ledger_sequence => {
+                top_up.sequences.release(sequence).await;
+            }
+            _ => top_up.sequences.reconcile(client, sequence, hash).await,
+        }
+    }
+}
+
+/// Whether an error message is a bare engine result such as `tefPAST_SEQ`
+fn is_engine_result(message: &str) -> bool {
+    message.starts_with("te") && message.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, queued_account_info, submit_result, validated};
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const SECRET: &str = "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2";
+    const ACCOUNT: &str = "rSender123456789012345678901234";
+
+    fn tickets(sequences: &[u32]) -> serde_json::Value {
+        let objects: Vec<_> = sequences
+            .iter()
+            .map(|sequence| json!({ "LedgerEntryType": "Ticket", "Account": ACCOUNT, "TicketSequence": sequence }))
+            .collect();
+        json!({ "account": ACCOUNT, "account_objects": objects, "ledger_index": 100 })
+    }
+
+    #[tokio::test]
+    async fn test_reserve_release_consume() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[7, 5, 6]));
+        let client = client(&mock);
+        let pool = TicketPool::new(ACCOUNT);
+
+        assert_eq!(pool.reserve(&client).await.unwrap(), 5);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 6);
+        pool.release(5).await;
+        pool.consume(6).await;
+        assert_eq!(pool.available().await, 2);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 5);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 7);
+        assert!(pool.reserve(&client).await.is_err());
+
+        assert_eq!(mock.request_count("account_objects"), 1);
+        assert_eq!(mock.requests_for("account_objects")[0]["type"], "ticket");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_reservations() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[5, 6]))
+            .on("account_objects", tickets(&[5, 6, 9]));
+        let client = client(&mock);
+        let pool = TicketPool::new(ACCOUNT);
+
+        assert_eq!(pool.reserve(&client).await.unwrap(), 5);
+        assert_eq!(pool.refresh(&client).await.unwrap(), 2);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_consumed_tickets_stay_out_until_gone() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[5, 6]))
+            .on("account_objects", tickets(&[5, 6]))
+            .on("account_objects", tickets(&[6]));
+        let client = client(&mock);
+        let pool = TicketPool::new(ACCOUNT);
+
+        assert_eq!(pool.reserve(&client).await.unwrap(), 5);
+        pool.consume(5).await;
+        assert_eq!(pool.refresh(&client).await.unwrap(), 1);
+        assert_eq!(pool.refresh(&client).await.unwrap(), 1);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_top_up_when_low() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[5]));
+        mock.on("account_info", json!({
+            "account_data": { "Account": ACCOUNT, "Balance": "50000000", "Sequence": 18 },
+            "ledger_current_index": 101,
+            "queue_data": { "txn_count": 2, "highest_sequence": 19 },
+            "validated": false
+        }));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("submit", json!({
+            "engine_result": "tesSUCCESS",
+            "engine_result_code": 0,
+            "engine_result_message": "",
+            "tx_blob": "",
+            "tx_json": {}
+        }));
+        mock.on("tx", json!({
+            "hash": "CREATE",
+            "ledger_index": 101,
+            "validated": true,
+            "TransactionType": "TicketCreate",
+            "Account": ACCOUNT,
+            "Fee": "12",
+            "Sequence": 20,
+            "TicketCount": 2,
+            "meta": {
+                "TransactionIndex": 0,
+                "TransactionResult": "tesSUCCESS",
+                "AffectedNodes": [
+                    { "CreatedNode": { "LedgerEntryType": "Ticket", "LedgerIndex": "T1", "NewFields": { "Account": ACCOUNT, "TicketSequence": 21 } } },
+                    { "CreatedNode": { "LedgerEntryType": "Ticket", "LedgerIndex": "T2", "NewFields": { "Account": ACCOUNT, "TicketSequence": 22 } } }
+                ]
+            }
+        }));
+        let client = client(&mock);
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(1));
+        let sequences = Arc::new(SequenceManager::new(ACCOUNT));
+        let pool = TicketPool::new(ACCOUNT)
+            .with_top_up(SECRET, 2, 2, policy)
+            .with_sequence_manager(sequences.clone());
+
+        assert_eq!(pool.reserve(&client).await.unwrap(), 5);
+        assert_eq!(pool.available().await, 2);
+        assert_eq!(pool.reserve(&client).await.unwrap(), 21);
+        assert_eq!(mock.request_count("submit"), 1);
+        // The TicketCreate took 20, after the queued transactions
+        assert_eq!(sequences.next(&client).await.unwrap(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_kept_when_poll_fails() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[]));
+        mock.on("account_info", queued_account_info(ACCOUNT, 20, json!(null)));
+        mock.on("ledger", json!({ "ledger_index": 100 }))
+            .on_error("ledger", "internal");
+        mock.on("submit", submit_result("terQUEUED"));
+        mock.on("tx", validated("CREATE", ACCOUNT, 20, 101));
+        let client = client(&mock);
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(1));
+        let sequences = Arc::new(SequenceManager::new(ACCOUNT));
+        let pool = TicketPool::new(ACCOUNT)
+            .with_top_up(SECRET, 1, 2, policy)
+            .with_sequence_manager(sequences.clone());
+
+        assert!(pool.reserve(&client).await.is_err());
+        // The submitted TicketCreate is known and may still validate
+        assert_eq!(sequences.next(&client).await.unwrap(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_pool_usable_during_top_up() {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_objects", tickets(&[5]));
+        mock.on("account_info", queued_account_info(ACCOUNT, 20, json!(null)));
+        mock.on("ledger", json!({ "ledger_index": 100 }));
+        mock.on("submit", submit_result("terQUEUED"));
+        mock.on_error("tx", "txnNotFound")
+            .on_error("tx", "txnNotFound")
+            .on_error("tx", "txnNotFound")
+            .on("tx", validated("CREATE", ACCOUNT, 20, 101));
+        let client = client(&mock);
+        let policy = FeeBumpPolicy::new(100).with_poll_interval(Duration::from_millis(20));
+        let pool = TicketPool::new(ACCOUNT).with_top_up(SECRET, 2, 2, policy);
+        pool.refresh(&client).await.unwrap();
+
+        let (reserved, available) = tokio::join!(pool.reserve(&client), async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            tokio::time::timeout(Duration::from_millis(10), pool.available()).await
+        });
+        assert_eq!(available.unwrap(), 1);
+        assert_eq!(reserved.unwrap(), 5);
+    }
+}
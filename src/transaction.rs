@@ -1,18 +1,37 @@
 use crate::error::XrplError;
+use crate::fee::DEFAULT_FEE_DROPS;
+use crate::network::Network;
+use crate::preflight::preflight;
 use crate::types::*;
 use anyhow::Result;
 use serde_json::{json, Value};
 
+/// Most tickets an account can hold, and so create at once
+pub const MAX_TICKET_COUNT: u32 = 250;
+
 /// Transaction builder for creating XRPL transactions
 pub struct TransactionBuilder {
-    testnet: bool,
+    network: Network,
 }
 
 impl TransactionBuilder {
     pub fn new(testnet: bool) -> Self {
-        Self { testnet }
+        if testnet {
+            Self::with_network(Network::testnet())
+        } else {
+            Self::with_network(Network::mainnet())
+        }
+    }
+
+    pub fn with_network(network: Network) -> Self {
+        Self { network }
     }
 
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn build_payment_transaction(
         &self,
         account: &str,
@@ -24,22 +43,23 @@ impl TransactionBuilder {
         sequence: u32,
         last_ledger_sequence: Option<u32>,
     ) -> Result<Transaction> {
-        let mut transaction = Transaction::default();
-        
-        transaction.account = account.to_string();
-        transaction.destination = destination.to_string();
-        transaction.amount = amount.to_string();
-        transaction.currency = currency.to_string();
-        transaction.issuer = issuer.map(|i| i.to_string());
-        transaction.fee = fee.unwrap_or("12").to_string();
-        transaction.sequence = sequence;
-        transaction.last_ledger_sequence = last_ledger_sequence;
-        
-        transaction.flags = Some(0x00020000);
-        
+        let transaction = Transaction {
+            account: account.to_string(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            currency: currency.to_string(),
+            issuer: issuer.map(|i| i.to_string()),
+            fee: fee.map(str::to_string).unwrap_or_else(|| DEFAULT_FEE_DROPS.to_string()),
+            sequence,
+            last_ledger_sequence,
+            network_id: self.network_id_field(),
+            ..Transaction::default()
+        };
+
         Ok(transaction)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn build_trust_set_transaction(
         &self,
         account: &str,
@@ -50,51 +70,60 @@ impl TransactionBuilder {
         sequence: u32,
         last_ledger_sequence: Option<u32>,
     ) -> Result<Transaction> {
-        let mut transaction = Transaction::default();
-        
-        transaction.transaction_type = "TrustSet".to_string();
-        transaction.account = account.to_string();
-        transaction.fee = fee.unwrap_or("12").to_string();
-        transaction.sequence = sequence;
-        transaction.last_ledger_sequence = last_ledger_sequence;
-        
-        transaction.amount = limit.to_string();
-        transaction.currency = currency.to_string();
-        transaction.issuer = Some(issuer.to_string());
-        
+        let transaction = Transaction {
+            transaction_type: "TrustSet".to_string(),
+            account: account.to_string(),
+            fee: fee.map(str::to_string).unwrap_or_else(|| DEFAULT_FEE_DROPS.to_string()),
+            sequence,
+            last_ledger_sequence,
+            amount: limit.to_string(),
+            currency: currency.to_string(),
+            issuer: Some(issuer.to_string()),
+            network_id: self.network_id_field(),
+            ..Transaction::default()
+        };
+
         Ok(transaction)
     }
 
-    pub fn validate_transaction(&self, transaction: &Transaction) -> Result<()> {
-        if transaction.account.is_empty() {
-            return Err(XrplError::InvalidTransaction("Account is required".to_string()).into());
-        }
-
-        if transaction.destination.is_empty() {
-            return Err(XrplError::InvalidTransaction("Destination is required".to_string()).into());
-        }
-
-        if transaction.amount.is_empty() {
-            return Err(XrplError::InvalidTransaction("Amount is required".to_string()).into());
-        }
-
-        if transaction.currency.is_empty() {
-            return Err(XrplError::InvalidTransaction("Currency is required".to_string()).into());
+    /// Build a TicketCreate setting aside `ticket_count` sequence numbers
+    pub fn build_ticket_create_transaction(
+        &self,
+        account: &str,
+        ticket_count: u32,
+        fee: Option<&str>,
+        sequence: u32,
+        last_ledger_sequence: Option<u32>,
+    ) -> Result<Transaction> {
+        if !(1..=MAX_TICKET_COUNT).contains(&ticket_count) {
+            return Err(XrplError::InvalidTransaction(format!(
+                "TicketCount must be between 1 and {}",
+                MAX_TICKET_COUNT
+            ))
+            .into());
         }
 
-        if transaction.fee.is_empty() {
-            return Err(XrplError::InvalidTransaction("Fee is required".to_string()).into());
-        }
+        let transaction = Transaction {
+            transaction_type: "TicketCreate".to_string(),
+            account: account.to_string(),
+            fee: fee.map(str::to_string).unwrap_or_else(|| DEFAULT_FEE_DROPS.to_string()),
+            sequence,
+            last_ledger_sequence,
+            ticket_count: Some(ticket_count),
+            network_id: self.network_id_field(),
+            ..Transaction::default()
+        };
 
-        if let Err(_) = transaction.amount.parse::<f64>() {
-            return Err(XrplError::InvalidAmount("Invalid amount format".to_string()).into());
-        }
+        Ok(transaction)
+    }
 
-        if let Err(_) = transaction.fee.parse::<u32>() {
-            return Err(XrplError::InvalidTransaction("Invalid fee format".to_string()).into());
+    /// Check a transaction against rippled's `tem` rules for its type. The
+    /// error is the first `PreflightError` found; use `preflight` for all.
+    pub fn validate_transaction(&self, transaction: &Transaction) -> Result<()> {
+        match preflight(transaction).into_iter().next() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
         }
-
-        Ok(())
     }
 
     pub fn transaction_to_json(&self, transaction: &Transaction) -> Result<Value> {
@@ -132,14 +161,39 @@ impl TransactionBuilder {
             tx_json["InvoiceID"] = json!(invoice_id);
         }
 
+        if let Some(network_id) = transaction.network_id {
+            tx_json["NetworkID"] = json!(network_id);
+        }
+
+        if let Some(ticket_sequence) = transaction.ticket_sequence {
+            tx_json["TicketSequence"] = json!(ticket_sequence);
+        }
+
+        if let Some(ticket_count) = transaction.ticket_count {
+            tx_json["TicketCount"] = json!(ticket_count);
+        }
+
+        if let Some(expiration) = transaction.expiration {
+            tx_json["Expiration"] = json!(expiration);
+        }
+
+        if let Some(memos) = &transaction.memos {
+            let memos: Vec<Value> = memos.iter().map(|memo| json!({ "Memo": memo })).collect();
+            tx_json["Memos"] = json!(memos);
+        }
+
         Ok(tx_json)
     }
 
     pub fn get_network_id(&self) -> u32 {
-        if self.testnet {
-            1024 // Testnet network ID
+        self.network.network_id
+    }
+
+    fn network_id_field(&self) -> Option<u32> {
+        if self.network.requires_network_id() {
+            Some(self.network.network_id)
         } else {
-            1049344 // Mainnet network ID
+            None
         }
     }
 }
@@ -202,7 +256,7 @@ impl TransactionValidator {
             return Err(XrplError::InvalidAmount("Amount cannot be empty".to_string()).into());
         }
 
-        if let Err(_) = amount.parse::<f64>() {
+        if amount.parse::<f64>().is_err() {
             return Err(XrplError::InvalidAmount("Invalid amount format".to_string()).into());
         }
 
@@ -221,7 +275,37 @@ mod tests {
     #[test]
     fn test_transaction_builder_creation() {
         let builder = TransactionBuilder::new(true);
-        assert!(builder.testnet);
+        assert!(builder.network.is_testnet());
+    }
+
+    #[test]
+    fn test_network_id_field() {
+        let builder = TransactionBuilder::with_network(Network::custom("sidechain", 21337, "http://localhost:5005"));
+        let tx = builder.build_payment_transaction(
+            "rAccount123",
+            "rDestination456",
+            "100",
+            "USD",
+            Some("rIssuer789"),
+            None,
+            1,
+            None,
+        ).unwrap();
+
+        assert_eq!(tx.network_id, Some(21337));
+        assert_eq!(builder.transaction_to_json(&tx).unwrap()["NetworkID"], 21337);
+
+        let testnet_tx = TransactionBuilder::new(true).build_payment_transaction(
+            "rAccount123",
+            "rDestination456",
+            "100",
+            "USD",
+            Some("rIssuer789"),
+            None,
+            1,
+            None,
+        ).unwrap();
+        assert_eq!(testnet_tx.network_id, None);
     }
 
     #[test]
@@ -248,6 +332,31 @@ mod tests {
         assert_eq!(tx.last_ledger_sequence, Some(1000));
     }
 
+    #[test]
+    fn test_ticket_create_building() {
+        let builder = TransactionBuilder::new(true);
+        let tx = builder
+            .build_ticket_create_transaction("rAccount123", 10, None, 5, Some(1000))
+            .unwrap();
+
+        assert_eq!(tx.transaction_type, "TicketCreate");
+        assert_eq!(builder.transaction_to_json(&tx).unwrap()["TicketCount"], 10);
+        assert!(builder.build_ticket_create_transaction("rAccount123", 0, None, 5, None).is_err());
+        assert!(builder.build_ticket_create_transaction("rAccount123", 251, None, 5, None).is_err());
+    }
+
+    #[test]
+    fn test_memos_in_json() {
+        let builder = TransactionBuilder::new(true);
+        let tx = Transaction {
+            memos: Some(vec![Memo::text("payout")]),
+            ..Transaction::default()
+        };
+
+        let tx_json = builder.transaction_to_json(&tx).unwrap();
+        assert_eq!(tx_json["Memos"][0]["Memo"]["MemoData"], hex::encode_upper("payout"));
+    }
+
     #[test]
     fn test_transaction_validation() {
         let builder = TransactionBuilder::new(true);
@@ -263,6 +372,17 @@ mod tests {
         ).unwrap();
 
         assert!(builder.validate_transaction(&tx).is_ok());
+        assert_eq!(tx.flags, None);
+
+        let xrp = builder
+            .build_payment_transaction("rAccount123", "rDestination456", "1000000", "XRP", None, Some("12"), 1, Some(1000))
+            .unwrap();
+        assert!(builder.validate_transaction(&xrp).is_ok());
+
+        let trust_set = builder
+            .build_trust_set_transaction("rAccount123", "USD", "rIssuer789", "1000", None, 1, None)
+            .unwrap();
+        assert!(builder.validate_transaction(&trust_set).is_ok());
     }
 
     #[test]
@@ -0,0 +1,277 @@
+use crate::error::XrplError;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A way of delivering JSON-RPC requests to a rippled (or Clio) server
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send a request of the form `{"method": ..., "params": [...]}` and return the response body
+    async fn send(&self, request: &Value) -> Result<Value>;
+
+    /// Description of the endpoint, e.g. its URL
+    fn endpoint(&self) -> &str;
+}
+
+/// JSON-RPC over HTTP(S) using `reqwest`
+pub struct HttpTransport {
+    client: Client,
+    url: String,
+}
+
+impl HttpTransport {
+    pub fn new(url: &str) -> Self {
+        Self::with_client(Client::new(), url)
+    }
+
+    /// Use a preconfigured `reqwest` client (timeouts, proxy, headers, TLS)
+    pub fn with_client(client: Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: &Value) -> Result<Value> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| XrplError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(XrplError::HttpStatus(response.status().as_u16()).into());
+        }
+
+        let response_data: Value = response
+            .json()
+            .await
+            .map_err(|e| XrplError::Network(e.to_string()))?;
+        Ok(response_data)
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.url
+    }
+}
+
+/// A canned reply of the `MockTransport`
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    /// Respond with `{"result": <value>}`
+    Result(Value),
+    /// Fail with the given HTTP status
+    HttpStatus(u16),
+    /// Fail as if the connection broke
+    NetworkError(String),
+}
+
+struct RegisteredReply {
+    params: Option<Value>,
+    reply: MockReply,
+}
+
+impl RegisteredReply {
+    /// Whether every field of the registered params appears in the request params
+    fn matches(&self, request_params: &Value) -> bool {
+        match &self.params {
+            None => true,
+            Some(Value::Object(expected)) => expected
+                .iter()
+                .all(|(key, value)| request_params.get(key) == Some(value)),
+            Some(expected) => expected == request_params,
+        }
+    }
+}
+
+/// In-memory transport for tests: canned responses per RPC method and a log
+/// of every request sent.
+///
+/// Replies registered for a method are used in order; the last one keeps
+/// being returned once the others are used up. Replies registered with
+/// `on_params` only answer requests containing those params and take
+/// precedence over the others. Methods without a registered reply get
+/// rippled's `unknownCmd` error.
+pub struct MockTransport {
+    name: String,
+    replies: Mutex<HashMap<String, Vec<RegisteredReply>>>,
+    requests: Mutex<Vec<Value>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::named("mock")
+    }
+
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            replies: Mutex::new(HashMap::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue the `result` object to return for `method`
+    pub fn on(&self, method: &str, result: Value) -> &Self {
+        self.reply(method, MockReply::Result(result))
+    }
+
+    /// Queue the `result` object to return for `method` requests whose params
+    /// contain all fields of `params`
+    pub fn on_params(&self, method: &str, params: Value, result: Value) -> &Self {
+        self.register(method, Some(params), MockReply::Result(result))
+    }
+
+    /// Queue a rippled error response (e.g. `actNotFound`) for `method`
+    pub fn on_error(&self, method: &str, error: &str) -> &Self {
+        self.reply(
+            method,
+            MockReply::Result(json!({
+                "error": error,
+                "status": "error"
+            })),
+        )
+    }
+
+    pub fn reply(&self, method: &str, reply: MockReply) -> &Self {
+        self.register(method, None, reply)
+    }
+
+    fn register(&self, method: &str, params: Option<Value>, reply: MockReply) -> &Self {
+        self.replies
+            .lock()
+            .unwrap()
+            .entry(method.to_string())
+            .or_default()
+            .push(RegisteredReply { params, reply });
+        self
+    }
+
+    /// Every request sent so far, in order
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Params objects of every request sent for `method`
+    pub fn requests_for(&self, method: &str) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request["method"] == method)
+            .map(|request| request["params"][0].clone())
+            .collect()
+    }
+
+    pub fn request_count(&self, method: &str) -> usize {
+        self.requests_for(method).len()
+    }
+
+    fn next_reply(&self, method: &str, params: &Value) -> Option<MockReply> {
+        let mut replies = self.replies.lock().unwrap();
+        let registered = replies.get_mut(method)?;
+
+        let specific: Vec<usize> = (0..registered.len())
+            .filter(|&i| registered[i].params.is_some() && registered[i].matches(params))
+            .collect();
+        let candidates = if specific.is_empty() {
+            (0..registered.len())
+                .filter(|&i| registered[i].params.is_none())
+                .collect()
+        } else {
+            specific
+        };
+
+        match candidates.len() {
+            0 => None,
+            1 => Some(registered[candidates[0]].reply.clone()),
+            _ => Some(registered.remove(candidates[0]).reply),
+        }
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request: &Value) -> Result<Value> {
+        self.requests.lock().unwrap().push(request.clone());
+
+        let method = request["method"].as_str().unwrap_or("");
+        match self.next_reply(method, &request["params"][0]) {
+            Some(MockReply::Result(result)) => Ok(json!({ "result": result })),
+            Some(MockReply::HttpStatus(status)) => Err(XrplError::HttpStatus(status).into()),
+            Some(MockReply::NetworkError(message)) => Err(XrplError::Network(message).into()),
+            None => Ok(json!({
+                "result": {
+                    "error": "unknownCmd",
+                    "status": "error",
+                    "request": request
+                }
+            })),
+        }
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_replies_in_order() {
+        let mock = MockTransport::new();
+        mock.on("ledger", json!({ "ledger_index": 1 }))
+            .on("ledger", json!({ "ledger_index": 2 }));
+
+        let request = json!({ "method": "ledger", "params": [{}] });
+        assert_eq!(mock.send(&request).await.unwrap()["result"]["ledger_index"], 1);
+        assert_eq!(mock.send(&request).await.unwrap()["result"]["ledger_index"], 2);
+        assert_eq!(mock.send(&request).await.unwrap()["result"]["ledger_index"], 2);
+        assert_eq!(mock.request_count("ledger"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mock_params_matching() {
+        let mock = MockTransport::new();
+        mock.on("ledger", json!({ "ledger_index": 99 }))
+            .on_params("ledger", json!({ "ledger_index": 5 }), json!({ "ledger_index": 5 }));
+
+        let request = json!({ "method": "ledger", "params": [{ "ledger_index": 5, "expand": true }] });
+        assert_eq!(mock.send(&request).await.unwrap()["result"]["ledger_index"], 5);
+
+        let request = json!({ "method": "ledger", "params": [{ "ledger_index": "validated" }] });
+        assert_eq!(mock.send(&request).await.unwrap()["result"]["ledger_index"], 99);
+    }
+
+    #[tokio::test]
+    async fn test_mock_unknown_method() {
+        let mock = MockTransport::new();
+        let response = mock.send(&json!({ "method": "foo", "params": [{}] })).await.unwrap();
+        assert_eq!(response["result"]["error"], "unknownCmd");
+    }
+
+    #[tokio::test]
+    async fn test_mock_failures() {
+        let mock = MockTransport::new();
+        mock.reply("submit", MockReply::HttpStatus(503));
+
+        let error = mock.send(&json!({ "method": "submit", "params": [{}] })).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<XrplError>(), Some(XrplError::HttpStatus(503))));
+    }
+}
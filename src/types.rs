@@ -1,3 +1,5 @@
+use crate::amount::CurrencyAmount;
+use crate::meta::TransactionMeta;
 use serde::{Deserialize, Serialize};
 
 pub type Address = String;
@@ -35,6 +37,18 @@ pub struct Transaction {
     pub paths: Option<Vec<Vec<serde_json::Value>>>,
     pub send_max: Option<Amount>,
     pub deliver_min: Option<Amount>,
+    pub network_id: Option<u32>,
+    /// Ticket used instead of a sequence number; `sequence` must then be 0
+    #[serde(default)]
+    pub ticket_sequence: Option<u32>,
+    /// Number of tickets a TicketCreate creates
+    #[serde(default)]
+    pub ticket_count: Option<u32>,
+    #[serde(default)]
+    pub memos: Option<Vec<Memo>>,
+    /// Ripple epoch seconds after which an offer or check can no longer be used
+    #[serde(default)]
+    pub expiration: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,37 +68,131 @@ pub struct TransactionResult {
     pub meta: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A transaction as returned by the `tx` method
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TransactionMetadata {
+    pub hash: TransactionHash,
+    /// Absent while the transaction is not in a closed ledger
+    pub ledger_index: Option<u32>,
+    pub ledger_hash: Option<TransactionHash>,
+    /// Close time of the ledger in seconds since the Ripple epoch
+    pub date: Option<Timestamp>,
+    pub close_time_iso: Option<String>,
+    pub validated: bool,
+    /// Compact transaction identifier, on servers that support it
+    pub ctid: Option<String>,
+    pub tx_json: TransactionFields,
+    pub meta: Option<TransactionMeta>,
+}
+
+impl<'de> Deserialize<'de> for TransactionMetadata {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let result = serde_json::Value::deserialize(deserializer)?;
+        Self::from_response(&result).map_err(serde::de::Error::custom)
+    }
+}
+
+impl TransactionMetadata {
+    /// Parse a `tx` result. Handles API v2 (`tx_json` next to `meta`) and v1
+    /// (transaction fields flattened into the result).
+    pub fn from_response(result: &serde_json::Value) -> anyhow::Result<Self> {
+        let parse_error = |e: serde_json::Error| crate::error::XrplError::Deserialization(e.to_string());
+
+        let mut result = result.clone();
+        crate::api_version::normalize_transaction(&mut result);
+
+        let tx_json = result
+            .get("tx_json")
+            .filter(|tx_json| tx_json.is_object())
+            .cloned()
+            .ok_or_else(|| crate::error::XrplError::Deserialization("Invalid tx response".to_string()))?;
+        let tx_json: TransactionFields = serde_json::from_value(tx_json).map_err(parse_error)?;
+
+        let meta = match result.get("meta").filter(|meta| meta.is_object()) {
+            Some(meta) => Some(TransactionMeta::from_json(meta)?),
+            None => None,
+        };
+
+        let hash = result["hash"]
+            .as_str()
+            .ok_or_else(|| crate::error::XrplError::Deserialization("Missing transaction hash".to_string()))?;
+
+        Ok(Self {
+            hash: hash.to_string(),
+            ledger_index: result["ledger_index"].as_u64().map(|index| index as u32),
+            ledger_hash: result["ledger_hash"].as_str().map(str::to_string),
+            date: result["date"].as_u64(),
+            close_time_iso: result["close_time_iso"].as_str().map(str::to_string),
+            validated: result["validated"] == true,
+            ctid: result["ctid"].as_str().map(str::to_string),
+            tx_json,
+            meta,
+        })
+    }
+
+    /// The engine result recorded in the metadata, e.g. `tesSUCCESS`
+    pub fn transaction_result(&self) -> Option<&str> {
+        self.meta.as_ref().map(|meta| meta.transaction_result.as_str())
+    }
+}
+
+/// The fields of a transaction with rippled's names. Fields without a
+/// dedicated member are kept in `other`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TransactionFields {
     pub transaction_type: String,
     pub account: Address,
-    pub payment: Option<PaymentDetails>,
     pub fee: Fee,
+    #[serde(default)]
     pub sequence: Sequence,
-    pub hash: TransactionHash,
-    pub ledger_index: u32,
-    pub date: Timestamp,
-    pub validated: bool,
+    #[serde(default)]
+    pub flags: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<CurrencyAmount>,
+    /// API v2 name of a payment's `Amount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deliver_max: Option<CurrencyAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_max: Option<CurrencyAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_tag: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_ledger_sequence: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket_sequence: Option<u32>,
+    #[serde(rename = "NetworkID", default, skip_serializing_if = "Option::is_none")]
+    pub network_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_pub_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txn_signature: Option<String>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccountInfo {
-    pub account_data: AccountData,
-    pub ledger_current_index: u32,
-    pub validated: bool,
+impl TransactionFields {
+    /// `Amount`, or `DeliverMax` in API v2 responses
+    pub fn requested_amount(&self) -> Option<&CurrencyAmount> {
+        self.amount.as_ref().or(self.deliver_max.as_ref())
+    }
+
+    pub fn memos(&self) -> Vec<Memo> {
+        self.other.get("Memos").map(Memo::parse_memos).unwrap_or_default()
+    }
 }
 
+/// The transactions of a validated ledger, as returned by `ledger` with `expand`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccountData {
-    pub account: Address,
-    pub balance: Amount,
-    pub flags: u32,
-    pub ledger_entry_type: String,
-    pub owner_count: u32,
-    pub previous_txn_id: Option<TransactionHash>,
-    pub previous_txn_lgr_seq: Option<u32>,
-    pub sequence: Sequence,
-    pub transfer_rate: Option<u32>,
+pub struct LedgerTransactions {
+    pub ledger_index: u32,
+    pub ledger_hash: TransactionHash,
+    pub close_time: Timestamp,
+    pub transactions: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +208,80 @@ pub struct XrplResponse<T> {
     pub type_field: Option<String>,
 }
 
+/// A memo attached to a transaction. Fields are hex encoded as on the ledger.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memo {
+    #[serde(rename = "MemoType", skip_serializing_if = "Option::is_none")]
+    pub memo_type: Option<String>,
+    #[serde(rename = "MemoData", skip_serializing_if = "Option::is_none")]
+    pub memo_data: Option<String>,
+    #[serde(rename = "MemoFormat", skip_serializing_if = "Option::is_none")]
+    pub memo_format: Option<String>,
+}
+
+impl Memo {
+    /// A plain text memo
+    pub fn text(data: &str) -> Self {
+        Self {
+            memo_type: None,
+            memo_data: Some(hex::encode_upper(data.as_bytes())),
+            memo_format: Some(hex::encode_upper(b"text/plain")),
+        }
+    }
+
+    /// `MemoData` decoded as UTF-8, if it is valid text
+    pub fn data_text(&self) -> Option<String> {
+        decode_hex_text(self.memo_data.as_deref()?)
+    }
+
+    /// `MemoType` decoded as UTF-8, if it is valid text
+    pub fn type_text(&self) -> Option<String> {
+        decode_hex_text(self.memo_type.as_deref()?)
+    }
+
+    /// Parse a transaction's `Memos` array (`[{"Memo": {...}}, ...]`)
+    pub fn parse_memos(memos: &serde_json::Value) -> Vec<Memo> {
+        memos
+            .as_array()
+            .map(|memos| {
+                memos
+                    .iter()
+                    .filter_map(|wrapper| serde_json::from_value(wrapper["Memo"].clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn decode_hex_text(hex_data: &str) -> Option<String> {
+    let bytes = hex::decode(hex_data).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// A currency as used in order books and path finding: XRP has no issuer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    pub currency: CurrencyCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Address>,
+}
+
+impl Issue {
+    pub fn xrp() -> Self {
+        Self {
+            currency: "XRP".to_string(),
+            issuer: None,
+        }
+    }
+
+    pub fn issued(currency: &str, issuer: &str) -> Self {
+        Self {
+            currency: currency.to_string(),
+            issuer: Some(issuer.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustLine {
     pub account: Address,
@@ -107,13 +289,22 @@ pub struct TrustLine {
     pub currency: CurrencyCode,
     pub limit: Amount,
     pub limit_peer: Amount,
+    #[serde(default)]
     pub quality_in: u32,
+    #[serde(default)]
     pub quality_out: u32,
+    // rippled omits the flags below when they are not set
+    #[serde(default)]
     pub no_ripple: bool,
+    #[serde(default)]
     pub no_ripple_peer: bool,
+    #[serde(default)]
     pub authorized: bool,
+    #[serde(default)]
     pub peer_authorized: bool,
+    #[serde(default)]
     pub freeze: bool,
+    #[serde(default)]
     pub freeze_peer: bool,
     pub obligation: Option<Amount>,
 }
@@ -127,7 +318,7 @@ impl Default for Transaction {
             amount: String::new(),
             currency: String::new(),
             issuer: None,
-            fee: "12".to_string(), // Default fee in drops
+            fee: crate::fee::DEFAULT_FEE_DROPS.to_string(),
             sequence: 0,
             flags: None,
             last_ledger_sequence: None,
@@ -137,6 +328,11 @@ impl Default for Transaction {
             paths: None,
             send_max: None,
             deliver_min: None,
+            network_id: None,
+            ticket_sequence: None,
+            ticket_count: None,
+            memos: None,
+            expiration: None,
         }
     }
 }
@@ -0,0 +1,335 @@
+use crate::amount::{CurrencyAmount, IouValue};
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::types::*;
+use crate::watcher::TF_PARTIAL_PAYMENT;
+use anyhow::Result;
+
+/// The transfer a payment is expected to have made
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedTransfer {
+    pub source: Address,
+    pub destination: Address,
+    /// "XRP" or the issued currency code
+    pub currency: CurrencyCode,
+    /// Required for issued currencies
+    pub issuer: Option<Address>,
+    /// Drops for XRP, the decimal value for issued currencies
+    pub amount: Amount,
+    pub destination_tag: Option<u32>,
+}
+
+impl ExpectedTransfer {
+    pub fn xrp(source: &str, destination: &str, drops: u64) -> Self {
+        Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            currency: "XRP".to_string(),
+            issuer: None,
+            amount: drops.to_string(),
+            destination_tag: None,
+        }
+    }
+
+    pub fn issued(source: &str, destination: &str, currency: &str, issuer: &str, value: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            currency: currency.to_string(),
+            issuer: Some(issuer.to_string()),
+            amount: value.to_string(),
+            destination_tag: None,
+        }
+    }
+
+    pub fn with_destination_tag(mut self, tag: u32) -> Self {
+        self.destination_tag = Some(tag);
+        self
+    }
+}
+
+/// Why a transaction does not prove the expected transfer
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationIssue {
+    /// Not in a validated ledger yet; the outcome may still change
+    NotValidated,
+    NotPayment(String),
+    /// The transaction failed, e.g. with a `tec` code
+    Failed(String),
+    WrongSender { expected: Address, actual: Address },
+    WrongDestination { expected: Address, actual: Address },
+    WrongDestinationTag { expected: Option<u32>, actual: Option<u32> },
+    WrongCurrency { expected: String, actual: String },
+    /// The ledger is too old to record what was delivered
+    DeliveredAmountUnavailable,
+    AmountMismatch { expected: Amount, delivered: Amount },
+}
+
+/// Outcome of checking a transaction against an `ExpectedTransfer`.
+///
+/// Amounts are always compared against `delivered_amount` from the metadata,
+/// never the `Amount` field, which a partial payment does not honor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferVerification {
+    pub hash: TransactionHash,
+    pub validated: bool,
+    pub transaction_result: Option<String>,
+    pub ledger_index: Option<u32>,
+    /// What actually arrived
+    pub delivered_amount: Option<CurrencyAmount>,
+    /// The `Amount` field of the transaction
+    pub requested_amount: Option<CurrencyAmount>,
+    /// Whether the sender set `tfPartialPayment`. Not an issue by itself as
+    /// long as the delivered amount matches.
+    pub partial_payment: bool,
+    pub issues: Vec<VerificationIssue>,
+}
+
+impl TransferVerification {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Check a transaction against the expected transfer
+    pub fn check(transaction: &TransactionMetadata, expected: &ExpectedTransfer) -> Result<Self> {
+        let tx = &transaction.tx_json;
+        let meta = transaction.meta.as_ref();
+
+        let mut verification = Self {
+            hash: transaction.hash.clone(),
+            validated: transaction.validated,
+            transaction_result: meta.map(|meta| meta.transaction_result.clone()),
+            ledger_index: transaction.ledger_index,
+            delivered_amount: meta.and_then(|meta| meta.delivered_amount.clone()),
+            requested_amount: tx.requested_amount().cloned(),
+            partial_payment: tx.flags & TF_PARTIAL_PAYMENT != 0,
+            issues: Vec::new(),
+        };
+
+        let issues = &mut verification.issues;
+
+        if !verification.validated {
+            issues.push(VerificationIssue::NotValidated);
+        }
+
+        if tx.transaction_type != "Payment" {
+            issues.push(VerificationIssue::NotPayment(tx.transaction_type.clone()));
+        }
+
+        match &verification.transaction_result {
+            Some(result) if result == "tesSUCCESS" => {}
+            Some(result) => issues.push(VerificationIssue::Failed(result.clone())),
+            None => issues.push(VerificationIssue::Failed("missing metadata".to_string())),
+        }
+
+        if tx.account != expected.source {
+            issues.push(VerificationIssue::WrongSender {
+                expected: expected.source.clone(),
+                actual: tx.account.clone(),
+            });
+        }
+
+        let destination = tx.destination.as_deref().unwrap_or("");
+        if destination != expected.destination {
+            issues.push(VerificationIssue::WrongDestination {
+                expected: expected.destination.clone(),
+                actual: destination.to_string(),
+            });
+        }
+
+        if expected.destination_tag.is_some() && tx.destination_tag != expected.destination_tag {
+            issues.push(VerificationIssue::WrongDestinationTag {
+                expected: expected.destination_tag,
+                actual: tx.destination_tag,
+            });
+        }
+
+        match &verification.delivered_amount {
+            None => issues.push(VerificationIssue::DeliveredAmountUnavailable),
+            Some(delivered) => {
+                if delivered.currency() != expected.currency || delivered.issuer() != expected.issuer.as_deref() {
+                    issues.push(VerificationIssue::WrongCurrency {
+                        expected: describe_currency(&expected.currency, expected.issuer.as_deref()),
+                        actual: describe_currency(delivered.currency(), delivered.issuer()),
+                    });
+                } else if !amounts_equal(delivered, &expected.amount)? {
+                    issues.push(VerificationIssue::AmountMismatch {
+                        expected: expected.amount.clone(),
+                        delivered: delivered.value().to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(verification)
+    }
+}
+
+fn describe_currency(currency: &str, issuer: Option<&str>) -> String {
+    match issuer {
+        Some(issuer) => format!("{}.{}", currency, issuer),
+        None => currency.to_string(),
+    }
+}
+
+/// Compare XRP as exact drops, and issued values numerically so that
+/// "100.5" and "100.50" match
+fn amounts_equal(delivered: &CurrencyAmount, expected: &str) -> Result<bool> {
+    match delivered {
+        CurrencyAmount::Xrp(drops) => {
+            let parse = |drops: &str| {
+                drops
+                    .parse::<u64>()
+                    .map_err(|_| XrplError::InvalidAmount(format!("Invalid XRP amount {}", drops)))
+            };
+            Ok(parse(drops)? == parse(expected)?)
+        }
+        CurrencyAmount::Issued(issued) => Ok(issued.value.parse::<IouValue>()? == expected.parse::<IouValue>()?),
+    }
+}
+
+impl XrplClient {
+    /// Fetch a transaction and check it against the expected transfer
+    pub async fn verify_transfer(&self, tx_hash: &str, expected: &ExpectedTransfer) -> Result<TransferVerification> {
+        let transaction = self.get_transaction(tx_hash).await?;
+        TransferVerification::check(&transaction, expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const ALICE: &str = "rAlice12345678901234567890123456";
+    const BOB: &str = "rBob1234567890123456789012345678";
+    const ISSUER: &str = "rIssuer123456789012345678901234";
+
+    fn usd(value: &str) -> Value {
+        json!({ "currency": "USD", "issuer": ISSUER, "value": value })
+    }
+
+    fn response(flags: u32, amount: Value, delivered: Value, result: &str) -> TransactionMetadata {
+        TransactionMetadata::from_response(&json!({
+            "hash": "ABC",
+            "ledger_index": 77,
+            "validated": true,
+            "TransactionType": "Payment",
+            "Account": ALICE,
+            "Fee": "12",
+            "Destination": BOB,
+            "DestinationTag": 9,
+            "Amount": amount,
+            "Flags": flags,
+            "meta": {
+                "TransactionIndex": 0,
+                "TransactionResult": result,
+                "AffectedNodes": [],
+                "delivered_amount": delivered
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_transfer() {
+        let expected = ExpectedTransfer::issued(ALICE, BOB, "USD", ISSUER, "100.5").with_destination_tag(9);
+        let verification = TransferVerification::check(
+            &response(0, usd("100.50"), usd("100.50"), "tesSUCCESS"),
+            &expected,
+        )
+        .unwrap();
+
+        assert!(verification.is_valid(), "{:?}", verification.issues);
+        assert_eq!(verification.ledger_index, Some(77));
+        assert!(!verification.partial_payment);
+    }
+
+    #[test]
+    fn test_partial_payment_exploit_detected() {
+        let expected = ExpectedTransfer::issued(ALICE, BOB, "USD", ISSUER, "100");
+        let verification = TransferVerification::check(
+            &response(TF_PARTIAL_PAYMENT, usd("100"), usd("0.0001"), "tesSUCCESS"),
+            &expected,
+        )
+        .unwrap();
+
+        assert!(!verification.is_valid());
+        assert!(verification.partial_payment);
+        assert_eq!(
+            verification.issues,
+            vec![VerificationIssue::AmountMismatch {
+                expected: "100".to_string(),
+                delivered: "0.0001".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_failed_and_unvalidated() {
+        let mut tx = response(0, json!("1000"), json!("0"), "tecPATH_DRY");
+        tx.validated = false;
+
+        let verification = TransferVerification::check(&tx, &ExpectedTransfer::xrp(ALICE, BOB, 1000)).unwrap();
+        assert!(verification.issues.contains(&VerificationIssue::NotValidated));
+        assert!(verification
+            .issues
+            .contains(&VerificationIssue::Failed("tecPATH_DRY".to_string())));
+    }
+
+    #[test]
+    fn test_wrong_currency_and_tag() {
+        let expected = ExpectedTransfer::xrp(ALICE, BOB, 1000).with_destination_tag(1);
+        let verification = TransferVerification::check(
+            &response(0, usd("1000"), usd("1000"), "tesSUCCESS"),
+            &expected,
+        )
+        .unwrap();
+
+        assert_eq!(
+            verification.issues,
+            vec![
+                VerificationIssue::WrongDestinationTag { expected: Some(1), actual: Some(9) },
+                VerificationIssue::WrongCurrency {
+                    expected: "XRP".to_string(),
+                    actual: format!("USD.{}", ISSUER),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_api_v2_shape() {
+        let v2 = TransactionMetadata::from_response(&json!({
+            "hash": "ABC",
+            "ledger_index": 5,
+            "validated": true,
+            "tx_json": { "TransactionType": "Payment", "Account": ALICE, "Fee": "12", "Destination": BOB, "DeliverMax": "25" },
+            "meta": { "TransactionIndex": 1, "TransactionResult": "tesSUCCESS", "delivered_amount": "25" }
+        }))
+        .unwrap();
+
+        let verification = TransferVerification::check(&v2, &ExpectedTransfer::xrp(ALICE, BOB, 25)).unwrap();
+        assert!(verification.is_valid(), "{:?}", verification.issues);
+        assert_eq!(verification.requested_amount, Some(CurrencyAmount::xrp_drops(25)));
+    }
+
+    #[test]
+    fn test_large_xrp_amounts_are_exact() {
+        // 17 significant digits: equal as decimals rounded to 16, not as drops
+        let expected = ExpectedTransfer::xrp(ALICE, BOB, 10_000_000_000_000_001);
+        let verification = TransferVerification::check(
+            &response(0, json!("10000000000000001"), json!("10000000000000000"), "tesSUCCESS"),
+            &expected,
+        )
+        .unwrap();
+
+        assert_eq!(
+            verification.issues,
+            vec![VerificationIssue::AmountMismatch {
+                expected: "10000000000000001".to_string(),
+                delivered: "10000000000000000".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,464 @@
+use crate::amount::CurrencyAmount;
+use crate::client::XrplClient;
+use crate::error::XrplError;
+use crate::types::*;
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub use crate::preflight::TF_PARTIAL_PAYMENT;
+
+/// A validated, successful payment to one of the watched addresses
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingPayment {
+    pub hash: TransactionHash,
+    pub ledger_index: u32,
+    pub close_time: Timestamp,
+    pub source: Address,
+    pub destination: Address,
+    pub source_tag: Option<u32>,
+    pub destination_tag: Option<u32>,
+    /// What actually arrived; always credit this, never `requested_amount`.
+    /// `None` when the server reports it as unavailable, as it does for
+    /// payments from before 2014: reconcile those from the ledger's balance
+    /// changes instead of crediting them.
+    pub delivered_amount: Option<CurrencyAmount>,
+    /// The `Amount` field of the transaction
+    pub requested_amount: CurrencyAmount,
+    /// Whether the sender set `tfPartialPayment`
+    pub partial_payment: bool,
+    pub memos: Vec<Memo>,
+}
+
+/// The payments found in one validated ledger
+#[derive(Debug, Clone)]
+pub struct LedgerPayments {
+    pub ledger_index: u32,
+    pub payments: Vec<IncomingPayment>,
+}
+
+/// Where a `PaymentWatcher` persists the last fully processed ledger
+pub trait CheckpointStore: Send + Sync {
+    fn load(&self) -> Result<Option<u32>>;
+    fn save(&self, ledger_index: u32) -> Result<()>;
+}
+
+/// Keeps the checkpoint in memory only; useful for tests
+#[derive(Default)]
+pub struct MemoryCheckpointStore {
+    ledger_index: Mutex<Option<u32>>,
+}
+
+impl MemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self) -> Result<Option<u32>> {
+        Ok(*self.ledger_index.lock().unwrap())
+    }
+
+    fn save(&self, ledger_index: u32) -> Result<()> {
+        *self.ledger_index.lock().unwrap() = Some(ledger_index);
+        Ok(())
+    }
+}
+
+/// Stores the checkpoint as a decimal ledger index in a file, replaced
+/// atomically and synced to disk
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self) -> Result<Option<u32>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|e| XrplError::Deserialization(format!("Invalid checkpoint: {}", e)).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, ledger_index: u32) -> Result<()> {
+        let temp_path = self.path.with_extension("tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(ledger_index.to_string().as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        // Make the rename itself survive a power loss
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(directory)?.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Follows one or more receiving addresses ledger by ledger and reports
+/// every validated, successful incoming payment.
+///
+/// Delivery is at-least-once: a ledger is only checkpointed once all of its
+/// payments have been handed out, so after a crash the last ledger may be
+/// reported again and callers should deduplicate by hash.
+pub struct PaymentWatcher<'a> {
+    client: &'a XrplClient,
+    addresses: HashSet<Address>,
+    store: Box<dyn CheckpointStore>,
+    start_ledger: Option<u32>,
+    poll_interval: Duration,
+    next_ledger: Option<u32>,
+}
+
+impl<'a> PaymentWatcher<'a> {
+    pub fn new(client: &'a XrplClient, addresses: &[&str], store: Box<dyn CheckpointStore>) -> Self {
+        Self {
+            client,
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            store,
+            start_ledger: None,
+            poll_interval: Duration::from_secs(4),
+            next_ledger: None,
+        }
+    }
+
+    /// First ledger to scan when there is no checkpoint yet; defaults to the
+    /// latest validated ledger
+    pub fn start_ledger(mut self, ledger_index: u32) -> Self {
+        self.start_ledger = Some(ledger_index);
+        self
+    }
+
+    /// How long to wait for a new validated ledger once caught up
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// The last ledger recorded as fully processed
+    pub fn checkpoint(&self) -> Result<Option<u32>> {
+        self.store.load()
+    }
+
+    /// Record that every payment up to and including `ledger_index` was handled
+    pub fn commit(&mut self, ledger_index: u32) -> Result<()> {
+        self.store.save(ledger_index)
+    }
+
+    async fn resolve_next_ledger(&mut self) -> Result<u32> {
+        if let Some(next) = self.next_ledger {
+            return Ok(next);
+        }
+
+        let next = match self.store.load()? {
+            Some(checkpoint) => checkpoint + 1,
+            None => match self.start_ledger {
+                Some(start) => start,
+                None => self.client.get_ledger_index().await?,
+            },
+        };
+
+        self.next_ledger = Some(next);
+        Ok(next)
+    }
+
+    /// Scan the next ledger if it is validated; `None` when caught up.
+    /// The ledger is not checkpointed until `commit` is called.
+    pub async fn next_ledger(&mut self) -> Result<Option<LedgerPayments>> {
+        let next = self.resolve_next_ledger().await?;
+
+        if next > self.client.get_ledger_index().await? {
+            return Ok(None);
+        }
+
+        let ledger = self.client.get_ledger_transactions(next).await?;
+        let payments = ledger
+            .transactions
+            .iter()
+            .filter_map(|tx| parse_incoming_payment(tx, &ledger, &self.addresses))
+            .collect();
+
+        self.next_ledger = Some(next + 1);
+
+        Ok(Some(LedgerPayments {
+            ledger_index: next,
+            payments,
+        }))
+    }
+
+    /// Scan every validated ledger not yet processed. Nothing is
+    /// checkpointed: call `commit` with each ledger's index once its payments
+    /// are credited.
+    pub async fn poll(&mut self) -> Result<Vec<LedgerPayments>> {
+        let mut found = Vec::new();
+
+        while let Some(ledger) = self.next_ledger().await? {
+            found.push(ledger);
+        }
+
+        Ok(found)
+    }
+
+    /// Follow the addresses forever. A ledger is checkpointed when the item
+    /// after its last payment is requested.
+    pub fn into_stream(self) -> impl Stream<Item = Result<IncomingPayment>> + 'a {
+        let state = (self, VecDeque::new(), None::<u32>);
+
+        stream::unfold(state, |(mut watcher, mut queue, mut uncommitted)| async move {
+            loop {
+                if let Some(payment) = queue.pop_front() {
+                    return Some((Ok(payment), (watcher, queue, uncommitted)));
+                }
+
+                if let Some(ledger_index) = uncommitted.take() {
+                    if let Err(e) = watcher.commit(ledger_index) {
+                        return Some((Err(e), (watcher, queue, uncommitted)));
+                    }
+                }
+
+                match watcher.next_ledger().await {
+                    Ok(Some(ledger)) => {
+                        queue.extend(ledger.payments);
+                        uncommitted = Some(ledger.ledger_index);
+                    }
+                    Ok(None) => tokio::time::sleep(watcher.poll_interval).await,
+                    Err(e) => {
+                        tokio::time::sleep(watcher.poll_interval).await;
+                        return Some((Err(e), (watcher, queue, uncommitted)));
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Extract an incoming payment from an expanded ledger transaction, if it is
+/// a successful payment to one of `addresses`. Handles both the API v1 shape
+/// (flattened fields, `metaData`) and v2 (`tx_json`, `meta`).
+fn parse_incoming_payment(
+    entry: &Value,
+    ledger: &LedgerTransactions,
+    addresses: &HashSet<Address>,
+) -> Option<IncomingPayment> {
+    let tx = if entry["tx_json"].is_object() {
+        &entry["tx_json"]
+    } else {
+        entry
+    };
+    let meta = if entry["meta"].is_object() {
+        &entry["meta"]
+    } else {
+        &entry["metaData"]
+    };
+
+    if tx["TransactionType"] != "Payment" || meta["TransactionResult"] != "tesSUCCESS" {
+        return None;
+    }
+
+    let destination = tx["Destination"].as_str()?;
+    if !addresses.contains(destination) {
+        return None;
+    }
+
+    let requested_amount = CurrencyAmount::from_json(&tx["Amount"])
+        .or_else(|| CurrencyAmount::from_json(&tx["DeliverMax"]))?;
+    let delivered_amount = CurrencyAmount::from_json(&meta["delivered_amount"])
+        .or_else(|| CurrencyAmount::from_json(&meta["DeliveredAmount"]))
+        .filter(|amount| amount.value() != "unavailable");
+
+    let flags = tx["Flags"].as_u64().unwrap_or(0) as u32;
+
+    Some(IncomingPayment {
+        hash: entry["hash"]
+            .as_str()
+            .or_else(|| tx["hash"].as_str())
+            .unwrap_or("")
+            .to_string(),
+        ledger_index: ledger.ledger_index,
+        close_time: ledger.close_time,
+        source: tx["Account"].as_str().unwrap_or("").to_string(),
+        destination: destination.to_string(),
+        source_tag: tx["SourceTag"].as_u64().map(|v| v as u32),
+        destination_tag: tx["DestinationTag"].as_u64().map(|v| v as u32),
+        delivered_amount,
+        requested_amount,
+        partial_payment: flags & TF_PARTIAL_PAYMENT != 0,
+        memos: Memo::parse_memos(&tx["Memos"]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::client;
+    use crate::transport::MockTransport;
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const DEPOSIT: &str = "rDeposit123456789012345678901234";
+
+    fn payment(hash: &str, destination: &str, result: &str, flags: u32, delivered: Value) -> Value {
+        json!({
+            "hash": hash,
+            "TransactionType": "Payment",
+            "Account": "rCustomer123456789012345678901234",
+            "Destination": destination,
+            "DestinationTag": 42,
+            "Amount": "5000000",
+            "Flags": flags,
+            "Memos": [{ "Memo": { "MemoData": hex::encode_upper("order 7") } }],
+            "metaData": { "TransactionResult": result, "delivered_amount": delivered }
+        })
+    }
+
+    fn ledger(index: u32, transactions: Vec<Value>) -> Value {
+        json!({
+            "validated": true,
+            "ledger_index": index,
+            "ledger": { "ledger_hash": "AB", "close_time": 1000 + index, "transactions": transactions }
+        })
+    }
+
+    fn mock_with_ledgers() -> Arc<MockTransport> {
+        let mock = Arc::new(MockTransport::new());
+        mock.on_params("ledger", json!({ "ledger_index": 10 }), ledger(10, vec![
+            payment("A", DEPOSIT, "tesSUCCESS", 0, json!("5000000")),
+            payment("B", "rSomeoneElse1234567890123456789", "tesSUCCESS", 0, json!("5000000")),
+            payment("C", DEPOSIT, "tecPATH_DRY", 0, json!("0")),
+        ]));
+        mock.on_params("ledger", json!({ "ledger_index": 11 }), ledger(11, vec![
+            payment("D", DEPOSIT, "tesSUCCESS", TF_PARTIAL_PAYMENT, json!("1")),
+        ]));
+        mock
+    }
+
+    #[test]
+    fn test_parse_incoming_payment() {
+        let addresses: HashSet<Address> = [DEPOSIT.to_string()].into_iter().collect();
+        let ledger = LedgerTransactions {
+            ledger_index: 5,
+            ledger_hash: String::new(),
+            close_time: 0,
+            transactions: Vec::new(),
+        };
+
+        let parsed = parse_incoming_payment(
+            &payment("A", DEPOSIT, "tesSUCCESS", TF_PARTIAL_PAYMENT, json!("10")),
+            &ledger,
+            &addresses,
+        )
+        .unwrap();
+        assert_eq!(parsed.delivered_amount, Some(CurrencyAmount::xrp_drops(10)));
+        assert_eq!(parsed.requested_amount, CurrencyAmount::xrp_drops(5000000));
+        assert!(parsed.partial_payment);
+        assert_eq!(parsed.destination_tag, Some(42));
+        assert_eq!(parsed.memos[0].data_text().unwrap(), "order 7");
+
+        assert!(parse_incoming_payment(
+            &payment("B", DEPOSIT, "tecUNFUNDED_PAYMENT", 0, json!("10")),
+            &ledger,
+            &addresses,
+        )
+        .is_none());
+
+        // Reported without an amount so the caller can reconcile it
+        let unavailable = parse_incoming_payment(
+            &payment("C", DEPOSIT, "tesSUCCESS", 0, json!("unavailable")),
+            &ledger,
+            &addresses,
+        )
+        .unwrap();
+        assert_eq!(unavailable.delivered_amount, None);
+        assert_eq!(unavailable.requested_amount, CurrencyAmount::xrp_drops(5000000));
+    }
+
+    #[test]
+    fn test_file_checkpoint_store() {
+        let path = std::env::temp_dir().join(format!("xrpl-checkpoint-{}.checkpoint", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = FileCheckpointStore::new(&path);
+
+        assert_eq!(store.load().unwrap(), None);
+        store.save(41).unwrap();
+        store.save(42).unwrap();
+        assert_eq!(store.load().unwrap(), Some(42));
+        assert!(!path.with_extension("tmp").exists());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_poll_resumes_from_checkpoint() {
+        let mock = mock_with_ledgers();
+        mock.on("ledger", json!({ "ledger_index": 11 }));
+        let client = client(&mock);
+
+        let store = MemoryCheckpointStore::new();
+        store.save(9).unwrap();
+
+        let mut watcher = PaymentWatcher::new(&client, &[DEPOSIT], Box::new(store));
+        let ledgers = watcher.poll().await.unwrap();
+
+        let hashes: Vec<&str> = ledgers
+            .iter()
+            .flat_map(|ledger| &ledger.payments)
+            .map(|p| p.hash.as_str())
+            .collect();
+        assert_eq!(hashes, vec!["A", "D"]);
+        assert_eq!(watcher.checkpoint().unwrap(), Some(9));
+
+        watcher.commit(ledgers.last().unwrap().ledger_index).unwrap();
+        assert_eq!(watcher.checkpoint().unwrap(), Some(11));
+
+        let scanned: Vec<Value> = mock
+            .requests_for("ledger")
+            .into_iter()
+            .filter(|params| params["transactions"] == true)
+            .map(|params| params["ledger_index"].clone())
+            .collect();
+        assert_eq!(scanned, vec![json!(10), json!(11)]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_commits_after_delivery() {
+        let mock = mock_with_ledgers();
+        mock.on("ledger", json!({ "ledger_index": 11 }));
+        let client = client(&mock);
+
+        let path = std::env::temp_dir().join(format!("xrpl-watcher-{}.checkpoint", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let watcher = PaymentWatcher::new(&client, &[DEPOSIT], Box::new(FileCheckpointStore::new(&path)))
+            .start_ledger(10)
+            .poll_interval(Duration::from_millis(1));
+        let mut stream = Box::pin(watcher.into_stream());
+
+        assert_eq!(stream.next().await.unwrap().unwrap().hash, "A");
+        assert_eq!(FileCheckpointStore::new(&path).load().unwrap(), None);
+
+        assert_eq!(stream.next().await.unwrap().unwrap().hash, "D");
+        assert_eq!(FileCheckpointStore::new(&path).load().unwrap(), Some(10));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
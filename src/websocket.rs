@@ -0,0 +1,396 @@
+use crate::error::XrplError;
+use crate::transport::Transport;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Number of unread stream messages kept per subscriber before the oldest are dropped
+const EVENT_BUFFER: usize = 1024;
+
+/// Timeouts and reconnect behaviour of a `WebSocketTransport`
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Time allowed for the server to answer a single request
+    pub request_timeout: Duration,
+    /// Interval between keepalive pings; a ping left unanswered for a full
+    /// interval is treated as a dead connection
+    pub ping_interval: Duration,
+    /// Delay before the first reconnect attempt
+    pub reconnect_delay: Duration,
+    /// Upper bound for the delay between reconnect attempts
+    pub max_reconnect_delay: Duration,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(30),
+            ping_interval: Duration::from_secs(20),
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+struct Shared {
+    url: String,
+    config: WebSocketConfig,
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    outgoing: Mutex<Option<mpsc::UnboundedSender<Message>>>,
+    connected: watch::Sender<bool>,
+    reconnects: AtomicU64,
+    events: broadcast::Sender<Value>,
+    subscriptions: Mutex<Vec<(u64, Value)>>,
+}
+
+impl Shared {
+    fn current_sender(&self) -> Option<mpsc::UnboundedSender<Message>> {
+        self.outgoing.lock().unwrap().clone()
+    }
+
+    /// Route a text frame from the server to the request waiting for it
+    fn dispatch(&self, text: &str) {
+        let message: Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(_) => return,
+        };
+
+        if let Some(id) = message["id"].as_u64() {
+            if let Some(waiter) = self.pending.lock().unwrap().remove(&id) {
+                let _ = waiter.send(message);
+            }
+            return;
+        }
+
+        if message["type"].is_string() {
+            let _ = self.events.send(message);
+        }
+    }
+
+    /// Replay every active subscription on a fresh connection. Responses are
+    /// not waited for; the streams simply continue once events flow again.
+    fn resubscribe(&self, sender: &mpsc::UnboundedSender<Message>) {
+        for (_, params) in self.subscriptions.lock().unwrap().iter() {
+            let mut message = params.clone();
+            if let Some(map) = message.as_object_mut() {
+                map.remove("snapshot");
+            }
+            message["id"] = json!(self.next_id.fetch_add(1, Ordering::SeqCst));
+            message["command"] = json!("subscribe");
+            let _ = sender.send(Message::Text(message.to_string()));
+        }
+    }
+
+    fn mark_connected(&self, sender: mpsc::UnboundedSender<Message>) {
+        *self.outgoing.lock().unwrap() = Some(sender);
+        self.connected.send_replace(true);
+    }
+
+    /// Forget the current connection and fail every request still waiting on it
+    fn mark_disconnected(&self) {
+        *self.outgoing.lock().unwrap() = None;
+        self.connected.send_replace(false);
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// JSON-RPC over a persistent WebSocket connection.
+///
+/// Concurrent requests share the connection and are matched to their
+/// responses by `id`. The connection is kept alive with pings and
+/// re-established automatically when it drops; requests in flight at that
+/// moment fail with a network error so the retry policy can resend them.
+pub struct WebSocketTransport {
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl WebSocketTransport {
+    pub async fn connect(url: &str) -> Result<Self> {
+        Self::connect_with_config(url, WebSocketConfig::default()).await
+    }
+
+    pub async fn connect_with_config(url: &str, config: WebSocketConfig) -> Result<Self> {
+        let (stream, _) = connect_async(url)
+            .await
+            .map_err(|e| XrplError::Network(e.to_string()))?;
+
+        let (connected, _) = watch::channel(false);
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let shared = Arc::new(Shared {
+            url: url.to_string(),
+            config,
+            next_id: AtomicU64::new(1),
+            pending: Mutex::new(HashMap::new()),
+            outgoing: Mutex::new(None),
+            connected,
+            reconnects: AtomicU64::new(0),
+            events,
+            subscriptions: Mutex::new(Vec::new()),
+        });
+
+        let (sink, source) = stream.split();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        shared.mark_connected(sender);
+
+        let task = tokio::spawn(run(shared.clone(), sink, source, receiver));
+
+        Ok(Self { shared, task })
+    }
+
+    pub fn is_connected(&self) -> bool {
+        *self.shared.connected.borrow()
+    }
+
+    /// Number of times the connection has been re-established
+    pub fn reconnect_count(&self) -> u64 {
+        self.shared.reconnects.load(Ordering::SeqCst)
+    }
+
+    /// Send a command in WebSocket format (`{"command": ..., ...}`) and return
+    /// the raw response message
+    pub async fn request(&self, command: &str, params: Value) -> Result<Value> {
+        let id = self.shared.next_id.fetch_add(1, Ordering::SeqCst);
+
+        let mut message = match params {
+            Value::Object(map) => Value::Object(map),
+            _ => json!({}),
+        };
+        message["id"] = json!(id);
+        message["command"] = json!(command);
+
+        let (waiter, response) = oneshot::channel();
+        self.shared.pending.lock().unwrap().insert(id, waiter);
+
+        let result = tokio::time::timeout(self.shared.config.request_timeout, async {
+            let sender = self.wait_for_connection().await;
+            sender
+                .send(Message::Text(message.to_string()))
+                .map_err(|_| XrplError::Network("WebSocket connection closed".to_string()))?;
+
+            response
+                .await
+                .map_err(|_| XrplError::Network("WebSocket connection closed".to_string()))
+        })
+        .await;
+
+        match result {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(e)) => {
+                self.shared.pending.lock().unwrap().remove(&id);
+                Err(e.into())
+            }
+            Err(_) => {
+                self.shared.pending.lock().unwrap().remove(&id);
+                Err(XrplError::Network(format!("WebSocket request {} timed out", command)).into())
+            }
+        }
+    }
+
+    /// Receiver for every server-initiated message (those without an `id`)
+    pub fn events(&self) -> broadcast::Receiver<Value> {
+        self.shared.events.subscribe()
+    }
+
+    /// Remember `subscribe` params so they are replayed after a reconnect
+    pub(crate) fn register_subscription(&self, params: Value) -> u64 {
+        let key = self.shared.next_id.fetch_add(1, Ordering::SeqCst);
+        self.shared.subscriptions.lock().unwrap().push((key, params));
+        key
+    }
+
+    /// Forget a subscription, returning its params and whether another
+    /// subscription still needs the same stream
+    pub(crate) fn remove_subscription(&self, key: u64) -> Option<(Value, bool)> {
+        let mut subscriptions = self.shared.subscriptions.lock().unwrap();
+        let position = subscriptions.iter().position(|(k, _)| *k == key)?;
+        let (_, params) = subscriptions.remove(position);
+        let still_needed = subscriptions.iter().any(|(_, other)| *other == params);
+        Some((params, still_needed))
+    }
+
+    pub fn subscription_count(&self) -> usize {
+        self.shared.subscriptions.lock().unwrap().len()
+    }
+
+    async fn wait_for_connection(&self) -> mpsc::UnboundedSender<Message> {
+        let mut connected = self.shared.connected.subscribe();
+
+        loop {
+            if let Some(sender) = self.shared.current_sender() {
+                return sender;
+            }
+
+            let _ = connected.wait_for(|connected| *connected).await;
+        }
+    }
+}
+
+impl Drop for WebSocketTransport {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.shared.mark_disconnected();
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn send(&self, request: &Value) -> Result<Value> {
+        let method = request["method"]
+            .as_str()
+            .ok_or_else(|| XrplError::InvalidTransaction("Request has no method".to_string()))?;
+        let params = request["params"][0].clone();
+
+        let response = self.request(method, params).await?;
+        Ok(to_rpc_response(response))
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.shared.url
+    }
+}
+
+/// Reshape a WebSocket response into the `{"result": ...}` form returned over JSON-RPC
+fn to_rpc_response(mut response: Value) -> Value {
+    if response["status"] == "error" {
+        if let Some(map) = response.as_object_mut() {
+            map.remove("id");
+            map.remove("type");
+        }
+        return json!({ "result": response });
+    }
+
+    let mut result = response["result"].take();
+    if result.is_object() {
+        result["status"] = json!("success");
+    }
+
+    json!({ "result": result })
+}
+
+/// Drive the connection: serve it until it drops, then reconnect with backoff
+async fn run(
+    shared: Arc<Shared>,
+    sink: SplitSink<WsStream, Message>,
+    source: SplitStream<WsStream>,
+    receiver: mpsc::UnboundedReceiver<Message>,
+) {
+    serve_connection(&shared, sink, source, receiver).await;
+
+    loop {
+        shared.mark_disconnected();
+
+        let mut delay = shared.config.reconnect_delay;
+        let stream = loop {
+            tokio::time::sleep(delay).await;
+            match connect_async(shared.url.as_str()).await {
+                Ok((stream, _)) => break stream,
+                Err(_) => delay = (delay * 2).min(shared.config.max_reconnect_delay),
+            }
+        };
+
+        let (sink, source) = stream.split();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        shared.resubscribe(&sender);
+        shared.mark_connected(sender);
+        shared.reconnects.fetch_add(1, Ordering::SeqCst);
+
+        serve_connection(&shared, sink, source, receiver).await;
+    }
+}
+
+async fn serve_connection(
+    shared: &Shared,
+    mut sink: SplitSink<WsStream, Message>,
+    mut source: SplitStream<WsStream>,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+) {
+    let mut ping = tokio::time::interval(shared.config.ping_interval);
+    ping.tick().await;
+    let mut awaiting_pong = false;
+
+    loop {
+        tokio::select! {
+            outgoing = receiver.recv() => {
+                let message = match outgoing {
+                    Some(message) => message,
+                    None => break,
+                };
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+            incoming = source.next() => {
+                match incoming {
+                    Some(Ok(Message::Text(text))) => shared.dispatch(&text),
+                    Some(Ok(Message::Ping(payload))) => {
+                        if sink.send(Message::Pong(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Pong(_))) => awaiting_pong = false,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+            _ = ping.tick() => {
+                if awaiting_pong {
+                    break;
+                }
+                if sink.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                awaiting_pong = true;
+            }
+        }
+    }
+
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success_response_conversion() {
+        let response = to_rpc_response(json!({
+            "id": 1,
+            "status": "success",
+            "type": "response",
+            "result": { "ledger_index": 10 }
+        }));
+
+        assert_eq!(response["result"]["ledger_index"], 10);
+        assert_eq!(response["result"]["status"], "success");
+    }
+
+    #[test]
+    fn test_error_response_conversion() {
+        let response = to_rpc_response(json!({
+            "id": 2,
+            "status": "error",
+            "type": "response",
+            "error": "actNotFound",
+            "error_message": "Account not found."
+        }));
+
+        assert_eq!(response["result"]["error"], "actNotFound");
+        assert_eq!(response["result"]["status"], "error");
+        assert!(response["result"].get("id").is_none());
+    }
+}
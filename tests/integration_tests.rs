@@ -1,8 +1,19 @@
 use ripple_xrpl::{
-    TransactionBuilder, TransactionSigner, TransactionValidator,
-    XrplError
+    AccountTxRequest, ApiVersion, MockReply, MockTransport, Network, RetryPolicy, SignedTransaction, TransactionBuilder,
+    TransactionSigner, TransactionValidator, VerificationIssue, XrplClient, XrplError, XrplLib
 };
+use serde_json::json;
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn mock_client(mock: &Arc<MockTransport>) -> XrplClient {
+    XrplClient::builder(Network::testnet())
+        .transport(mock.clone())
+        .retry_policy(RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5)))
+        .build()
+        .unwrap()
+}
 
 #[tokio::test]
 async fn test_complete_workflow() -> Result<(), Box<dyn Error>> {
@@ -51,7 +62,7 @@ async fn test_complete_workflow() -> Result<(), Box<dyn Error>> {
     
     // In a real test with valid credentials, you would test the actual signing
     // For now, we'll just verify the signer exists
-    assert!(std::mem::size_of_val(&signer) == 0); // Unit struct has size 0
+    assert!(signer.network().is_none());
     
     Ok(())
 }
@@ -156,15 +167,181 @@ fn test_transaction_hash_validation() {
 
 #[tokio::test]
 async fn test_client_operations() -> Result<(), Box<dyn Error>> {
-    // Test client operations (these will fail with testnet but test the flow)
-    
-    // Note: XrplLib client field is private, so we can't test it directly
-    // In a real implementation, you would test the public methods instead
-    println!("✓ XRPL library instance created successfully");
+    // Test client operations against canned rippled responses
+    
+    let mock = Arc::new(MockTransport::new());
+    mock.on("ledger", json!({ "ledger_index": 5000, "validated": true }));
+    mock.on("account_info", json!({
+        "account_data": {
+            "Account": "rTestAccount123456789012345678901234",
+            "Balance": "25000000",
+            "Flags": 0,
+            "LedgerEntryType": "AccountRoot",
+            "OwnerCount": 2,
+            "PreviousTxnID": "0E4A3C6D1D1C0F5D33B3A7D5B0C81E3E2E1F21F6A5F3C3C4E3F2A1B0C9D8E7F6",
+            "PreviousTxnLgrSeq": 4990,
+            "Sequence": 42,
+            "index": "13F1A95D7AAB7108D5CE7EEAF504B2894B8C674E6D68499076441C4837282BF8"
+        },
+        "ledger_index": 5000,
+        "validated": true
+    }));
+
+    let client = mock_client(&mock);
+    assert_eq!(client.get_ledger_index().await?, 5000);
+    assert_eq!(client.get_account_sequence("rTestAccount123456789012345678901234").await?, 42);
+    assert_eq!(client.get_account_balance("rTestAccount123456789012345678901234").await?, "25000000");
+
+    let info = client.get_account_info("rTestAccount123456789012345678901234").await?;
+    assert_eq!(info.ledger_index, Some(5000));
+    assert!(info.validated);
+    assert_eq!(info.account_data.owner_count, 2);
+    assert_eq!(info.account_data.previous_txn_lgr_seq, Some(4990));
+
+    let account_requests = mock.requests_for("account_info");
+    assert_eq!(account_requests.len(), 3);
+    assert_eq!(account_requests[0]["account"], "rTestAccount123456789012345678901234");
+    assert_eq!(account_requests[0]["ledger_index"], "validated");
     
     Ok(())
 }
 
+#[tokio::test]
+async fn test_client_api_error() {
+    let mock = Arc::new(MockTransport::new());
+    mock.on_error("account_info", "actNotFound");
+
+    let client = mock_client(&mock);
+    let error = client.get_account_info("rMissing123456789012345678901234").await.unwrap_err();
+    assert_eq!(error.to_string(), "XRPL API error: actNotFound");
+    assert_eq!(mock.request_count("account_info"), 1);
+}
+
+#[tokio::test]
+async fn test_client_retries_rate_limiting() -> Result<(), Box<dyn Error>> {
+    let mock = Arc::new(MockTransport::new());
+    mock.reply("ledger", MockReply::HttpStatus(429));
+    mock.on_error("ledger", "slowDown");
+    mock.on("ledger", json!({ "ledger_index": 7 }));
+
+    let client = mock_client(&mock);
+    assert_eq!(client.get_ledger_index().await?, 7);
+    assert_eq!(mock.request_count("ledger"), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_fails_over_to_next_endpoint() -> Result<(), Box<dyn Error>> {
+    let primary = Arc::new(MockTransport::named("primary"));
+    primary.on_error("ledger", "noCurrent");
+    let secondary = Arc::new(MockTransport::named("secondary"));
+    secondary.on("ledger", json!({ "ledger_index": 9 }));
+
+    let client = XrplClient::builder(Network::testnet())
+        .transport(primary.clone())
+        .transport(secondary.clone())
+        .build()?;
+
+    assert_eq!(client.get_ledger_index().await?, 9);
+    assert_eq!(client.get_ledger_index().await?, 9);
+    assert_eq!(primary.request_count("ledger"), 1);
+    assert_eq!(secondary.request_count("ledger"), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_does_not_fail_over_blindly() -> Result<(), Box<dyn Error>> {
+    let primary = Arc::new(MockTransport::named("primary"));
+    primary.reply("submit", MockReply::NetworkError("connection reset".to_string()));
+    let secondary = Arc::new(MockTransport::named("secondary"));
+    secondary.on("tx", json!({
+        "hash": "ABC",
+        "validated": true,
+        "ledger_index": 100,
+        "tx_json": { "TransactionType": "Payment", "Account": "rSender", "Fee": "12", "Sequence": 1 },
+        "meta": { "TransactionResult": "tesSUCCESS" }
+    }));
+
+    let client = XrplClient::builder(Network::testnet())
+        .transport(primary.clone())
+        .transport(secondary.clone())
+        .build()?;
+    let signed_tx = SignedTransaction {
+        tx_blob: "1200002280000000".to_string(),
+        tx_json: Default::default(),
+    };
+
+    let result = client.submit_transaction(&signed_tx).await?;
+    assert_eq!(result.engine_result, "tesSUCCESS");
+    assert_eq!(primary.request_count("submit"), 1);
+    assert_eq!(secondary.request_count("submit"), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_is_not_resent_when_hash_is_known() -> Result<(), Box<dyn Error>> {
+    let mock = Arc::new(MockTransport::new());
+    mock.reply("submit", MockReply::NetworkError("connection reset".to_string()));
+    mock.on("tx", json!({
+        "hash": "ABC",
+        "validated": true,
+        "ledger_index": 100,
+        "tx_json": { "TransactionType": "Payment", "Account": "rSender", "Fee": "12", "Sequence": 1 },
+        "meta": { "TransactionResult": "tesSUCCESS" }
+    }));
+
+    let client = mock_client(&mock);
+    let signed_tx = SignedTransaction {
+        tx_blob: "1200002280000000".to_string(),
+        tx_json: Default::default(),
+    };
+
+    let result = client.submit_transaction(&signed_tx).await?;
+    assert_eq!(result.engine_result, "tesSUCCESS");
+    assert!(result.validated);
+    assert_eq!(mock.request_count("submit"), 1);
+
+    let tx_lookup = &mock.requests_for("tx")[0];
+    assert_eq!(tx_lookup["transaction"], TransactionSigner::transaction_hash(&signed_tx.tx_blob)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_trust_lines_follow_markers() -> Result<(), Box<dyn Error>> {
+    let line = |currency: &str| json!({
+        "account": "rIssuer123456789012345678901234",
+        "balance": "10",
+        "currency": currency,
+        "limit": "100",
+        "limit_peer": "0",
+        "quality_in": 0,
+        "quality_out": 0,
+        "no_ripple": true,
+        "no_ripple_peer": false,
+        "authorized": false,
+        "peer_authorized": false,
+        "freeze": false,
+        "freeze_peer": false
+    });
+
+    let mock = Arc::new(MockTransport::new());
+    mock.on("account_lines", json!({ "ledger_index": 42, "lines": [line("USD"), line("EUR")], "marker": "page2" }));
+    mock.on_params("account_lines", json!({ "marker": "page2" }), json!({ "ledger_index": 42, "lines": [line("JPY")] }));
+
+    let client = mock_client(&mock);
+    let lines = client.get_trust_lines("rHolder123456789012345678901234").await?;
+
+    let currencies: Vec<&str> = lines.iter().map(|line| line.currency.as_str()).collect();
+    assert_eq!(currencies, vec!["USD", "EUR", "JPY"]);
+    assert_eq!(mock.requests_for("account_lines")[1]["ledger_index"], 42);
+
+    Ok(())
+}
+
 #[test]
 fn test_error_types() {
     // Test that our custom error types work correctly
@@ -187,24 +364,258 @@ fn test_transaction_builder_network_config() {
     // Test network configuration in transaction builder
     
     let testnet_builder = TransactionBuilder::new(true);
-    assert_eq!(testnet_builder.get_network_id(), 1024);
+    assert_eq!(testnet_builder.get_network_id(), Network::TESTNET_ID);
     
     let mainnet_builder = TransactionBuilder::new(false);
-    assert_eq!(mainnet_builder.get_network_id(), 1049344);
+    assert_eq!(mainnet_builder.get_network_id(), Network::MAINNET_ID);
+
+    let devnet_builder = TransactionBuilder::with_network(Network::devnet());
+    assert_eq!(devnet_builder.get_network_id(), Network::DEVNET_ID);
 }
 
 #[test]
 fn test_signer_network_config() {
     // Test network configuration in transaction signer
     
-    let testnet_signer = TransactionSigner::with_network(true);
-    let mainnet_signer = TransactionSigner::with_network(false);
+    let testnet_signer = TransactionSigner::with_network(Network::testnet());
+    let mainnet_signer = TransactionSigner::with_network(Network::mainnet());
     
-    // Note: testnet field is private, so we can't test it directly
-    // In a real implementation, you would test the public methods instead
     println!("✓ Transaction signers created successfully for both networks");
     
-    // Verify the signers exist
-    assert!(std::mem::size_of_val(&testnet_signer) == 0); // Unit struct has size 0
-    assert!(std::mem::size_of_val(&mainnet_signer) == 0); // Unit struct has size 0
+    assert!(testnet_signer.network().unwrap().is_testnet());
+    assert!(mainnet_signer.network().unwrap().is_mainnet());
+}
+
+#[test]
+fn test_client_builder_network_config() {
+    // Test a client configured for a private standalone node
+
+    let client = XrplClient::builder(Network::standalone("http://127.0.0.1:5005"))
+        .header("Authorization", "Bearer token")
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.base_url(), "http://127.0.0.1:5005");
+    assert_eq!(client.network().name, "standalone");
+}
+
+#[tokio::test]
+async fn test_verify_token_transfer_uses_delivered_amount() -> Result<(), Box<dyn Error>> {
+    let mock = Arc::new(MockTransport::new());
+    mock.on("tx", json!({
+        "hash": "ABC",
+        "ledger_index": 100,
+        "validated": true,
+        "TransactionType": "Payment",
+        "Account": "rUser1Address123456789012345678901234",
+        "Fee": "12",
+        "Destination": "rUser2Address123456789012345678901234",
+        "Amount": { "currency": "USD", "issuer": "rIssuerAddress123456789012345678901234", "value": "100.50" },
+        "Flags": 0x0002_0000,
+        "meta": {
+            "TransactionIndex": 0,
+            "TransactionResult": "tesSUCCESS",
+            "AffectedNodes": [],
+            "delivered_amount": { "currency": "USD", "issuer": "rIssuerAddress123456789012345678901234", "value": "0.01" }
+        }
+    }));
+
+    let xrpl = XrplLib::with_client(mock_client(&mock));
+    let verification = xrpl
+        .verify_token_transfer(
+            "rUser1Address123456789012345678901234",
+            "rUser2Address123456789012345678901234",
+            "rIssuerAddress123456789012345678901234",
+            "USD",
+            "100.50",
+            "ABC",
+        )
+        .await?;
+
+    assert!(!verification.is_valid());
+    assert!(verification.partial_payment);
+    assert!(matches!(verification.issues[0], VerificationIssue::AmountMismatch { .. }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_transaction_api_v1_and_v2() -> Result<(), Box<dyn Error>> {
+    let meta = json!({
+        "TransactionIndex": 2,
+        "TransactionResult": "tesSUCCESS",
+        "AffectedNodes": [],
+        "delivered_amount": "1000000"
+    });
+
+    let mock = Arc::new(MockTransport::new());
+    mock.on_params("tx", json!({ "transaction": "V1" }), json!({
+        "Account": "rSender123456789012345678901234",
+        "Amount": "1000000",
+        "DeliverMax": "1000000",
+        "Destination": "rReceiver1234567890123456789012",
+        "Fee": "12",
+        "Flags": 0,
+        "Sequence": 7,
+        "TransactionType": "Payment",
+        "ctid": "C000000100020000",
+        "date": 750000000,
+        "hash": "V1",
+        "inLedger": 100,
+        "ledger_index": 100,
+        "meta": meta,
+        "validated": true
+    }));
+    mock.on_params("tx", json!({ "transaction": "V2" }), json!({
+        "close_time_iso": "2023-10-06T12:00:00Z",
+        "hash": "V2",
+        "ledger_hash": "LH",
+        "ledger_index": 100,
+        "meta": meta,
+        "tx_json": {
+            "Account": "rSender123456789012345678901234",
+            "DeliverMax": "1000000",
+            "Destination": "rReceiver1234567890123456789012",
+            "Fee": "12",
+            "Sequence": 7,
+            "TransactionType": "Payment",
+            "date": 750000000
+        },
+        "validated": true
+    }));
+
+    let client = mock_client(&mock);
+    for hash in ["V1", "V2"] {
+        let tx = client.get_transaction(hash).await?;
+        assert_eq!(tx.hash, hash);
+        assert_eq!(tx.ledger_index, Some(100));
+        assert_eq!(tx.date, Some(750000000));
+        assert!(tx.validated);
+        assert_eq!(tx.tx_json.transaction_type, "Payment");
+        assert_eq!(tx.tx_json.sequence, 7);
+        assert_eq!(tx.tx_json.requested_amount().and_then(|amount| amount.drops()), Some(1000000));
+        assert_eq!(tx.transaction_result(), Some("tesSUCCESS"));
+    }
+
+    let v1 = client.get_transaction("V1").await?;
+    assert_eq!(v1.ctid.as_deref(), Some("C000000100020000"));
+    assert!(!v1.tx_json.other.contains_key("meta"));
+    assert!(!v1.tx_json.other.contains_key("inLedger"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_version_is_sent_and_responses_match() -> Result<(), Box<dyn Error>> {
+    let v1_entry = json!({
+        "meta": { "TransactionIndex": 0, "TransactionResult": "tesSUCCESS", "AffectedNodes": [] },
+        "tx": {
+            "Account": "rSender123456789012345678901234",
+            "Amount": "500",
+            "Destination": "rReceiver1234567890123456789012",
+            "Fee": "12",
+            "TransactionType": "Payment",
+            "date": 750000000,
+            "hash": "H1",
+            "inLedger": 80,
+            "ledger_index": 80
+        },
+        "validated": true
+    });
+    let v2_entry = json!({
+        "close_time_iso": "2023-10-06T12:00:00Z",
+        "date": 750000000,
+        "hash": "H1",
+        "ledger_index": 80,
+        "meta": { "TransactionIndex": 0, "TransactionResult": "tesSUCCESS", "AffectedNodes": [] },
+        "tx_json": {
+            "Account": "rSender123456789012345678901234",
+            "DeliverMax": "500",
+            "Destination": "rReceiver1234567890123456789012",
+            "Fee": "12",
+            "TransactionType": "Payment"
+        },
+        "validated": true
+    });
+
+    let mut transactions = Vec::new();
+    for (version, entry) in [(ApiVersion::V1, v1_entry), (ApiVersion::V2, v2_entry)] {
+        let mock = Arc::new(MockTransport::new());
+        mock.on("account_tx", json!({
+            "account": "rReceiver1234567890123456789012",
+            "ledger_index_min": 1,
+            "ledger_index_max": 100,
+            "transactions": [entry]
+        }));
+        let client = XrplClient::builder(Network::testnet())
+            .transport(mock.clone())
+            .api_version(version)
+            .build()?;
+
+        let page = client
+            .account_tx(&AccountTxRequest::new("rReceiver1234567890123456789012"), None)
+            .await?;
+        assert_eq!(mock.requests_for("account_tx")[0]["api_version"], version.number());
+
+        let tx = page.transactions.into_iter().next().unwrap();
+        assert_eq!(tx.hash, "H1");
+        assert_eq!(tx.ledger_index, 80);
+        assert_eq!(tx.date, Some(750000000));
+        transactions.push(tx.tx);
+    }
+
+    assert_eq!(transactions[0], transactions[1]);
+    assert_eq!(transactions[0]["Amount"], "500");
+    assert_eq!(transactions[0]["DeliverMax"], "500");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_send_token_uses_distinct_sequences() -> Result<(), Box<dyn Error>> {
+    let mock = Arc::new(MockTransport::new());
+    mock.on("account_info", json!({
+        "account_data": { "Account": "rSender", "Balance": "50000000", "Sequence": 30 },
+        "ledger_current_index": 101,
+        "validated": false
+    }));
+    mock.on("submit", json!({
+        "engine_result": "tesSUCCESS",
+        "engine_result_code": 0,
+        "engine_result_message": "The transaction was applied.",
+        "tx_blob": "",
+        "tx_json": { "hash": "ABC" }
+    }));
+    let xrpl = XrplLib::with_client(mock_client(&mock));
+
+    let sends = (0..25).map(|_| {
+        xrpl.send_token(
+            "sEdTM1uX8pu2do5XvTnutH6HsouMaM2sEdTM1uX8pu2",
+            "rReceiver1234567890123456789012",
+            "rIssuer123456789012345678901234",
+            "USD",
+            "1",
+        )
+    });
+    for result in futures::future::join_all(sends).await {
+        assert_eq!(result?.engine_result, "tesSUCCESS");
+    }
+
+    // The unsigned fields of a blob are NUL separated, the sequence is the seventh
+    let mut sequences: Vec<u32> = mock
+        .requests_for("submit")
+        .iter()
+        .map(|params| {
+            let blob = hex::decode(params["tx_blob"].as_str().unwrap()).unwrap();
+            let field = blob.split(|byte| *byte == 0).nth(6).unwrap();
+            String::from_utf8_lossy(field).parse().unwrap()
+        })
+        .collect();
+    sequences.sort();
+
+    assert_eq!(sequences, (30..55).collect::<Vec<_>>());
+    assert_eq!(mock.request_count("account_info"), 1);
+
+    Ok(())
 }
@@ -0,0 +1,240 @@
+use futures::{SinkExt, StreamExt};
+use ripple_xrpl::{
+    Issue, Network, OrderBook, Transport, WebSocketConfig, WebSocketTransport, XrplClient,
+    XrplClientBuilder,
+};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Answer a WebSocket command the way rippled would, followed by any stream
+/// messages the command triggers on connection number `connection`
+fn respond(request: &Value, connection: usize) -> Vec<Value> {
+    let id = request["id"].clone();
+    let response = match request["command"].as_str() {
+        Some("ledger") => json!({
+            "id": id,
+            "status": "success",
+            "type": "response",
+            "result": { "ledger_index": 321, "validated": true }
+        }),
+        Some("ping") => json!({
+            "id": id,
+            "status": "success",
+            "type": "response",
+            "result": { "echo": request["echo"] }
+        }),
+        Some("subscribe") | Some("unsubscribe") => json!({
+            "id": id,
+            "status": "success",
+            "type": "response",
+            "result": if request["books"][0]["snapshot"] == true {
+                json!({ "offers": [{ "Account": "rMaker", "TakerGets": "1000000" }] })
+            } else {
+                json!({})
+            }
+        }),
+        _ => json!({
+            "id": id,
+            "status": "error",
+            "type": "response",
+            "error": "unknownCmd",
+            "request": request
+        }),
+    };
+
+    let mut messages = vec![response];
+    if request["command"] == "subscribe" {
+        if request["streams"][0] == "ledger" {
+            messages.push(json!({
+                "type": "ledgerClosed",
+                "ledger_index": 100 + connection,
+                "ledger_hash": "F".repeat(64),
+                "ledger_time": 750000000,
+                "fee_base": 10,
+                "reserve_base": 1000000,
+                "reserve_inc": 200000,
+                "txn_count": 3
+            }));
+        }
+        if let Some(account) = request["accounts"][0].as_str() {
+            for (source, destination) in [("rSomeoneElse", "rUnrelated"), ("rSender", account)] {
+                messages.push(json!({
+                    "type": "transaction",
+                    "validated": true,
+                    "engine_result": "tesSUCCESS",
+                    "engine_result_code": 0,
+                    "ledger_index": 100 + connection,
+                    "transaction": {
+                        "TransactionType": "Payment",
+                        "Account": source,
+                        "Destination": destination,
+                        "Amount": "1000"
+                    },
+                    "meta": { "TransactionResult": "tesSUCCESS", "AffectedNodes": [] }
+                }));
+            }
+        }
+    }
+
+    messages
+}
+
+/// Start a stand-in rippled WebSocket server.
+///
+/// Responses to `ping` commands are delayed by the `delay_ms` field so tests
+/// can check that out-of-order responses reach the right caller. When
+/// `drop_after` is set, every connection is closed after that many requests.
+async fn start_server(drop_after: Option<usize>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let connection = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::spawn(async move {
+                let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let (sink, mut source) = ws.split();
+                let sink = Arc::new(tokio::sync::Mutex::new(sink));
+                let mut served = 0;
+
+                while let Some(Ok(message)) = source.next().await {
+                    let text = match message {
+                        Message::Text(text) => text,
+                        _ => continue,
+                    };
+
+                    let request: Value = serde_json::from_str(&text).unwrap();
+                    let delay = request["delay_ms"].as_u64().unwrap_or(0);
+                    let responder = sink.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_millis(delay)).await;
+                        let mut sink = responder.lock().await;
+                        for message in respond(&request, connection) {
+                            let _ = sink.send(Message::Text(message.to_string())).await;
+                        }
+                    });
+
+                    served += 1;
+                    if drop_after == Some(served) {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        let _ = sink.lock().await.close().await;
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (url, connections)
+}
+
+#[tokio::test]
+async fn test_websocket_typed_methods() -> Result<(), Box<dyn Error>> {
+    let (url, _) = start_server(None).await;
+    let client = XrplClient::connect_websocket(Network::standalone(&url), &url).await?;
+
+    assert_eq!(client.get_ledger_index().await?, 321);
+
+    let error = client.get_account_info("rMissing123456789012345678901234").await.unwrap_err();
+    assert_eq!(error.to_string(), "XRPL API error: unknownCmd");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_correlates_concurrent_requests() -> Result<(), Box<dyn Error>> {
+    let (url, _) = start_server(None).await;
+    let transport = WebSocketTransport::connect(&url).await?;
+
+    let slow = transport.request("ping", json!({ "echo": "slow", "delay_ms": 200 }));
+    let fast = transport.request("ping", json!({ "echo": "fast", "delay_ms": 0 }));
+    let (slow, fast) = tokio::join!(slow, fast);
+
+    assert_eq!(slow?["result"]["echo"], "slow");
+    assert_eq!(fast?["result"]["echo"], "fast");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_reconnects() -> Result<(), Box<dyn Error>> {
+    let (url, connections) = start_server(Some(1)).await;
+    let config = WebSocketConfig {
+        reconnect_delay: Duration::from_millis(20),
+        ..WebSocketConfig::default()
+    };
+    let transport = Arc::new(WebSocketTransport::connect_with_config(&url, config).await?);
+    let client = XrplClientBuilder::new(Network::standalone(&url))
+        .transport(transport.clone())
+        .build()?;
+
+    assert_eq!(client.get_ledger_index().await?, 321);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(client.get_ledger_index().await?, 321);
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+    assert_eq!(transport.reconnect_count(), 1);
+    assert!(transport.is_connected());
+    assert_eq!(transport.endpoint(), url);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ledger_subscription_survives_reconnect() -> Result<(), Box<dyn Error>> {
+    let (url, _) = start_server(Some(1)).await;
+    let config = WebSocketConfig {
+        reconnect_delay: Duration::from_millis(20),
+        ..WebSocketConfig::default()
+    };
+    let transport = WebSocketTransport::connect_with_config(&url, config).await?;
+
+    let mut ledgers = transport.subscribe_ledger().await?;
+    let first = tokio::time::timeout(Duration::from_secs(5), ledgers.next()).await?.unwrap();
+    assert_eq!(first.ledger_index, 101);
+    assert_eq!(first.reserve_base, Some(1000000));
+
+    // The server drops the connection; the subscription is replayed on the new one
+    let second = tokio::time::timeout(Duration::from_secs(5), ledgers.next()).await?.unwrap();
+    assert_eq!(second.ledger_index, 102);
+    assert!(transport.reconnect_count() >= 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_subscription_filters_other_accounts() -> Result<(), Box<dyn Error>> {
+    let (url, _) = start_server(None).await;
+    let transport = WebSocketTransport::connect(&url).await?;
+
+    let mut payments = transport.subscribe_accounts(&["rWatched"]).await?;
+    let event = tokio::time::timeout(Duration::from_secs(5), payments.next()).await?.unwrap();
+    assert_eq!(event.transaction["Destination"], "rWatched");
+    assert_eq!(event.engine_result, "tesSUCCESS");
+    assert_eq!(transport.subscription_count(), 1);
+
+    transport.unsubscribe(payments).await?;
+    assert_eq!(transport.subscription_count(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_book_subscription_returns_snapshot() -> Result<(), Box<dyn Error>> {
+    let (url, _) = start_server(None).await;
+    let transport = WebSocketTransport::connect(&url).await?;
+
+    let book = OrderBook::new(Issue::xrp(), Issue::issued("USD", "rIssuer123456789012345678901234"));
+    let (snapshot, _stream) = transport.subscribe_book(&book).await?;
+    assert_eq!(snapshot.offers.len(), 1);
+    assert_eq!(snapshot.offers[0]["Account"], "rMaker");
+
+    Ok(())
+}